@@ -14,6 +14,8 @@
 //!   groups. `leb128`'s *signed* encoding is true signed-LEB128 (sign-extension based), a
 //!   different wire format from zigzag, so it is deliberately excluded from the `i64` groups
 //!   to avoid presenting an apples-to-oranges number as if it were an equivalent comparison.
+//!   It is instead compared against `varing::sleb128`, which implements the same format, in
+//!   the `sleb128-i64` groups.
 //! - `unsigned-varint` (`unsigned_varint::encode` / `unsigned_varint::decode`): unsigned-only,
 //!   but it does implement `u16`/`u32`/`u64`/`u128`, so it appears in every unsigned group.
 //! - `prost` (`prost::encoding::encode_varint` / `prost::encoding::decode_varint`): this *is*
@@ -41,6 +43,42 @@
 //! (`"varing"`, `"integer-encoding"`, `"unsigned-varint"`, `"leb128"`, `"prost"`), so
 //! `cargo bench` output and the criterion HTML report show the libraries side by side for
 //! the same case.
+//!
+//! The `u64` and `i64` decode groups also include `"varing-fast-path"`, decoding the
+//! same bytes through the `Varint` trait, which loads eight bytes at once and finds the end
+//! of the varint with bit tricks whenever that many are available (in the single value
+//! groups, the encoding is followed by padding to make them available). Its length no
+//! longer comes from a branch per byte, but each value then waits for the previous one to
+//! be decoded before its position is known. The mixed sweeps repeat the same 1000 values on
+//! every iteration, so the branch predictor of a modern CPU learns their lengths and the
+//! byte-by-byte decoder is not penalised for its branches as it would be on real,
+//! unrepeated data; raise `MIXED_LEN` to e.g. `100_000` to compare them on a sequence too
+//! long to be learned.
+//!
+//! Median times measured on one core of an x86_64 Xeon VM, built without `+bmi2` (`cargo
+//! bench --bench varint -- --warm-up-time 1 --measurement-time 3 'decode/(u64|i64)/'`),
+//! in ns per value and µs per mixed sweep:
+//!
+//! | group | `varing` | `varing-fast-path` |
+//! |-------|---------:|-------------------:|
+//! | `decode/u64/small` | 3.05 | 2.09 |
+//! | `decode/u64/mid` | 7.30 | 7.51 |
+//! | `decode/u64/max` | 11.43 | 8.40 |
+//! | `decode/u64/mixed` | 6.42 | 5.58 |
+//! | `decode/i64/small` | 3.72 | 3.22 |
+//! | `decode/i64/mid` | 5.84 | 6.16 |
+//! | `decode/i64/max` | 11.99 | 8.17 |
+//! | `decode/i64/mixed` | 9.07 | 6.97 |
+//!
+//! The fast path wins on the longest encodings and the sweeps. On `u32` it was slower for
+//! values of one to three bytes and on the mixed sweep (2.93 against 4.81 µs), where the
+//! byte loop has little to branch on, so `u32` and the narrower integers keep decoding
+//! with the const functions.
+//!
+//! The `u32-sequence` groups instead compare `varing`'s own sequence formats on the mixed
+//! `u32` sweep: one LEB128 varint after another (`encode_u32_sequence_to`), Group Varint
+//! (`"varing-group-varint"`) and Stream VByte (`"varing-stream-vbyte"`), whose decoder uses
+//! SSSE3/AVX2 when the CPU supports them.
 
 use criterion::{Criterion, criterion_group, criterion_main};
 use integer_encoding::VarInt;
@@ -61,6 +99,8 @@ const U32_MAX_LEN: usize = <u32 as varing::Varint>::MAX_ENCODED_LEN.get();
 const U64_MAX_LEN: usize = <u64 as varing::Varint>::MAX_ENCODED_LEN.get();
 const U128_MAX_LEN: usize = <u128 as varing::Varint>::MAX_ENCODED_LEN.get();
 const I64_MAX_LEN: usize = <i64 as varing::Varint>::MAX_ENCODED_LEN.get();
+const SLEB128_I64_MAX_LEN: usize =
+  <varing::sleb128::Sleb128<i64> as varing::Varint>::MAX_ENCODED_LEN.get();
 
 /// A single SplitMix64 step: deterministic, fast, decent-quality bit mixing.
 ///
@@ -625,6 +665,17 @@ fn bench_decode_u64(c: &mut Criterion) {
       })
     });
 
+    // The same encoding followed by padding, so that `Varint::decode` can load a whole
+    // word and take its unrolled fast path instead of the byte-by-byte const decoder.
+    let mut padded = [0u8; 16];
+    padded[..encoded.len()].copy_from_slice(encoded);
+    group.bench_function("varing-fast-path", |b| {
+      b.iter(|| {
+        let (_, v) = <u64 as varing::Varint>::decode(black_box(&padded)).unwrap();
+        black_box(v);
+      })
+    });
+
     group.bench_function("integer-encoding", |b| {
       b.iter(|| {
         let (v, _) = <u64 as VarInt>::decode_var(black_box(encoded)).unwrap();
@@ -672,6 +723,14 @@ fn bench_decode_u64(c: &mut Criterion) {
       })
     })
   });
+  group.bench_function("varing-fast-path", |b| {
+    b.iter(|| {
+      sweep_decode(&varing_stream, MIXED_LEN, |buf| {
+        let (n, v) = <u64 as varing::Varint>::decode(buf).unwrap();
+        (n.get(), v)
+      })
+    })
+  });
 
   let ie_stream = build_stream(&values, U64_MAX_LEN, |v, buf| v.encode_var(buf));
   group.bench_function("integer-encoding", |b| {
@@ -920,6 +979,17 @@ fn bench_decode_i64(c: &mut Criterion) {
       })
     });
 
+    // The same encoding followed by padding, so that `Varint::decode` can load a whole
+    // word and take its unrolled fast path instead of the byte-by-byte const decoder.
+    let mut padded = [0u8; 16];
+    padded[..encoded.len()].copy_from_slice(encoded);
+    group.bench_function("varing-fast-path", |b| {
+      b.iter(|| {
+        let (_, v) = <i64 as varing::Varint>::decode(black_box(&padded)).unwrap();
+        black_box(v);
+      })
+    });
+
     group.bench_function("integer-encoding", |b| {
       b.iter(|| {
         let (v, _) = <i64 as VarInt>::decode_var(black_box(encoded)).unwrap();
@@ -944,6 +1014,14 @@ fn bench_decode_i64(c: &mut Criterion) {
       })
     })
   });
+  group.bench_function("varing-fast-path", |b| {
+    b.iter(|| {
+      sweep_decode(&varing_stream, MIXED_LEN, |buf| {
+        let (n, v) = <i64 as varing::Varint>::decode(buf).unwrap();
+        (n.get(), v)
+      })
+    })
+  });
 
   let ie_stream = build_stream(&values, I64_MAX_LEN, |v, buf| v.encode_var(buf));
   group.bench_function("integer-encoding", |b| {
@@ -958,6 +1036,200 @@ fn bench_decode_i64(c: &mut Criterion) {
   group.finish();
 }
 
+// ---------------------------------------------------------------------------------------
+// i64, signed LEB128
+// ---------------------------------------------------------------------------------------
+
+fn bench_encode_sleb128_i64(c: &mut Criterion) {
+  const CASES: [(&str, i64); 3] = [("small", -5), ("mid", -1_000_000_000), ("max", i64::MAX)];
+
+  for (label, value) in CASES {
+    let mut group = c.benchmark_group(format!("encode/sleb128-i64/{label}"));
+
+    let mut buf = [0u8; SLEB128_I64_MAX_LEN];
+    group.bench_function("varing", |b| {
+      b.iter(|| {
+        let n = varing::sleb128::encode_i64_sleb128_to(black_box(value), &mut buf).unwrap();
+        black_box(&buf[..n.get()]);
+      })
+    });
+
+    let mut buf = [0u8; SLEB128_I64_MAX_LEN];
+    group.bench_function("leb128", |b| {
+      b.iter(|| {
+        let mut w: &mut [u8] = &mut buf;
+        let n = leb128::write::signed(&mut w, black_box(value)).unwrap();
+        black_box(&buf[..n]);
+      })
+    });
+
+    group.finish();
+  }
+
+  let values = mixed_i64();
+  let mut group = c.benchmark_group("encode/sleb128-i64/mixed");
+
+  let mut scratch = vec![0u8; MIXED_LEN * SLEB128_I64_MAX_LEN];
+  group.bench_function("varing", |b| {
+    b.iter(|| {
+      let n = sweep_encode(&values, &mut scratch, |v, buf| {
+        varing::sleb128::encode_i64_sleb128_to(v, buf)
+          .unwrap()
+          .get()
+      });
+      black_box(&scratch[..n]);
+    })
+  });
+
+  let mut scratch = vec![0u8; MIXED_LEN * SLEB128_I64_MAX_LEN];
+  group.bench_function("leb128", |b| {
+    b.iter(|| {
+      let n = sweep_encode(&values, &mut scratch, |v, mut buf: &mut [u8]| {
+        leb128::write::signed(&mut buf, v).unwrap()
+      });
+      black_box(&scratch[..n]);
+    })
+  });
+
+  group.finish();
+}
+
+fn bench_decode_sleb128_i64(c: &mut Criterion) {
+  const CASES: [(&str, i64); 3] = [("small", -5), ("mid", -1_000_000_000), ("max", i64::MAX)];
+
+  for (label, value) in CASES {
+    let mut group = c.benchmark_group(format!("decode/sleb128-i64/{label}"));
+
+    let mut buf = [0u8; SLEB128_I64_MAX_LEN];
+    let n = varing::sleb128::encode_i64_sleb128_to(value, &mut buf).unwrap();
+    let encoded = &buf[..n.get()];
+
+    group.bench_function("varing", |b| {
+      b.iter(|| {
+        let (_, v) = varing::sleb128::decode_i64_sleb128(black_box(encoded)).unwrap();
+        black_box(v);
+      })
+    });
+
+    group.bench_function("leb128", |b| {
+      b.iter(|| {
+        let mut r: &[u8] = black_box(encoded);
+        let v = leb128::read::signed(&mut r).unwrap();
+        black_box(v);
+      })
+    });
+
+    group.finish();
+  }
+
+  let values = mixed_i64();
+  let mut group = c.benchmark_group("decode/sleb128-i64/mixed");
+
+  // Both libraries produce the same bytes, so a single stream serves both.
+  let stream = build_stream(&values, SLEB128_I64_MAX_LEN, |v, buf| {
+    varing::sleb128::encode_i64_sleb128_to(v, buf)
+      .unwrap()
+      .get()
+  });
+  group.bench_function("varing", |b| {
+    b.iter(|| {
+      sweep_decode(&stream, MIXED_LEN, |buf| {
+        let (n, v) = varing::sleb128::decode_i64_sleb128(buf).unwrap();
+        (n.get(), v)
+      })
+    })
+  });
+
+  group.bench_function("leb128", |b| {
+    b.iter(|| {
+      sweep_decode(&stream, MIXED_LEN, |buf| {
+        let mut r = buf;
+        let before = r.len();
+        let v = leb128::read::signed(&mut r).unwrap();
+        (before - r.len(), v)
+      })
+    })
+  });
+
+  group.finish();
+}
+
+// ---------------------------------------------------------------------------------------
+// u32 sequences
+// ---------------------------------------------------------------------------------------
+
+fn bench_encode_u32_sequence(c: &mut Criterion) {
+  let values = mixed_u32();
+  let mut group = c.benchmark_group("encode/u32-sequence/mixed");
+
+  let mut buf = vec![0u8; MIXED_LEN * U32_MAX_LEN];
+  group.bench_function("varing", |b| {
+    b.iter(|| {
+      let n = varing::encode_u32_sequence_to(black_box(&values), &mut buf).unwrap();
+      black_box(&buf[..n]);
+    })
+  });
+
+  let mut buf = vec![0u8; varing::group_varint::encoded_u32_group_sequence_len(&values)];
+  group.bench_function("varing-group-varint", |b| {
+    b.iter(|| {
+      let n =
+        varing::group_varint::encode_u32_group_sequence_to(black_box(&values), &mut buf).unwrap();
+      black_box(&buf[..n]);
+    })
+  });
+
+  let mut buf = vec![0u8; varing::stream_vbyte::encoded_u32_stream_vbyte_len(&values)];
+  group.bench_function("varing-stream-vbyte", |b| {
+    b.iter(|| {
+      let n = varing::stream_vbyte::encode_u32_stream_vbyte(black_box(&values), &mut buf).unwrap();
+      black_box(&buf[..n]);
+    })
+  });
+
+  group.finish();
+}
+
+fn bench_decode_u32_sequence(c: &mut Criterion) {
+  let values = mixed_u32();
+  let mut group = c.benchmark_group("decode/u32-sequence/mixed");
+  let mut out = vec![0u32; values.len()];
+
+  let mut stream = vec![0u8; varing::encoded_u32_sequence_len(&values)];
+  varing::encode_u32_sequence_to(&values, &mut stream).unwrap();
+  group.bench_function("varing", |b| {
+    b.iter(|| {
+      let mut pos = 0;
+      for value in out.iter_mut() {
+        let (n, v) = varing::decode_u32_varint(black_box(&stream[pos..])).unwrap();
+        *value = v;
+        pos += n.get();
+      }
+      black_box(&out);
+    })
+  });
+
+  let mut stream = vec![0u8; varing::group_varint::encoded_u32_group_sequence_len(&values)];
+  varing::group_varint::encode_u32_group_sequence_to(&values, &mut stream).unwrap();
+  group.bench_function("varing-group-varint", |b| {
+    b.iter(|| {
+      varing::group_varint::decode_u32_group_sequence(black_box(&stream), &mut out).unwrap();
+      black_box(&out);
+    })
+  });
+
+  let mut stream = vec![0u8; varing::stream_vbyte::encoded_u32_stream_vbyte_len(&values)];
+  varing::stream_vbyte::encode_u32_stream_vbyte(&values, &mut stream).unwrap();
+  group.bench_function("varing-stream-vbyte", |b| {
+    b.iter(|| {
+      varing::stream_vbyte::decode_u32_stream_vbyte(black_box(&stream), &mut out).unwrap();
+      black_box(&out);
+    })
+  });
+
+  group.finish();
+}
+
 criterion_group!(
   benches,
   bench_encode_u16,
@@ -970,5 +1242,9 @@ criterion_group!(
   bench_decode_u128,
   bench_encode_i64,
   bench_decode_i64,
+  bench_encode_sleb128_i64,
+  bench_decode_sleb128_i64,
+  bench_encode_u32_sequence,
+  bench_decode_u32_sequence,
 );
 criterion_main!(benches);
@@ -0,0 +1,4 @@
+#[cfg(feature = "bytes_1")]
+mod v1;
+#[cfg(feature = "bytes_1")]
+pub(crate) use v1::*;
@@ -0,0 +1,70 @@
+use core::num::NonZeroUsize;
+
+use bytes_1::{Buf, BufMut};
+
+use crate::{
+  DecodeError, EncodeError, Varint,
+  scratch::{PartialRead, Scratch},
+};
+
+#[inline]
+pub(crate) fn encode_to_buf<V, B>(value: &V, buf: &mut B) -> Result<NonZeroUsize, EncodeError>
+where
+  V: Varint + ?Sized,
+  B: BufMut + ?Sized,
+{
+  let encoded_len = value.encoded_len();
+  let available = buf.remaining_mut();
+  if encoded_len.get() > available {
+    return Err(EncodeError::insufficient_space(encoded_len, available));
+  }
+
+  let scratch = Scratch::encode(value)?;
+  buf.put_slice(&scratch);
+  // Safety: `Varint::encode` never writes zero bytes.
+  Ok(unsafe { NonZeroUsize::new_unchecked(scratch.len()) })
+}
+
+#[inline]
+pub(crate) fn decode_from_buf<V, B>(buf: &mut B) -> Result<(NonZeroUsize, V), DecodeError>
+where
+  V: Varint,
+  B: Buf + ?Sized,
+{
+  let chunk = buf.chunk();
+  let chunk_len = chunk.len();
+  match V::decode(chunk) {
+    Ok((read, value)) => {
+      if read.get() > chunk_len {
+        return Err(crate::invalid_consumed_length());
+      }
+      buf.advance(read.get());
+      return Ok((read, value));
+    }
+    // The value may continue in the next chunk.
+    Err(DecodeError::InsufficientData(_)) if buf.remaining() > chunk_len => {}
+    Err(e) => return Err(e),
+  }
+
+  // `Buf` cannot look past its current chunk without advancing, so gather the value
+  // chunk by chunk, copying no more than `required_len` reports the value still needs to
+  // never consume a byte that belongs to the next value.
+  let mut partial = PartialRead::<V>::new();
+  loop {
+    if !buf.has_remaining() {
+      return Err(DecodeError::insufficient_data(partial.filled()));
+    }
+
+    let chunk = buf.chunk();
+    let unfilled = partial.unfilled();
+    let n = unfilled.len().min(chunk.len());
+    unfilled[..n].copy_from_slice(&chunk[..n]);
+    buf.advance(n);
+    if let Some(res) = partial.advance(n) {
+      return res;
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests;
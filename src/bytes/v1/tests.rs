@@ -0,0 +1,141 @@
+use super::*;
+
+use core::time::Duration;
+use std::vec::Vec;
+
+use bytes_1::{Bytes, BytesMut, buf::Chain};
+use quickcheck_macros::quickcheck;
+
+/// Splits `data` into a chain whose first chunk ends at `split`.
+fn chained(data: &[u8], split: usize) -> Chain<Bytes, Bytes> {
+  let split = split.min(data.len());
+  Bytes::copy_from_slice(&data[..split]).chain(Bytes::copy_from_slice(&data[split..]))
+}
+
+macro_rules! roundtrip {
+  ($($ty:ty), +$(,)?) => {
+    $(
+      paste::paste! {
+        #[quickcheck]
+        fn [< buf_roundtrip_ $ty:snake >](values: Vec<$ty>, split: usize) -> bool {
+          let mut buf = BytesMut::new();
+          for value in &values {
+            match value.encode_to_buf(&mut buf) {
+              Ok(written) if written == value.encoded_len() => {}
+              _ => return false,
+            }
+          }
+
+          let split = if buf.is_empty() { 0 } else { split % buf.len() };
+          let mut chain = chained(&buf, split);
+          for value in &values {
+            match <$ty>::decode_from_buf(&mut chain) {
+              Ok((read, decoded)) if decoded == *value && read == value.encoded_len() => {}
+              _ => return false,
+            }
+          }
+          !chain.has_remaining()
+        }
+      }
+    )*
+  };
+}
+
+roundtrip!(
+  u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, bool, char, Duration
+);
+
+#[test]
+fn decode_does_not_consume_next_value() {
+  // 300 = [0xAC, 0x02], followed by 1 = [0x01], split inside the first value.
+  let mut chain = chained(&[0xAC, 0x02, 0x01], 1);
+  assert_eq!(u32::decode_from_buf(&mut chain).unwrap().1, 300);
+  assert_eq!(chain.remaining(), 1);
+  assert_eq!(u32::decode_from_buf(&mut chain).unwrap().1, 1);
+}
+
+/// A `Buf` which counts the calls to its `advance`.
+struct Counted<B> {
+  inner: B,
+  advances: usize,
+}
+
+impl<B: Buf> Buf for Counted<B> {
+  fn remaining(&self) -> usize {
+    self.inner.remaining()
+  }
+
+  fn chunk(&self) -> &[u8] {
+    self.inner.chunk()
+  }
+
+  fn advance(&mut self, cnt: usize) {
+    self.advances += 1;
+    self.inner.advance(cnt);
+  }
+}
+
+#[test]
+fn decode_copies_whole_chunks() {
+  let value: [u8; 64] = core::array::from_fn(|i| i as u8);
+  let mut buf = BytesMut::new();
+  value.encode_to_buf(&mut buf).unwrap();
+  buf.extend_from_slice(&[0x01]);
+
+  let mut chain = Counted {
+    inner: chained(&buf, 1),
+    advances: 0,
+  };
+  assert_eq!(<[u8; 64]>::decode_from_buf(&mut chain).unwrap().1, value);
+  assert_eq!(chain.advances, 2);
+  assert_eq!(chain.remaining(), 1);
+}
+
+#[test]
+fn decode_insufficient_data_contiguous_is_untouched() {
+  let mut buf: &[u8] = &[0x80, 0x80];
+  assert!(matches!(
+    u64::decode_from_buf(&mut buf),
+    Err(DecodeError::InsufficientData(_))
+  ));
+  assert_eq!(buf.len(), 2);
+}
+
+#[test]
+fn decode_insufficient_data_chained() {
+  let mut chain = chained(&[0x80, 0x80, 0x80], 1);
+  assert!(matches!(
+    u64::decode_from_buf(&mut chain),
+    Err(DecodeError::InsufficientData(_))
+  ));
+}
+
+#[test]
+fn decode_overflow_chained() {
+  let mut chain = chained(&[0x80; 16], 2);
+  assert!(matches!(
+    u32::decode_from_buf(&mut chain),
+    Err(DecodeError::Overflow)
+  ));
+}
+
+#[test]
+fn encode_insufficient_space() {
+  let mut storage = [0u8; 1];
+  let mut buf = &mut storage[..];
+  match 300u32.encode_to_buf(&mut buf) {
+    Err(EncodeError::InsufficientSpace(e)) => {
+      assert_eq!(e.requested().get(), 2);
+      assert_eq!(e.available(), 1);
+    }
+    other => panic!("expected InsufficientSpace, got {other:?}"),
+  }
+}
+
+#[test]
+fn encode_to_limited_buf() {
+  let mut buf = Vec::new().limit(2);
+  300u32.encode_to_buf(&mut buf).unwrap();
+  assert!(1u8.encode_to_buf(&mut buf).is_err());
+  assert_eq!(buf.into_inner(), [0xAC, 0x02]);
+}
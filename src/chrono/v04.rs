@@ -1,5 +1,6 @@
 use crate::{
   ConstDecodeError, ConstEncodeError, DecodeError, EncodeError, NON_ZERO_USIZE_ONE, Varint,
+  delta::Delta,
   time_utils::{self, DurationBuffer},
 };
 
@@ -255,5 +256,47 @@ impl Varint for DateTime<Utc> {
   }
 }
 
+/// Delta encoded as nanoseconds since the Unix epoch.
+///
+/// Leap seconds (a nanosecond of `1_000_000_000` or more) have no representation, as they
+/// would share the nanoseconds of the following second, so the delta sequence encoders
+/// return an error for a sequence that contains one.
+impl Delta for NaiveDateTime {
+  type Repr = u128;
+
+  #[inline]
+  fn to_delta_repr(&self) -> Option<Self::Repr> {
+    let utc = self.and_utc();
+    let nanos = utc.timestamp_subsec_nanos();
+    if nanos >= 1_000_000_000 {
+      return None;
+    }
+    Some((utc.timestamp() as i128 * 1_000_000_000 + nanos as i128) as u128)
+  }
+
+  #[inline]
+  fn from_delta_repr(repr: Self::Repr) -> Option<Self> {
+    let nanos = repr as i128;
+    let secs = i64::try_from(nanos.div_euclid(1_000_000_000)).ok()?;
+    DateTime::from_timestamp(secs, nanos.rem_euclid(1_000_000_000) as u32).map(|dt| dt.naive_utc())
+  }
+}
+
+/// Delta encoded as nanoseconds since the Unix epoch, with the same leap second
+/// restriction as [`NaiveDateTime`].
+impl Delta for DateTime<Utc> {
+  type Repr = u128;
+
+  #[inline]
+  fn to_delta_repr(&self) -> Option<Self::Repr> {
+    self.naive_utc().to_delta_repr()
+  }
+
+  #[inline]
+  fn from_delta_repr(repr: Self::Repr) -> Option<Self> {
+    NaiveDateTime::from_delta_repr(repr).map(|naive| naive.and_utc())
+  }
+}
+
 #[cfg(test)]
 mod tests;
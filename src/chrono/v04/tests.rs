@@ -292,3 +292,71 @@ fn decode_date_round_trip() {
   assert_eq!(decoded, value);
   assert_eq!(read, n);
 }
+
+#[cfg(feature = "std")]
+#[quickcheck_macros::quickcheck]
+fn fuzzy_delta_datetime(values: Vec<TimeUtc>) -> bool {
+  use crate::delta::{decode_delta_of_delta_sequence, encode_delta_of_delta_sequence};
+
+  let values = values
+    .into_iter()
+    .filter_map(IntoChrono::into_chrono)
+    .collect::<Vec<DateTime<Utc>>>();
+  let mut buf = vec![0; values.len() * <u128>::MAX_ENCODED_LEN.get()];
+  let written = encode_delta_of_delta_sequence(values.iter(), &mut buf).unwrap();
+  decode_delta_of_delta_sequence::<DateTime<Utc>, Vec<_>>(&buf[..written]).unwrap()
+    == (written, values)
+}
+
+#[test]
+fn delta_rejects_leap_seconds() {
+  let leap = NaiveDate::from_ymd_opt(2016, 12, 31)
+    .unwrap()
+    .and_hms_nano_opt(23, 59, 59, 1_500_000_000)
+    .unwrap();
+  assert_eq!(leap.to_delta_repr(), None);
+
+  let second = leap.with_nanosecond(0).unwrap();
+  assert_eq!(
+    NaiveDateTime::from_delta_repr(second.to_delta_repr().unwrap()),
+    Some(second)
+  );
+}
+
+#[test]
+fn delta_sequence_rejects_leap_seconds() {
+  use crate::delta::{
+    encode_delta_of_delta_sequence, encode_delta_sequence, encoded_delta_of_delta_sequence_len,
+    encoded_delta_sequence_len,
+  };
+
+  let before = NaiveDate::from_ymd_opt(2016, 12, 31)
+    .unwrap()
+    .and_hms_opt(23, 59, 59)
+    .unwrap();
+  let leap = before.with_nanosecond(1_000_000_000).unwrap();
+  let values = [before.and_utc(), leap.and_utc()];
+  let expected = EncodeError::other("value cannot be delta encoded");
+
+  let mut buf = [0u8; 64];
+  assert_eq!(
+    encoded_delta_sequence_len(values.iter()),
+    Err(expected.clone())
+  );
+  assert_eq!(
+    encode_delta_sequence(values.iter(), &mut buf),
+    Err(expected.clone())
+  );
+  assert_eq!(
+    encoded_delta_of_delta_sequence_len([before, leap].iter()),
+    Err(expected.clone())
+  );
+  assert_eq!(
+    encode_delta_of_delta_sequence([before, leap].iter(), &mut buf),
+    Err(expected)
+  );
+
+  // The leap second itself still has its `Varint` encoding.
+  let written = leap.encode(&mut buf).unwrap();
+  assert_eq!(NaiveDateTime::decode(&buf).unwrap(), (written, leap));
+}
@@ -0,0 +1,527 @@
+//! [`Varint`] for the `alloc` collections, as a `u64` varint count followed by the elements.
+//!
+//! `Vec<T>`, `Box<[T]>` and `VecDeque<T>` are encoded with [`encode_sequence_with_len`],
+//! `BTreeMap<K, V>` and `HashMap<K, V, S>` with [`encode_map_with_len`], both with a
+//! [`LengthPrefix::Count`] prefix. `String` is the byte length followed by its UTF-8 bytes.
+//!
+//! The count is checked against a limit before anything is allocated, so a hostile prefix
+//! cannot force a huge allocation. The [`Varint`] implementations of the collections use
+//! [`DEFAULT_MAX_DECODED_LEN`], [`Limited`] sets the limit in the type, and
+//! [`decode_collection`] takes it as an argument.
+//!
+//! [`encode_sequence_with_len`]: crate::encode_sequence_with_len
+//! [`encode_map_with_len`]: crate::encode_map_with_len
+//! [`DEFAULT_MAX_DECODED_LEN`]: crate::collections::DEFAULT_MAX_DECODED_LEN
+//! [`Limited`]: crate::collections::Limited
+//! [`decode_collection`]: crate::collections::decode_collection
+
+use core::num::NonZeroUsize;
+
+use std::{
+  boxed::Box,
+  collections::{BTreeMap, VecDeque},
+  string::String,
+  vec::Vec,
+};
+
+#[cfg(feature = "std")]
+use std::{
+  collections::HashMap,
+  hash::{BuildHasher, Hash},
+};
+
+use super::{
+  DecodeError, EncodeError, LengthPrefix, NON_ZERO_USIZE_ONE, Varint, decode_length_prefix,
+  derive::required_elements_len, encode_map_with_len, encode_sequence_with_len,
+  encoded_map_with_len_len, encoded_sequence_with_len_len, encoded_u64_varint_len,
+  map_decoder_with_len, sequence_decoder_with_len,
+};
+
+/// The default maximum number of elements, entries or bytes of a `String`, that the
+/// [`Varint`] implementations of the collections decode, 64 Ki.
+pub const DEFAULT_MAX_DECODED_LEN: usize = 64 * 1024;
+
+/// A collection with a length-prefixed [`Varint`] encoding.
+///
+/// This trait is sealed, it is implemented for `Vec<T>`, `Box<[T]>`, `VecDeque<T>`, `String`,
+/// `BTreeMap<K, V>` and `HashMap<K, V, S>`.
+pub trait Collection: sealed::Sealed {}
+
+mod sealed {
+  use super::{DecodeError, EncodeError, NonZeroUsize};
+
+  pub trait Sealed: Sized {
+    /// The minimum encoded length of an element.
+    const MIN_ELEMENT_ENCODED_LEN: NonZeroUsize;
+    /// The maximum encoded length of an element.
+    const MAX_ELEMENT_ENCODED_LEN: NonZeroUsize;
+
+    /// The number of elements, entries or bytes recorded in the prefix.
+    fn count(&self) -> usize;
+
+    /// The encoded length, the prefix included.
+    fn encoded_collection_len(&self) -> usize;
+
+    /// Encodes the prefix and the elements.
+    fn encode_collection(&self, buf: &mut [u8]) -> Result<usize, EncodeError>;
+
+    /// Decodes the prefix and the elements, `capacity` is a bound of the number of
+    /// elements that fit in `buf`.
+    fn decode_collection(buf: &[u8], capacity: usize) -> Result<(usize, Self), DecodeError>;
+
+    /// Returns the offset past `count` elements, entries or bytes at `offset`, like
+    /// [`Varint::required_len`](crate::Varint::required_len) a lower bound past the end of
+    /// `buf` if `buf` does not hold all of them.
+    fn required_collection_len(
+      buf: &[u8],
+      offset: usize,
+      count: usize,
+    ) -> Result<usize, DecodeError>;
+  }
+}
+
+use sealed::Sealed;
+
+/// Wraps a [`Collection`] to decode at most `MAX_LEN` elements, entries or bytes of a
+/// `String`, instead of [`DEFAULT_MAX_DECODED_LEN`].
+///
+/// The encoding is the same as the one of the collection. `MAX_ENCODED_LEN` is computed from
+/// `MAX_LEN`, and encoding a longer collection fails.
+///
+/// ## Example
+///
+/// ```rust
+/// use varing::{Varint, collections::Limited};
+///
+/// let mut buf = [0u8; 16];
+/// let written = Limited::<_, 4>(vec![1u32, 2, 3]).encode(&mut buf).unwrap();
+/// let (_, decoded) = Limited::<Vec<u32>, 4>::decode(&buf[..written.get()]).unwrap();
+/// assert_eq!(decoded.0, [1, 2, 3]);
+///
+/// assert!(Limited::<Vec<u32>, 2>::decode(&buf[..written.get()]).is_err());
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Limited<C, const MAX_LEN: usize>(pub C);
+
+impl<C, const MAX_LEN: usize> Limited<C, MAX_LEN> {
+  /// Returns the wrapped collection.
+  #[inline]
+  pub fn into_inner(self) -> C {
+    self.0
+  }
+}
+
+impl<C, const MAX_LEN: usize> From<C> for Limited<C, MAX_LEN> {
+  #[inline]
+  fn from(collection: C) -> Self {
+    Self(collection)
+  }
+}
+
+/// Decodes a [`Collection`] from the buffer, rejecting prefixes larger than `max_len`.
+///
+/// Returns the number of bytes read and the decoded collection if successful.
+///
+/// ## Example
+///
+/// ```rust
+/// use varing::{Varint, collections::decode_collection};
+///
+/// let mut buf = [0u8; 16];
+/// let written = String::from("varing").encode(&mut buf).unwrap();
+///
+/// let (read, decoded) = decode_collection::<String>(&buf, 8).unwrap();
+/// assert_eq!((read, decoded.as_str()), (written, "varing"));
+/// assert!(decode_collection::<String>(&buf, 4).is_err());
+/// ```
+pub fn decode_collection<C: Collection>(
+  buf: &[u8],
+  max_len: usize,
+) -> Result<(NonZeroUsize, C), DecodeError> {
+  let (prefix_len, len) = decode_prefix(buf, max_len)?;
+  // Each element takes at least `MIN_ELEMENT_ENCODED_LEN` bytes, a larger count fails on
+  // the missing data anyway.
+  let capacity = len.min((buf.len() - prefix_len) / C::MIN_ELEMENT_ENCODED_LEN.get());
+  let (read, collection) = C::decode_collection(buf, capacity)?;
+  NonZeroUsize::new(read)
+    .map(|read| (read, collection))
+    .ok_or_else(crate::invalid_consumed_length)
+}
+
+/// Decodes the count prefix, rejecting counts larger than `max_len`.
+#[inline]
+fn decode_prefix(buf: &[u8], max_len: usize) -> Result<(usize, usize), DecodeError> {
+  let (prefix_len, len) = decode_length_prefix(buf, LengthPrefix::Count)?;
+  if len > max_len {
+    return Err(DecodeError::other(
+      "collection length exceeds the decode limit",
+    ));
+  }
+  Ok((prefix_len, len))
+}
+
+/// [`Varint::required_len`] of a collection, found by scanning the prefix and the elements
+/// instead of decoding them, so that a partially read collection is not decoded again
+/// each time more of it arrives.
+fn required_len<C: Collection>(buf: &[u8], max_len: usize) -> Result<NonZeroUsize, DecodeError> {
+  let prefix_len = u64::required_len(buf)?;
+  if prefix_len.get() > buf.len() {
+    return Ok(prefix_len);
+  }
+
+  let (prefix_len, len) = decode_prefix(buf, max_len)?;
+  NonZeroUsize::new(C::required_collection_len(buf, prefix_len, len)?)
+    .ok_or_else(crate::invalid_consumed_length)
+}
+
+/// The encoded length of a collection of at most `max_len` elements.
+const fn max_encoded_len(max_len: usize, element_len: NonZeroUsize) -> NonZeroUsize {
+  encoded_u64_varint_len(max_len as u64).saturating_add(max_len.saturating_mul(element_len.get()))
+}
+
+#[inline]
+fn encoded_len<C: Collection>(collection: &C) -> NonZeroUsize {
+  NonZeroUsize::new(collection.encoded_collection_len()).unwrap_or(NON_ZERO_USIZE_ONE)
+}
+
+#[inline]
+fn encode<C: Collection>(
+  collection: &C,
+  buf: &mut [u8],
+  max_len: usize,
+) -> Result<NonZeroUsize, EncodeError> {
+  if collection.count() > max_len {
+    return Err(EncodeError::other(
+      "collection length exceeds the decode limit",
+    ));
+  }
+
+  NonZeroUsize::new(collection.encode_collection(buf)?)
+    .ok_or_else(|| EncodeError::other("length-prefixed collection encoded to zero bytes"))
+}
+
+macro_rules! collection_varint {
+  ($max_len:expr) => {
+    const MIN_ENCODED_LEN: NonZeroUsize = NON_ZERO_USIZE_ONE;
+    const MAX_ENCODED_LEN: NonZeroUsize =
+      max_encoded_len($max_len, <Self as Sealed>::MAX_ELEMENT_ENCODED_LEN);
+
+    #[inline]
+    fn encoded_len(&self) -> NonZeroUsize {
+      encoded_len(self)
+    }
+
+    #[inline]
+    fn encode(&self, buf: &mut [u8]) -> Result<NonZeroUsize, EncodeError> {
+      encode(self, buf, $max_len)
+    }
+
+    #[inline]
+    fn decode(buf: &[u8]) -> Result<(NonZeroUsize, Self), DecodeError>
+    where
+      Self: Sized,
+    {
+      decode_collection(buf, $max_len)
+    }
+
+    #[inline]
+    fn required_len(buf: &[u8]) -> Result<NonZeroUsize, DecodeError>
+    where
+      Self: Sized,
+    {
+      required_len::<Self>(buf, $max_len)
+    }
+  };
+}
+
+impl<C: Collection, const MAX_LEN: usize> Varint for Limited<C, MAX_LEN> {
+  const MIN_ENCODED_LEN: NonZeroUsize = NON_ZERO_USIZE_ONE;
+  const MAX_ENCODED_LEN: NonZeroUsize = max_encoded_len(MAX_LEN, C::MAX_ELEMENT_ENCODED_LEN);
+
+  #[inline]
+  fn encoded_len(&self) -> NonZeroUsize {
+    encoded_len(&self.0)
+  }
+
+  #[inline]
+  fn encode(&self, buf: &mut [u8]) -> Result<NonZeroUsize, EncodeError> {
+    encode(&self.0, buf, MAX_LEN)
+  }
+
+  #[inline]
+  fn decode(buf: &[u8]) -> Result<(NonZeroUsize, Self), DecodeError>
+  where
+    Self: Sized,
+  {
+    decode_collection(buf, MAX_LEN).map(|(read, collection)| (read, Self(collection)))
+  }
+
+  #[inline]
+  fn required_len(buf: &[u8]) -> Result<NonZeroUsize, DecodeError>
+  where
+    Self: Sized,
+  {
+    required_len::<C>(buf, MAX_LEN)
+  }
+}
+
+macro_rules! sequence {
+  ($($ty:ident::$push:ident), +$(,)?) => {
+    $(
+      impl<T: Varint> Sealed for $ty<T> {
+        const MIN_ELEMENT_ENCODED_LEN: NonZeroUsize = T::MIN_ENCODED_LEN;
+        const MAX_ELEMENT_ENCODED_LEN: NonZeroUsize = T::MAX_ENCODED_LEN;
+
+        #[inline]
+        fn count(&self) -> usize {
+          self.len()
+        }
+
+        #[inline]
+        fn encoded_collection_len(&self) -> usize {
+          encoded_sequence_with_len_len(self.iter(), LengthPrefix::Count)
+        }
+
+        #[inline]
+        fn encode_collection(&self, buf: &mut [u8]) -> Result<usize, EncodeError> {
+          encode_sequence_with_len(self.iter(), LengthPrefix::Count, buf)
+        }
+
+        fn decode_collection(buf: &[u8], capacity: usize) -> Result<(usize, Self), DecodeError> {
+          let mut decoder = sequence_decoder_with_len::<T>(buf, LengthPrefix::Count)?;
+          let mut sequence = $ty::with_capacity(capacity);
+          for res in decoder.by_ref() {
+            sequence.$push(res?.1);
+          }
+          Ok((decoder.position(), sequence))
+        }
+
+        #[inline]
+        fn required_collection_len(buf: &[u8], offset: usize, count: usize) -> Result<usize, DecodeError> {
+          required_elements_len::<T>(buf, offset, count)
+        }
+      }
+
+      impl<T: Varint> Collection for $ty<T> {}
+
+      impl<T: Varint> Varint for $ty<T> {
+        collection_varint!(DEFAULT_MAX_DECODED_LEN);
+      }
+    )*
+  };
+}
+
+sequence!(Vec::push, VecDeque::push_back);
+
+impl<T: Varint> Sealed for Box<[T]> {
+  const MIN_ELEMENT_ENCODED_LEN: NonZeroUsize = T::MIN_ENCODED_LEN;
+  const MAX_ELEMENT_ENCODED_LEN: NonZeroUsize = T::MAX_ENCODED_LEN;
+
+  #[inline]
+  fn count(&self) -> usize {
+    self.len()
+  }
+
+  #[inline]
+  fn encoded_collection_len(&self) -> usize {
+    encoded_sequence_with_len_len(self.iter(), LengthPrefix::Count)
+  }
+
+  #[inline]
+  fn encode_collection(&self, buf: &mut [u8]) -> Result<usize, EncodeError> {
+    encode_sequence_with_len(self.iter(), LengthPrefix::Count, buf)
+  }
+
+  #[inline]
+  fn decode_collection(buf: &[u8], capacity: usize) -> Result<(usize, Self), DecodeError> {
+    Vec::<T>::decode_collection(buf, capacity).map(|(read, vec)| (read, vec.into_boxed_slice()))
+  }
+
+  #[inline]
+  fn required_collection_len(
+    buf: &[u8],
+    offset: usize,
+    count: usize,
+  ) -> Result<usize, DecodeError> {
+    required_elements_len::<T>(buf, offset, count)
+  }
+}
+
+impl<T: Varint> Collection for Box<[T]> {}
+
+impl<T: Varint> Varint for Box<[T]> {
+  collection_varint!(DEFAULT_MAX_DECODED_LEN);
+}
+
+impl Sealed for String {
+  const MIN_ELEMENT_ENCODED_LEN: NonZeroUsize = NON_ZERO_USIZE_ONE;
+  const MAX_ELEMENT_ENCODED_LEN: NonZeroUsize = NON_ZERO_USIZE_ONE;
+
+  #[inline]
+  fn count(&self) -> usize {
+    self.len()
+  }
+
+  #[inline]
+  fn encoded_collection_len(&self) -> usize {
+    encoded_u64_varint_len(self.len() as u64).get() + self.len()
+  }
+
+  fn encode_collection(&self, buf: &mut [u8]) -> Result<usize, EncodeError> {
+    let encoded_len = encoded_len(self);
+    if buf.len() < encoded_len.get() {
+      return Err(EncodeError::insufficient_space(encoded_len, buf.len()));
+    }
+
+    let prefix_len = crate::encode_u64_varint_to(self.len() as u64, buf)?.get();
+    buf[prefix_len..encoded_len.get()].copy_from_slice(self.as_bytes());
+    Ok(encoded_len.get())
+  }
+
+  fn decode_collection(buf: &[u8], _: usize) -> Result<(usize, Self), DecodeError> {
+    // The byte length is checked against the buffer with the prefix.
+    let (prefix_len, len) = decode_length_prefix(buf, LengthPrefix::Bytes)?;
+    let end = prefix_len + len;
+    match core::str::from_utf8(&buf[prefix_len..end]) {
+      Ok(s) => Ok((end, String::from(s))),
+      Err(_) => Err(DecodeError::other("invalid UTF-8 string")),
+    }
+  }
+
+  #[inline]
+  fn required_collection_len(_: &[u8], offset: usize, count: usize) -> Result<usize, DecodeError> {
+    Ok(offset.saturating_add(count))
+  }
+}
+
+impl Collection for String {}
+
+impl Varint for String {
+  collection_varint!(DEFAULT_MAX_DECODED_LEN);
+}
+
+/// The error of a map encoding with a repeated key.
+#[inline]
+fn duplicate_key() -> DecodeError {
+  DecodeError::other("duplicate map key")
+}
+
+impl<K: Varint + Ord, V: Varint> Sealed for BTreeMap<K, V> {
+  const MIN_ELEMENT_ENCODED_LEN: NonZeroUsize =
+    K::MIN_ENCODED_LEN.saturating_add(V::MIN_ENCODED_LEN.get());
+  const MAX_ELEMENT_ENCODED_LEN: NonZeroUsize =
+    K::MAX_ENCODED_LEN.saturating_add(V::MAX_ENCODED_LEN.get());
+
+  #[inline]
+  fn count(&self) -> usize {
+    self.len()
+  }
+
+  #[inline]
+  fn encoded_collection_len(&self) -> usize {
+    encoded_map_with_len_len(self.iter(), LengthPrefix::Count)
+  }
+
+  #[inline]
+  fn encode_collection(&self, buf: &mut [u8]) -> Result<usize, EncodeError> {
+    encode_map_with_len(self.iter(), LengthPrefix::Count, buf)
+  }
+
+  fn decode_collection(buf: &[u8], _: usize) -> Result<(usize, Self), DecodeError> {
+    let mut decoder = map_decoder_with_len::<K, V>(buf, LengthPrefix::Count)?;
+    let mut map = BTreeMap::new();
+    for res in decoder.by_ref() {
+      let (key, value) = res?.1;
+      if map.insert(key, value).is_some() {
+        return Err(duplicate_key());
+      }
+    }
+    Ok((decoder.position(), map))
+  }
+
+  #[inline]
+  fn required_collection_len(
+    buf: &[u8],
+    offset: usize,
+    count: usize,
+  ) -> Result<usize, DecodeError> {
+    required_elements_len::<(K, V)>(buf, offset, count)
+  }
+}
+
+impl<K: Varint + Ord, V: Varint> Collection for BTreeMap<K, V> {}
+
+impl<K: Varint + Ord, V: Varint> Varint for BTreeMap<K, V> {
+  collection_varint!(DEFAULT_MAX_DECODED_LEN);
+}
+
+#[cfg(feature = "std")]
+impl<K, V, S> Sealed for HashMap<K, V, S>
+where
+  K: Varint + Eq + Hash,
+  V: Varint,
+  S: BuildHasher + Default,
+{
+  const MIN_ELEMENT_ENCODED_LEN: NonZeroUsize =
+    K::MIN_ENCODED_LEN.saturating_add(V::MIN_ENCODED_LEN.get());
+  const MAX_ELEMENT_ENCODED_LEN: NonZeroUsize =
+    K::MAX_ENCODED_LEN.saturating_add(V::MAX_ENCODED_LEN.get());
+
+  #[inline]
+  fn count(&self) -> usize {
+    self.len()
+  }
+
+  #[inline]
+  fn encoded_collection_len(&self) -> usize {
+    encoded_map_with_len_len(self.iter(), LengthPrefix::Count)
+  }
+
+  #[inline]
+  fn encode_collection(&self, buf: &mut [u8]) -> Result<usize, EncodeError> {
+    encode_map_with_len(self.iter(), LengthPrefix::Count, buf)
+  }
+
+  fn decode_collection(buf: &[u8], capacity: usize) -> Result<(usize, Self), DecodeError> {
+    let mut decoder = map_decoder_with_len::<K, V>(buf, LengthPrefix::Count)?;
+    let mut map = HashMap::with_capacity_and_hasher(capacity, S::default());
+    for res in decoder.by_ref() {
+      let (key, value) = res?.1;
+      if map.insert(key, value).is_some() {
+        return Err(duplicate_key());
+      }
+    }
+    Ok((decoder.position(), map))
+  }
+
+  #[inline]
+  fn required_collection_len(
+    buf: &[u8],
+    offset: usize,
+    count: usize,
+  ) -> Result<usize, DecodeError> {
+    required_elements_len::<(K, V)>(buf, offset, count)
+  }
+}
+
+#[cfg(feature = "std")]
+impl<K, V, S> Collection for HashMap<K, V, S>
+where
+  K: Varint + Eq + Hash,
+  V: Varint,
+  S: BuildHasher + Default,
+{
+}
+
+#[cfg(feature = "std")]
+impl<K, V, S> Varint for HashMap<K, V, S>
+where
+  K: Varint + Eq + Hash,
+  V: Varint,
+  S: BuildHasher + Default,
+{
+  collection_varint!(DEFAULT_MAX_DECODED_LEN);
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests;
@@ -0,0 +1,258 @@
+use super::*;
+
+use std::{collections::HashMap, vec};
+
+use quickcheck_macros::quickcheck;
+
+fn roundtrip<V: Varint + PartialEq>(value: V) -> bool {
+  let len = value.encoded_len().get();
+  let mut buf = vec![0u8; len + 1];
+  let Ok(written) = value.encode(&mut buf) else {
+    return false;
+  };
+  if written.get() != len || !V::ENCODED_LEN_RANGE.contains(&written) {
+    return false;
+  }
+  if value.encode(&mut buf[..len - 1]).is_ok() {
+    return false;
+  }
+
+  matches!(V::decode(&buf), Ok((read, decoded)) if read == written && decoded == value)
+    && V::required_len(&buf) == Ok(written)
+    && [0, len / 2, len - 1].into_iter().all(|end| {
+      V::decode(&buf[..end]).is_err()
+        && matches!(V::required_len(&buf[..end]), Ok(required) if required.get() > end)
+    })
+}
+
+macro_rules! collection_roundtrip {
+  ($($name:ident: $ty:ty), +$(,)?) => {
+    $(
+      paste::paste! {
+        #[quickcheck]
+        fn [< roundtrip_ $name >](value: $ty) -> bool {
+          roundtrip(value)
+        }
+      }
+    )*
+  };
+}
+
+collection_roundtrip!(
+  vec_u32: Vec<u32>,
+  vec_i64: Vec<i64>,
+  vec_option_u16: Vec<Option<u16>>,
+  vec_vec_u8: Vec<Vec<u8>>,
+  vec_deque_u8: VecDeque<u8>,
+  string: String,
+  vec_string: Vec<String>,
+  btree_map_u32_i64: BTreeMap<u32, i64>,
+  hash_map_u64_string: HashMap<u64, String>,
+  tuple_u32_vec_u8: (u32, Vec<u8>),
+  option_string: Option<String>,
+);
+
+#[quickcheck]
+fn roundtrip_boxed_slice_u64(value: Vec<u64>) -> bool {
+  roundtrip(value.into_boxed_slice())
+}
+
+#[test]
+fn encoding() {
+  let mut buf = [0u8; 8];
+  let written = vec![1u32, 300].encode(&mut buf).unwrap();
+  assert_eq!(&buf[..written.get()], [2, 0x01, 0xAC, 0x02]);
+
+  let written = String::from("hi").encode(&mut buf).unwrap();
+  assert_eq!(&buf[..written.get()], [2, b'h', b'i']);
+
+  let written = Vec::<u32>::new().encode(&mut buf).unwrap();
+  assert_eq!(&buf[..written.get()], [0]);
+
+  let written = BTreeMap::from([(1u8, 2u8)]).encode(&mut buf).unwrap();
+  assert_eq!(&buf[..written.get()], [1, 1, 2]);
+}
+
+#[test]
+fn encoded_len_bounds() {
+  assert_eq!(String::MIN_ENCODED_LEN.get(), 1);
+  assert_eq!(String::MAX_ENCODED_LEN.get(), 3 + DEFAULT_MAX_DECODED_LEN);
+  assert_eq!(<Limited<Vec<u8>, 4>>::MAX_ENCODED_LEN.get(), 1 + 4 * 2);
+  assert_eq!(
+    <Limited<BTreeMap<u8, u64>, 100>>::MAX_ENCODED_LEN.get(),
+    1 + 100 * 12
+  );
+  assert_eq!(
+    <Limited<Vec<Vec<u64>>, { usize::MAX }>>::MAX_ENCODED_LEN,
+    NonZeroUsize::MAX
+  );
+}
+
+#[test]
+fn hostile_count_is_rejected_before_allocating() {
+  let mut buf = [0u8; 16];
+  let written = crate::encode_u64_varint_to(u64::MAX, &mut buf).unwrap();
+  assert!(Vec::<u8>::decode(&buf[..written.get()]).is_err());
+  assert!(HashMap::<u8, u8>::decode(&buf[..written.get()]).is_err());
+
+  // Within the limit, but far more than the buffer holds.
+  let written = crate::encode_u64_varint_to(usize::MAX as u64, &mut buf).unwrap();
+  assert!(matches!(
+    decode_collection::<Vec<u8>>(&buf[..written.get()], usize::MAX),
+    Err(DecodeError::InsufficientData(_))
+  ));
+}
+
+#[test]
+fn limits() {
+  let value = vec![1u8, 2, 3];
+  let mut buf = [0u8; 8];
+  let written = Limited::<_, 3>(value.clone()).encode(&mut buf).unwrap();
+  assert_eq!(
+    Limited::<Vec<u8>, 3>::decode(&buf).unwrap(),
+    (written, Limited(value.clone()))
+  );
+
+  assert!(matches!(
+    Limited::<Vec<u8>, 2>::decode(&buf),
+    Err(DecodeError::Other(_))
+  ));
+  assert!(matches!(
+    Limited::<_, 2>(value).encode(&mut buf),
+    Err(EncodeError::Other(_))
+  ));
+  assert!(decode_collection::<VecDeque<u8>>(&buf, 3).is_ok());
+  assert!(decode_collection::<VecDeque<u8>>(&buf, 2).is_err());
+}
+
+#[test]
+fn string_rejects_invalid_utf8() {
+  assert!(matches!(
+    String::decode(&[2, 0xC3, 0x28]),
+    Err(DecodeError::Other(_))
+  ));
+}
+
+#[test]
+fn maps_reject_duplicate_keys() {
+  let buf = [2, 1, 2, 1, 3];
+  assert!(matches!(
+    BTreeMap::<u8, u8>::decode(&buf),
+    Err(DecodeError::Other(_))
+  ));
+  assert!(matches!(
+    HashMap::<u8, u8>::decode(&buf),
+    Err(DecodeError::Other(_))
+  ));
+}
+
+#[test]
+fn read_varint() {
+  use crate::{ReadVarint, WriteVarint};
+
+  let value = vec![String::from("varing"), String::new()];
+  let mut buf = Vec::new();
+  buf.write_varint(&value).unwrap();
+  buf.write_varint(&7u8).unwrap();
+
+  let mut reader = buf.as_slice();
+  assert_eq!(reader.read_varint::<Vec<String>>().unwrap(), value);
+  assert_eq!(reader.read_varint::<u8>().unwrap(), 7);
+}
+
+#[test]
+fn read_nested_collection() {
+  use crate::{ReadVarint, WriteVarint};
+
+  let value = vec![vec![1u64, u64::MAX], vec![], vec![300; 100]];
+  let mut buf = Vec::new();
+  buf.write_varint(&value).unwrap();
+  buf.write_varint(&7u8).unwrap();
+
+  // `Vec<Vec<u64>>::MAX_ENCODED_LEN` is tens of gigabytes, only the bytes read are buffered.
+  let mut reader = buf.as_slice();
+  assert_eq!(reader.read_varint::<Vec<Vec<u64>>>().unwrap(), value);
+  assert_eq!(reader.read_varint::<u8>().unwrap(), 7);
+}
+
+std::thread_local! {
+  static REQUIRED_LENS: core::cell::Cell<usize> = const { core::cell::Cell::new(0) };
+}
+
+/// A `u32` which counts the calls to its `required_len`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Counted(u32);
+
+impl Varint for Counted {
+  const MIN_ENCODED_LEN: NonZeroUsize = u32::MIN_ENCODED_LEN;
+  const MAX_ENCODED_LEN: NonZeroUsize = u32::MAX_ENCODED_LEN;
+
+  fn encoded_len(&self) -> NonZeroUsize {
+    self.0.encoded_len()
+  }
+
+  fn encode(&self, buf: &mut [u8]) -> Result<NonZeroUsize, EncodeError> {
+    self.0.encode(buf)
+  }
+
+  fn decode(buf: &[u8]) -> Result<(NonZeroUsize, Self), DecodeError> {
+    u32::decode(buf).map(|(read, value)| (read, Self(value)))
+  }
+
+  fn required_len(buf: &[u8]) -> Result<NonZeroUsize, DecodeError> {
+    REQUIRED_LENS.with(|calls| calls.set(calls.get() + 1));
+    u32::required_len(buf)
+  }
+}
+
+/// Encodes 20k elements of 1 to 5 bytes each, followed by a `u8`.
+fn many_elements() -> (Vec<Counted>, Vec<u8>) {
+  use crate::WriteVarint;
+
+  let value: Vec<Counted> = (0..20_000u32)
+    .map(|i| Counted(i.wrapping_mul(2_654_435_761) >> (i % 32)))
+    .collect();
+  let mut buf = Vec::new();
+  buf.write_varint(&value).unwrap();
+  buf.write_varint(&7u8).unwrap();
+  (value, buf)
+}
+
+/// Each element is scanned a logarithmic number of times, retrying after every byte scans
+/// them about `buf.len() * value.len() / 2` times.
+fn scan_bound(value: &[Counted], buf: &[u8]) -> usize {
+  2 * value.len() * buf.len().ilog2() as usize
+}
+
+#[test]
+fn read_varint_scans_each_element_a_bounded_number_of_times() {
+  use crate::ReadVarint;
+
+  let (value, buf) = many_elements();
+  REQUIRED_LENS.with(|calls| calls.set(0));
+  let mut reader = buf.as_slice();
+  assert_eq!(reader.read_varint::<Vec<Counted>>().unwrap(), value);
+  assert_eq!(reader.read_varint::<u8>().unwrap(), 7);
+
+  let calls = REQUIRED_LENS.with(|calls| calls.get());
+  assert!(calls <= scan_bound(&value, &buf), "{calls} element scans");
+}
+
+#[cfg(feature = "bytes_1")]
+#[test]
+fn decode_from_chained_buf_scans_each_element_a_bounded_number_of_times() {
+  use bytes_1::Buf;
+
+  let (value, buf) = many_elements();
+  let (head, tail) = buf.split_at(1);
+  let mut chain = head.chain(tail);
+  REQUIRED_LENS.with(|calls| calls.set(0));
+  assert_eq!(
+    Vec::<Counted>::decode_from_buf(&mut chain).unwrap().1,
+    value
+  );
+  assert_eq!(chain.remaining(), 1);
+
+  let calls = REQUIRED_LENS.with(|calls| calls.get());
+  assert!(calls <= scan_bound(&value, &buf), "{calls} element scans");
+}
@@ -0,0 +1,215 @@
+//! [`Varint`] for `Option<T>`, tuples and arrays of varints.
+//!
+//! Tuples and arrays encode their elements back-to-back in order, like a struct with
+//! `#[derive(Varint)]`. `Option<T>` is encoded like a derived enum with a `None = 0` and a
+//! `Some(T) = 1` variant: a one byte presence flag, followed by the value when present.
+
+use core::num::NonZeroUsize;
+
+use super::{
+  DecodeError, EncodeError, NON_ZERO_USIZE_ONE, Varint,
+  derive::{
+    check_space, decode_field, encode_field, non_zero, required_elements_len, required_field_len,
+  },
+};
+
+/// The presence flag of `None`.
+const NONE: u8 = 0;
+/// The presence flag of `Some`.
+const SOME: u8 = 1;
+
+/// Encoded as a one byte presence flag, `0` for `None` and `1` for `Some`, followed by the
+/// encoding of the value when present, so `Some(value)` always takes one byte more than
+/// `value`. The flag is never folded into the value, even when `T` has unused encodings,
+/// and other flag bytes are rejected when decoding. This layout is part of the wire format.
+///
+/// ## Example
+///
+/// ```rust
+/// use varing::Varint;
+///
+/// let mut buf = [0u8; 3];
+/// assert_eq!(None::<u16>.encode(&mut buf).unwrap().get(), 1);
+/// assert_eq!(buf[..1], [0]);
+/// assert_eq!(Some(300u16).encode(&mut buf).unwrap().get(), 3);
+/// assert_eq!(buf, [1, 0xAC, 0x02]);
+/// assert!(Option::<u16>::decode(&[2, 0]).is_err());
+/// ```
+impl<T: Varint> Varint for Option<T> {
+  const MIN_ENCODED_LEN: NonZeroUsize = NON_ZERO_USIZE_ONE;
+  const MAX_ENCODED_LEN: NonZeroUsize = T::MAX_ENCODED_LEN.saturating_add(1);
+
+  #[inline]
+  fn encoded_len(&self) -> NonZeroUsize {
+    match self {
+      None => NON_ZERO_USIZE_ONE,
+      Some(value) => value.encoded_len().saturating_add(1),
+    }
+  }
+
+  fn encode(&self, buf: &mut [u8]) -> Result<NonZeroUsize, EncodeError> {
+    check_space(self, buf)?;
+    match self {
+      None => {
+        buf[0] = NONE;
+        Ok(NON_ZERO_USIZE_ONE)
+      }
+      Some(value) => {
+        buf[0] = SOME;
+        encode_field(value, buf, 1).map(non_zero)
+      }
+    }
+  }
+
+  fn decode(buf: &[u8]) -> Result<(NonZeroUsize, Self), DecodeError>
+  where
+    Self: Sized,
+  {
+    match buf.first() {
+      None => Err(DecodeError::insufficient_data(0)),
+      Some(&NONE) => Ok((NON_ZERO_USIZE_ONE, None)),
+      Some(&SOME) => {
+        decode_field::<T>(buf, 1).map(|(offset, value)| (non_zero(offset), Some(value)))
+      }
+      Some(_) => Err(DecodeError::other("invalid Option presence flag")),
+    }
+  }
+
+  fn required_len(buf: &[u8]) -> Result<NonZeroUsize, DecodeError>
+  where
+    Self: Sized,
+  {
+    match buf.first() {
+      None | Some(&NONE) => Ok(NON_ZERO_USIZE_ONE),
+      Some(&SOME) => required_field_len::<T>(buf, 1).map(non_zero),
+      Some(_) => Err(DecodeError::other("invalid Option presence flag")),
+    }
+  }
+}
+
+macro_rules! impl_varint_for_tuple {
+  ($($ty:ident), +$(,)?) => {
+    paste::paste! {
+      impl<$($ty: Varint),+> Varint for ($($ty,)+) {
+        const MIN_ENCODED_LEN: NonZeroUsize = non_zero(0usize $(.saturating_add($ty::MIN_ENCODED_LEN.get()))+);
+        const MAX_ENCODED_LEN: NonZeroUsize = non_zero(0usize $(.saturating_add($ty::MAX_ENCODED_LEN.get()))+);
+
+        #[inline]
+        fn encoded_len(&self) -> NonZeroUsize {
+          let ($([< $ty:lower >],)+) = self;
+          non_zero(0 $(+ [< $ty:lower >].encoded_len().get())+)
+        }
+
+        fn encode(&self, buf: &mut [u8]) -> Result<NonZeroUsize, EncodeError> {
+          check_space(self, buf)?;
+          let offset = 0;
+          let ($([< $ty:lower >],)+) = self;
+          $(let offset = encode_field([< $ty:lower >], buf, offset)?;)+
+          Ok(non_zero(offset))
+        }
+
+        fn decode(buf: &[u8]) -> Result<(NonZeroUsize, Self), DecodeError>
+        where
+          Self: Sized,
+        {
+          let offset = 0;
+          $(let (offset, [< $ty:lower >]) = decode_field::<$ty>(buf, offset)?;)+
+          Ok((non_zero(offset), ($([< $ty:lower >],)+)))
+        }
+
+        fn required_len(buf: &[u8]) -> Result<NonZeroUsize, DecodeError>
+        where
+          Self: Sized,
+        {
+          let offset = 0;
+          $(let offset = required_field_len::<$ty>(buf, offset)?;)+
+          Ok(non_zero(offset))
+        }
+      }
+    }
+  };
+}
+
+impl_varint_for_tuple!(A);
+impl_varint_for_tuple!(A, B);
+impl_varint_for_tuple!(A, B, C);
+impl_varint_for_tuple!(A, B, C, D);
+impl_varint_for_tuple!(A, B, C, D, E);
+impl_varint_for_tuple!(A, B, C, D, E, F);
+impl_varint_for_tuple!(A, B, C, D, E, F, G);
+impl_varint_for_tuple!(A, B, C, D, E, F, G, H);
+impl_varint_for_tuple!(A, B, C, D, E, F, G, H, I);
+impl_varint_for_tuple!(A, B, C, D, E, F, G, H, I, J);
+impl_varint_for_tuple!(A, B, C, D, E, F, G, H, I, J, K);
+impl_varint_for_tuple!(A, B, C, D, E, F, G, H, I, J, K, L);
+
+/// Rejects `[T; 0]` at compile time, it would have an encoded length of zero.
+const fn array_len(elem_len: NonZeroUsize, n: usize) -> NonZeroUsize {
+  match NonZeroUsize::new(elem_len.get().saturating_mul(n)) {
+    Some(len) => len,
+    None => panic!("`Varint` is not implemented for empty arrays"),
+  }
+}
+
+/// Arrays of length zero have no encoding, using their implementation fails to compile.
+impl<T: Varint, const N: usize> Varint for [T; N] {
+  const MIN_ENCODED_LEN: NonZeroUsize = array_len(T::MIN_ENCODED_LEN, N);
+  const MAX_ENCODED_LEN: NonZeroUsize = array_len(T::MAX_ENCODED_LEN, N);
+
+  #[inline]
+  fn encoded_len(&self) -> NonZeroUsize {
+    let _ = Self::MIN_ENCODED_LEN;
+    non_zero(self.iter().map(|value| value.encoded_len().get()).sum())
+  }
+
+  fn encode(&self, buf: &mut [u8]) -> Result<NonZeroUsize, EncodeError> {
+    check_space(self, buf)?;
+    self
+      .iter()
+      .try_fold(0, |offset, value| encode_field(value, buf, offset))
+      .map(non_zero)
+  }
+
+  fn decode(buf: &[u8]) -> Result<(NonZeroUsize, Self), DecodeError>
+  where
+    Self: Sized,
+  {
+    let _ = Self::MIN_ENCODED_LEN;
+    let mut offset = 0;
+    let mut error = None;
+    let values: [Option<T>; N] = core::array::from_fn(|_| {
+      if error.is_some() {
+        return None;
+      }
+      match decode_field::<T>(buf, offset) {
+        Ok((next_offset, value)) => {
+          offset = next_offset;
+          Some(value)
+        }
+        Err(e) => {
+          error = Some(e);
+          None
+        }
+      }
+    });
+
+    match error {
+      Some(e) => Err(e),
+      None => Ok((
+        non_zero(offset),
+        values.map(|value| value.expect("every element is decoded without an error")),
+      )),
+    }
+  }
+
+  fn required_len(buf: &[u8]) -> Result<NonZeroUsize, DecodeError>
+  where
+    Self: Sized,
+  {
+    let _ = Self::MIN_ENCODED_LEN;
+    required_elements_len::<T>(buf, 0, N).map(non_zero)
+  }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,147 @@
+use super::*;
+
+use core::time::Duration;
+
+use quickcheck_macros::quickcheck;
+
+fn roundtrip<V: Varint + PartialEq>(value: V) -> bool {
+  let mut buf = [0u8; 512];
+  let Ok(written) = value.encode(&mut buf) else {
+    return false;
+  };
+  if written != value.encoded_len() || !V::ENCODED_LEN_RANGE.contains(&written) {
+    return false;
+  }
+
+  // Nothing is written on insufficient space.
+  let mut short = [0xFFu8; 512];
+  if value.encode(&mut short[..written.get() - 1]).is_ok() || short.iter().any(|&b| b != 0xFF) {
+    return false;
+  }
+
+  matches!(V::decode(&buf[..written.get()]), Ok((read, decoded)) if read == written && decoded == value)
+    && (0..written.get()).all(|end| V::decode(&buf[..end]).is_err())
+    && V::required_len(&buf) == Ok(written)
+    && (0..written.get())
+      .all(|end| matches!(V::required_len(&buf[..end]), Ok(required) if required.get() > end))
+}
+
+macro_rules! composite_roundtrip {
+  ($($name:ident: $ty:ty), +$(,)?) => {
+    $(
+      paste::paste! {
+        #[quickcheck]
+        fn [< roundtrip_ $name >](value: $ty) -> bool {
+          roundtrip(value)
+        }
+      }
+    )*
+  };
+}
+
+composite_roundtrip!(
+  option_u32: Option<u32>,
+  option_i128: Option<i128>,
+  option_option_u8: Option<Option<u8>>,
+  tuple_u16_i64: (u16, i64),
+  tuple_char_duration_option: (char, Duration, Option<u64>),
+  tuple_8: (u8, i8, u16, i16, u32, i32, u64, i64),
+  array_u8: [u8; 4],
+  array_i128: [i128; 3],
+  array_option_u32: [Option<u32>; 5],
+  array_tuple: [(u64, bool); 2],
+);
+
+#[quickcheck]
+fn roundtrip_tuple_12(
+  a: (u8, u16, u32, u64, u128, usize),
+  b: (i8, i16, i32, i64, i128, isize),
+) -> bool {
+  let (a0, a1, a2, a3, a4, a5) = a;
+  let (b0, b1, b2, b3, b4, b5) = b;
+  roundtrip((a0, a1, a2, a3, a4, a5, b0, b1, b2, b3, b4, b5))
+}
+
+#[test]
+fn option_encoding() {
+  let mut buf = [0u8; 8];
+  assert_eq!(None::<u32>.encode(&mut buf).unwrap().get(), 1);
+  assert_eq!(buf[0], 0);
+  assert_eq!(Some(300u32).encode(&mut buf).unwrap().get(), 3);
+  assert_eq!(&buf[..3], [0x01, 0xAC, 0x02]);
+
+  assert!(matches!(
+    Option::<u32>::decode(&[2, 0]),
+    Err(DecodeError::Other(_))
+  ));
+}
+
+#[test]
+fn encoded_len_bounds() {
+  assert_eq!(Option::<u32>::MIN_ENCODED_LEN.get(), 1);
+  assert_eq!(Option::<u32>::MAX_ENCODED_LEN.get(), 6);
+  assert_eq!(<(u16, i64)>::MIN_ENCODED_LEN.get(), 2);
+  assert_eq!(<(u16, i64)>::MAX_ENCODED_LEN.get(), 13);
+  assert_eq!(<[u8; 4]>::MIN_ENCODED_LEN.get(), 4);
+  assert_eq!(<[u8; 4]>::MAX_ENCODED_LEN.get(), 8);
+  assert_eq!(<[Option<u64>; 3]>::MAX_ENCODED_LEN.get(), 33);
+}
+
+#[test]
+fn tuple_and_array_share_the_struct_encoding() {
+  let mut tuple = [0u8; 16];
+  let mut array = [0u8; 16];
+  let written = (1u32, 300u32, 70_000u32).encode(&mut tuple).unwrap();
+  assert_eq!([1u32, 300, 70_000].encode(&mut array).unwrap(), written);
+  assert_eq!(tuple, array);
+  assert_eq!(
+    &tuple[..written.get()],
+    [0x01, 0xAC, 0x02, 0xF0, 0xA2, 0x04]
+  );
+}
+
+#[test]
+fn insufficient_data_is_relative_to_the_start() {
+  let mut buf = [0u8; 16];
+  let written = (1u32, 70_000u32).encode(&mut buf).unwrap();
+  match <(u32, u32)>::decode(&buf[..written.get() - 1]) {
+    Err(DecodeError::InsufficientData(e)) => assert_eq!(e.available(), written.get() - 1),
+    other => panic!("expected InsufficientData, got {other:?}"),
+  }
+  match <[u32; 2]>::decode(&buf[..written.get() - 1]) {
+    Err(DecodeError::InsufficientData(e)) => assert_eq!(e.available(), written.get() - 1),
+    other => panic!("expected InsufficientData, got {other:?}"),
+  }
+}
+
+/// An element without a useful upper bound on its encoded length.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Unbounded(u64);
+
+impl Varint for Unbounded {
+  const MIN_ENCODED_LEN: NonZeroUsize = u64::MIN_ENCODED_LEN;
+  const MAX_ENCODED_LEN: NonZeroUsize = NonZeroUsize::MAX;
+
+  fn encoded_len(&self) -> NonZeroUsize {
+    self.0.encoded_len()
+  }
+
+  fn encode(&self, buf: &mut [u8]) -> Result<NonZeroUsize, EncodeError> {
+    self.0.encode(buf)
+  }
+
+  fn decode(buf: &[u8]) -> Result<(NonZeroUsize, Self), DecodeError> {
+    u64::decode(buf).map(|(read, value)| (read, Self(value)))
+  }
+}
+
+#[test]
+fn unbounded_elements_saturate_encoded_len() {
+  // Summing without saturation overflows and fails to compile.
+  assert_eq!(<(u8, Unbounded)>::MAX_ENCODED_LEN, NonZeroUsize::MAX);
+  assert_eq!(<[Unbounded; 2]>::MAX_ENCODED_LEN, NonZeroUsize::MAX);
+  assert_eq!(Option::<Unbounded>::MAX_ENCODED_LEN, NonZeroUsize::MAX);
+  assert_eq!(<[Unbounded; 2]>::MIN_ENCODED_LEN.get(), 2);
+  assert!(roundtrip((7u8, Unbounded(u64::MAX))));
+  assert!(roundtrip([Unbounded(0), Unbounded(300)]));
+}
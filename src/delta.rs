@@ -0,0 +1,486 @@
+use core::num::NonZeroUsize;
+
+use super::{DecodeError, EncodeError, Varint, checked_decode_offset, utils};
+
+/// A value that can be delta encoded.
+///
+/// Values are mapped to an unsigned integer [representation](Delta::Repr), and a sequence
+/// stores the differences between consecutive representations instead of the values. The
+/// differences wrap around, so any sequence can be encoded, but only sequences of close
+/// values, such as sorted IDs or timestamps, are encoded compactly.
+///
+/// Implemented for all primitive integers, and for the timestamp types of the `chrono` and
+/// `time` features, as nanoseconds since the Unix epoch.
+pub trait Delta: Varint + Sized {
+  /// The unsigned integer the differences between values are computed in.
+  type Repr: DeltaRepr;
+
+  /// Returns the representation of the value, or `None` if it has none, e.g. a `chrono`
+  /// leap second.
+  fn to_delta_repr(&self) -> Option<Self::Repr>;
+
+  /// Returns the value of the representation, or `None` if it is out of range.
+  fn from_delta_repr(repr: Self::Repr) -> Option<Self>;
+}
+
+/// An unsigned integer that differences between [`Delta`] values are computed in.
+///
+/// This trait is sealed, it is implemented for `u8`, `u16`, `u32`, `u64` and `u128`.
+pub trait DeltaRepr: sealed::Sealed {}
+
+mod sealed {
+  pub trait Sealed: super::Varint + Copy + Default {
+    fn wrapping_add(self, rhs: Self) -> Self;
+
+    fn wrapping_sub(self, rhs: Self) -> Self;
+
+    fn zigzag_encode(self) -> Self;
+
+    fn zigzag_decode(self) -> Self;
+  }
+}
+
+use sealed::Sealed;
+
+macro_rules! delta_repr {
+  ($($bits:literal), +$(,)?) => {
+    $(
+      paste::paste! {
+        impl Sealed for [< u $bits >] {
+          #[inline]
+          fn wrapping_add(self, rhs: Self) -> Self {
+            [< u $bits >]::wrapping_add(self, rhs)
+          }
+
+          #[inline]
+          fn wrapping_sub(self, rhs: Self) -> Self {
+            [< u $bits >]::wrapping_sub(self, rhs)
+          }
+
+          #[inline]
+          fn zigzag_encode(self) -> Self {
+            utils::[< zigzag_encode_i $bits >](self as [< i $bits >])
+          }
+
+          #[inline]
+          fn zigzag_decode(self) -> Self {
+            utils::[< zigzag_decode_i $bits >](self) as [< u $bits >]
+          }
+        }
+
+        impl DeltaRepr for [< u $bits >] {}
+
+        impl Delta for [< u $bits >] {
+          type Repr = Self;
+
+          #[inline]
+          fn to_delta_repr(&self) -> Option<Self::Repr> {
+            Some(*self)
+          }
+
+          #[inline]
+          fn from_delta_repr(repr: Self::Repr) -> Option<Self> {
+            Some(repr)
+          }
+        }
+
+        impl Delta for [< i $bits >] {
+          type Repr = [< u $bits >];
+
+          #[inline]
+          fn to_delta_repr(&self) -> Option<Self::Repr> {
+            Some(*self as [< u $bits >])
+          }
+
+          #[inline]
+          fn from_delta_repr(repr: Self::Repr) -> Option<Self> {
+            Some(repr as [< i $bits >])
+          }
+        }
+      }
+    )*
+  };
+}
+
+delta_repr!(8, 16, 32, 64, 128);
+
+// `usize` and `isize` are varint encoded as 64-bit integers, so their differences are too.
+impl Delta for usize {
+  type Repr = u64;
+
+  #[inline]
+  fn to_delta_repr(&self) -> Option<Self::Repr> {
+    Some(*self as u64)
+  }
+
+  #[inline]
+  fn from_delta_repr(repr: Self::Repr) -> Option<Self> {
+    usize::try_from(repr).ok()
+  }
+}
+
+impl Delta for isize {
+  type Repr = u64;
+
+  #[inline]
+  fn to_delta_repr(&self) -> Option<Self::Repr> {
+    Some(*self as i64 as u64)
+  }
+
+  #[inline]
+  fn from_delta_repr(repr: Self::Repr) -> Option<Self> {
+    isize::try_from(repr as i64).ok()
+  }
+}
+
+/// Returns the representation of a value being encoded.
+#[inline]
+fn encode_repr<V: Delta>(value: &V) -> Result<V::Repr, EncodeError> {
+  value
+    .to_delta_repr()
+    .ok_or(EncodeError::other("value cannot be delta encoded"))
+}
+
+/// Returns the encoded length of a delta encoded sequence, or a delta-of-delta encoded
+/// one if `zigzag` is set.
+fn encoded_len<'i, V>(
+  sequence: impl Iterator<Item = &'i V>,
+  zigzag: bool,
+) -> Result<usize, EncodeError>
+where
+  V: Delta + 'i,
+{
+  let mut total = 0;
+  let mut state: Option<(V::Repr, V::Repr)> = None;
+  for value in sequence {
+    let repr = encode_repr(value)?;
+    state = Some(match state {
+      None => {
+        total += value.encoded_len().get();
+        (repr, V::Repr::default())
+      }
+      Some((prev, prev_delta)) => {
+        let delta = repr.wrapping_sub(prev);
+        total += if zigzag {
+          delta.wrapping_sub(prev_delta).zigzag_encode()
+        } else {
+          delta
+        }
+        .encoded_len()
+        .get();
+        (repr, delta)
+      }
+    });
+  }
+  Ok(total)
+}
+
+fn encode<'i, V>(
+  sequence: impl Iterator<Item = &'i V>,
+  buf: &mut [u8],
+  zigzag: bool,
+) -> Result<usize, EncodeError>
+where
+  V: Delta + 'i,
+{
+  let mut total_bytes = 0;
+  let mut state: Option<(V::Repr, V::Repr)> = None;
+  for value in sequence {
+    let repr = encode_repr(value)?;
+    let (bytes_written, next) = match state {
+      None => (
+        value.encode(&mut buf[total_bytes..])?,
+        (repr, V::Repr::default()),
+      ),
+      Some((prev, prev_delta)) => {
+        let delta = repr.wrapping_sub(prev);
+        let encoded = if zigzag {
+          delta.wrapping_sub(prev_delta).zigzag_encode()
+        } else {
+          delta
+        };
+        (encoded.encode(&mut buf[total_bytes..])?, (repr, delta))
+      }
+    };
+    total_bytes += bytes_written.get();
+    state = Some(next);
+  }
+  Ok(total_bytes)
+}
+
+/// Returns the encoded length of a delta encoded sequence.
+///
+/// Returns an error if a value [cannot be delta encoded](Delta::to_delta_repr).
+pub fn encoded_delta_sequence_len<'i, V>(
+  sequence: impl Iterator<Item = &'i V>,
+) -> Result<usize, EncodeError>
+where
+  V: Delta + 'i,
+{
+  encoded_len(sequence, false)
+}
+
+/// Encodes a sequence of values as the first value followed by the differences between
+/// consecutive values, and writes it to the buffer.
+///
+/// The first value is encoded with its [`Varint`] implementation, and each following one
+/// as the difference from its predecessor in its [representation](Delta::Repr), wrapping
+/// around, as an unsigned varint. Sorted sequences therefore only store small differences,
+/// and unsorted ones still round trip.
+///
+/// Returns the total number of bytes written to the buffer, or an error if a value
+/// [cannot be delta encoded](Delta::to_delta_repr), such as a `chrono` leap second.
+///
+/// ## Example
+///
+/// ```rust
+/// # #[cfg(feature = "std")]
+/// # {
+/// use varing::delta::{decode_delta_sequence, encode_delta_sequence, encoded_delta_sequence_len};
+///
+/// let ids = [1_000_000u64, 1_000_003, 1_000_010, 1_000_400];
+/// let encoded_len = encoded_delta_sequence_len(ids.iter()).unwrap();
+/// assert_eq!(encoded_len, 3 + 1 + 1 + 2);
+///
+/// let mut buf = vec![0; encoded_len];
+/// assert_eq!(encode_delta_sequence(ids.iter(), &mut buf).unwrap(), encoded_len);
+///
+/// let (read, decoded) = decode_delta_sequence::<u64, Vec<_>>(&buf).unwrap();
+/// assert_eq!(read, encoded_len);
+/// assert_eq!(decoded, ids);
+/// # }
+/// ```
+pub fn encode_delta_sequence<'i, V>(
+  sequence: impl Iterator<Item = &'i V>,
+  buf: &mut [u8],
+) -> Result<usize, EncodeError>
+where
+  V: Delta + 'i,
+{
+  encode(sequence, buf, false)
+}
+
+/// Returns a decoder for a sequence encoded by [`encode_delta_sequence`].
+#[inline]
+pub const fn delta_sequence_decoder<V: Delta>(buf: &[u8]) -> DeltaSequenceDecoder<'_, V> {
+  DeltaSequenceDecoder::new(buf, false)
+}
+
+/// Decodes a sequence encoded by [`encode_delta_sequence`] from the buffer.
+///
+/// Returns the number of bytes read from the buffer and a collection of the decoded values
+/// if successful.
+pub fn decode_delta_sequence<V, O>(buf: &[u8]) -> Result<(usize, O), DecodeError>
+where
+  V: Delta,
+  O: core::iter::FromIterator<V>,
+{
+  decode(delta_sequence_decoder::<V>(buf))
+}
+
+/// Returns the encoded length of a delta-of-delta encoded sequence.
+///
+/// Returns an error if a value [cannot be delta encoded](Delta::to_delta_repr).
+pub fn encoded_delta_of_delta_sequence_len<'i, V>(
+  sequence: impl Iterator<Item = &'i V>,
+) -> Result<usize, EncodeError>
+where
+  V: Delta + 'i,
+{
+  encoded_len(sequence, true)
+}
+
+/// Encodes a sequence of values as the first value followed by the changes between
+/// consecutive differences, and writes it to the buffer.
+///
+/// Like [`encode_delta_sequence`], but each difference is stored as its change from the
+/// previous difference (the first difference from zero), zigzag encoded. Values taken at a
+/// fixed interval, like the timestamps of a time series, then store a zero byte each.
+///
+/// Returns the total number of bytes written to the buffer, or an error if a value
+/// [cannot be delta encoded](Delta::to_delta_repr), such as a `chrono` leap second.
+///
+/// ## Example
+///
+/// ```rust
+/// # #[cfg(feature = "std")]
+/// # {
+/// use varing::delta::{
+///   decode_delta_of_delta_sequence, encode_delta_of_delta_sequence,
+///   encoded_delta_of_delta_sequence_len,
+/// };
+///
+/// // One sample every 15 seconds, with a late one.
+/// let timestamps = [1_700_000_000i64, 1_700_000_015, 1_700_000_030, 1_700_000_046, 1_700_000_060];
+/// let encoded_len = encoded_delta_of_delta_sequence_len(timestamps.iter()).unwrap();
+/// assert_eq!(encoded_len, 5 + 1 + 1 + 1 + 1);
+///
+/// let mut buf = vec![0; encoded_len];
+/// encode_delta_of_delta_sequence(timestamps.iter(), &mut buf).unwrap();
+/// assert_eq!(&buf[5..], [30, 0, 2, 3]);
+///
+/// let (_, decoded) = decode_delta_of_delta_sequence::<i64, Vec<_>>(&buf).unwrap();
+/// assert_eq!(decoded, timestamps);
+/// # }
+/// ```
+pub fn encode_delta_of_delta_sequence<'i, V>(
+  sequence: impl Iterator<Item = &'i V>,
+  buf: &mut [u8],
+) -> Result<usize, EncodeError>
+where
+  V: Delta + 'i,
+{
+  encode(sequence, buf, true)
+}
+
+/// Returns a decoder for a sequence encoded by [`encode_delta_of_delta_sequence`].
+#[inline]
+pub const fn delta_of_delta_sequence_decoder<V: Delta>(buf: &[u8]) -> DeltaSequenceDecoder<'_, V> {
+  DeltaSequenceDecoder::new(buf, true)
+}
+
+/// Decodes a sequence encoded by [`encode_delta_of_delta_sequence`] from the buffer.
+///
+/// Returns the number of bytes read from the buffer and a collection of the decoded values
+/// if successful.
+pub fn decode_delta_of_delta_sequence<V, O>(buf: &[u8]) -> Result<(usize, O), DecodeError>
+where
+  V: Delta,
+  O: core::iter::FromIterator<V>,
+{
+  decode(delta_of_delta_sequence_decoder::<V>(buf))
+}
+
+fn decode<V, O>(mut decoder: DeltaSequenceDecoder<'_, V>) -> Result<(usize, O), DecodeError>
+where
+  V: Delta,
+  O: core::iter::FromIterator<V>,
+{
+  let output = decoder
+    .by_ref()
+    .map(|result| result.map(|(_, value)| value))
+    .collect::<Result<O, _>>()?;
+  Ok((decoder.position(), output))
+}
+
+/// An iterator that decodes a delta or delta-of-delta encoded sequence from a buffer.
+///
+/// Created by [`delta_sequence_decoder`] and [`delta_of_delta_sequence_decoder`]. Like
+/// [`SequenceDecoder`](crate::SequenceDecoder), it yields the number of bytes read for each
+/// value along with the value, and stops after the first error.
+///
+/// ## Example
+///
+/// ```rust
+/// use varing::delta::{delta_sequence_decoder, encode_delta_sequence};
+///
+/// let ids = [10u32, 12, 15, 300];
+/// let mut buf = [0u8; 8];
+/// let written = encode_delta_sequence(ids.iter(), &mut buf).unwrap();
+///
+/// let mut decoder = delta_sequence_decoder::<u32>(&buf[..written]);
+/// // `DeltaSequenceDecoder` is copy
+/// let decoder1 = decoder;
+/// for id in ids {
+///   assert_eq!(decoder.next().unwrap().unwrap().1, id);
+/// }
+/// assert!(decoder.next().is_none());
+/// assert_eq!(decoder.position(), written);
+/// assert_eq!(decoder1.position(), 0);
+/// ```
+pub struct DeltaSequenceDecoder<'a, V: Delta> {
+  buf: &'a [u8],
+  offset: usize,
+  zigzag: bool,
+  /// The representation of the previous value and the previous difference.
+  state: Option<(V::Repr, V::Repr)>,
+}
+
+impl<V: Delta> core::fmt::Debug for DeltaSequenceDecoder<'_, V>
+where
+  V::Repr: core::fmt::Debug,
+{
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    f.debug_struct("DeltaSequenceDecoder")
+      .field("buf", &self.buf)
+      .field("offset", &self.offset)
+      .field("zigzag", &self.zigzag)
+      .field("state", &self.state)
+      .finish()
+  }
+}
+
+impl<V: Delta> Clone for DeltaSequenceDecoder<'_, V> {
+  fn clone(&self) -> Self {
+    *self
+  }
+}
+
+impl<V: Delta> Copy for DeltaSequenceDecoder<'_, V> {}
+
+impl<'a, V: Delta> DeltaSequenceDecoder<'a, V> {
+  #[inline]
+  const fn new(buf: &'a [u8], zigzag: bool) -> Self {
+    Self {
+      buf,
+      offset: 0,
+      zigzag,
+      state: None,
+    }
+  }
+
+  /// Returns the current position of the buffer.
+  #[inline]
+  pub const fn position(&self) -> usize {
+    self.offset
+  }
+
+  fn decode_next(&mut self) -> Result<(NonZeroUsize, V), DecodeError> {
+    let buf = &self.buf[self.offset..];
+    let (bytes_read, value, next) = match self.state {
+      None => {
+        let (bytes_read, value) = V::decode(buf)?;
+        let repr = value
+          .to_delta_repr()
+          .ok_or(DecodeError::other("value cannot be delta encoded"))?;
+        (bytes_read, value, (repr, V::Repr::default()))
+      }
+      Some((prev, prev_delta)) => {
+        let (bytes_read, encoded) = V::Repr::decode(buf)?;
+        let delta = if self.zigzag {
+          prev_delta.wrapping_add(encoded.zigzag_decode())
+        } else {
+          encoded
+        };
+        let repr = prev.wrapping_add(delta);
+        let value =
+          V::from_delta_repr(repr).ok_or(DecodeError::other("delta decoded value out of range"))?;
+        (bytes_read, value, (repr, delta))
+      }
+    };
+    self.offset = checked_decode_offset(self.offset, bytes_read, self.buf.len())?;
+    self.state = Some(next);
+    Ok((bytes_read, value))
+  }
+}
+
+impl<V: Delta> Iterator for DeltaSequenceDecoder<'_, V> {
+  type Item = Result<(NonZeroUsize, V), DecodeError>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    if self.offset < self.buf.len() {
+      let result = self.decode_next();
+      if result.is_err() {
+        self.offset = self.buf.len();
+      }
+      Some(result)
+    } else {
+      None
+    }
+  }
+}
+
+impl<V: Delta> core::iter::FusedIterator for DeltaSequenceDecoder<'_, V> {}
+
+#[cfg(all(test, feature = "std"))]
+mod tests;
@@ -0,0 +1,124 @@
+use super::*;
+
+use quickcheck_macros::quickcheck;
+
+macro_rules! delta_roundtrip {
+  ($($ty:ident), +$(,)?) => {
+    $(
+      paste::paste! {
+        #[quickcheck]
+        fn [< delta_ $ty _roundtrip >](values: Vec<$ty>) -> bool {
+          let len = encoded_delta_sequence_len(values.iter()).unwrap();
+          let mut buf = vec![0; len];
+          let written = encode_delta_sequence(values.iter(), &mut buf).unwrap();
+          let decoded = delta_sequence_decoder::<$ty>(&buf)
+            .map(|res| res.unwrap().1)
+            .collect::<Vec<_>>();
+          written == len
+            && decode_delta_sequence::<$ty, Vec<_>>(&buf).unwrap() == (len, values.clone())
+            && decoded == values
+        }
+
+        #[quickcheck]
+        fn [< delta_of_delta_ $ty _roundtrip >](values: Vec<$ty>) -> bool {
+          let len = encoded_delta_of_delta_sequence_len(values.iter()).unwrap();
+          let mut buf = vec![0; len];
+          let written = encode_delta_of_delta_sequence(values.iter(), &mut buf).unwrap();
+          written == len
+            && decode_delta_of_delta_sequence::<$ty, Vec<_>>(&buf).unwrap() == (len, values)
+        }
+      }
+    )*
+  };
+}
+
+delta_roundtrip!(
+  u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize
+);
+
+macro_rules! sorted_delta_is_no_larger {
+  ($($ty:ident), +$(,)?) => {
+    $(
+      paste::paste! {
+        #[quickcheck]
+        fn [< delta_ $ty _sorted_is_no_larger >](mut values: Vec<$ty>) -> bool {
+          values.sort_unstable();
+          encoded_delta_sequence_len(values.iter()).unwrap()
+            <= crate::encoded_sequence_len(values.iter())
+        }
+      }
+    )*
+  };
+}
+
+sorted_delta_is_no_larger!(u8, u16, u32, u64, u128, usize);
+
+#[test]
+fn delta_wraps_around() {
+  let values = [u8::MAX, 0, u8::MAX];
+  let mut buf = [0u8; 8];
+  let written = encode_delta_sequence(values.iter(), &mut buf).unwrap();
+  // 255 takes two bytes, then +1 and -1 wrap around to 1 and 255.
+  assert_eq!(&buf[..written], [0xFF, 0x01, 0x01, 0xFF, 0x01]);
+  assert_eq!(
+    decode_delta_sequence::<u8, Vec<_>>(&buf[..written]).unwrap(),
+    (written, values.to_vec())
+  );
+}
+
+#[test]
+fn delta_of_delta_of_fixed_interval_is_zero() {
+  let values = (0..100).map(|i| 1_000 + 60 * i).collect::<Vec<u64>>();
+  let len = encoded_delta_of_delta_sequence_len(values.iter()).unwrap();
+  let mut buf = vec![0; len];
+  encode_delta_of_delta_sequence(values.iter(), &mut buf).unwrap();
+  // the first value, the first difference (zigzag encoded 60), then zeroes
+  assert_eq!(&buf[..3], [0xE8, 0x07, 120]);
+  assert!(buf[3..].iter().all(|&b| b == 0));
+  assert_eq!(len, 3 + 98);
+}
+
+#[test]
+fn empty_sequence() {
+  let values: [u64; 0] = [];
+  assert_eq!(encoded_delta_sequence_len(values.iter()).unwrap(), 0);
+  assert_eq!(encode_delta_sequence(values.iter(), &mut []).unwrap(), 0);
+  assert_eq!(
+    decode_delta_of_delta_sequence::<u64, Vec<_>>(&[]).unwrap(),
+    (0, vec![])
+  );
+  assert!(delta_sequence_decoder::<u64>(&[]).next().is_none());
+}
+
+#[test]
+fn insufficient_space() {
+  let values = [1u32, 300, 70_000];
+  let len = encoded_delta_sequence_len(values.iter()).unwrap();
+  let mut buf = vec![0; len];
+  for available in 0..len {
+    assert!(encode_delta_sequence(values.iter(), &mut buf[..available]).is_err());
+  }
+}
+
+#[test]
+fn decoder_stops_after_error() {
+  let values = [1u32, 300, 70_000];
+  let mut buf = [0u8; 8];
+  let written = encode_delta_sequence(values.iter(), &mut buf).unwrap();
+
+  let truncated = &buf[..written - 1];
+  let mut decoder = delta_sequence_decoder::<u32>(truncated);
+  assert_eq!(decoder.next().unwrap().unwrap().1, 1);
+  assert_eq!(decoder.next().unwrap().unwrap().1, 300);
+  assert!(decoder.next().unwrap().is_err());
+  assert_eq!(decoder.position(), truncated.len());
+  assert!(decoder.next().is_none());
+  assert!(decode_delta_sequence::<u32, Vec<_>>(truncated).is_err());
+}
+
+#[test]
+fn decode_rejects_overflowing_differences() {
+  // Differences are varints of the representation, `u8` for `i8`, so 256 does not fit.
+  let buf = [0x00, 0x80, 0x02];
+  assert!(decode_delta_of_delta_sequence::<i8, Vec<_>>(&buf).is_err());
+}
@@ -0,0 +1,108 @@
+//! Helpers called from the code generated by `#[derive(Varint)]`, and shared with the
+//! tuple and array implementations.
+
+use core::num::NonZeroUsize;
+
+use super::{DecodeError, EncodeError, InsufficientData, Varint, checked_decode_offset};
+
+/// Converts a length the derive has proven non-zero.
+#[inline]
+pub const fn non_zero(len: usize) -> NonZeroUsize {
+  match NonZeroUsize::new(len) {
+    Some(len) => len,
+    None => panic!("derived `Varint` has an encoded length of zero"),
+  }
+}
+
+/// `core::cmp::min` is not `const`.
+#[cfg(feature = "derive")]
+#[inline]
+pub const fn min(a: usize, b: usize) -> usize {
+  if a < b { a } else { b }
+}
+
+/// `core::cmp::max` is not `const`.
+#[cfg(feature = "derive")]
+#[inline]
+pub const fn max(a: usize, b: usize) -> usize {
+  if a > b { a } else { b }
+}
+
+/// Fails before anything is written if the whole value does not fit in the buffer.
+#[inline]
+pub fn check_space<V: Varint + ?Sized>(value: &V, buf: &[u8]) -> Result<(), EncodeError> {
+  let len = value.encoded_len();
+  if buf.len() < len.get() {
+    return Err(EncodeError::insufficient_space(len, buf.len()));
+  }
+  Ok(())
+}
+
+/// Encodes `value` at `offset` and returns the offset past it.
+#[inline]
+pub fn encode_field<V: Varint + ?Sized>(
+  value: &V,
+  buf: &mut [u8],
+  offset: usize,
+) -> Result<usize, EncodeError> {
+  let written = value.encode(&mut buf[offset..])?;
+  match offset.checked_add(written.get()) {
+    Some(next_offset) if next_offset <= buf.len() => Ok(next_offset),
+    _ => Err(EncodeError::other(
+      "Varint::encode returned an invalid written length",
+    )),
+  }
+}
+
+/// Decodes a `V` at `offset` and returns the offset past it.
+///
+/// Insufficient data errors are reported relative to the start of `buf`.
+#[inline]
+pub fn decode_field<V: Varint>(buf: &[u8], offset: usize) -> Result<(usize, V), DecodeError> {
+  match V::decode(&buf[offset..]) {
+    Ok((read, value)) => Ok((checked_decode_offset(offset, read, buf.len())?, value)),
+    Err(DecodeError::InsufficientData(e)) => {
+      let available = e.available().saturating_add(offset);
+      Err(DecodeError::InsufficientData(match e.required() {
+        Some(required) => {
+          InsufficientData::with_required(required.saturating_add(offset), available)
+        }
+        None => InsufficientData::new(available),
+      }))
+    }
+    Err(e) => Err(e),
+  }
+}
+
+/// Returns the offset past a `V` at `offset`, found with [`Varint::required_len`].
+///
+/// If `buf` does not hold the whole `V`, the result is a lower bound past the end of `buf`,
+/// and so is the result of every following call.
+#[inline]
+pub fn required_field_len<V: Varint>(buf: &[u8], offset: usize) -> Result<usize, DecodeError> {
+  match buf.get(offset..) {
+    Some(rest) => Ok(offset.saturating_add(V::required_len(rest)?.get())),
+    // A previous field is already incomplete.
+    None => Ok(offset.saturating_add(V::MIN_ENCODED_LEN.get())),
+  }
+}
+
+/// Returns the offset past `count` consecutive `V`s at `offset`, like
+/// [`required_field_len`], without visiting the elements past the end of `buf`.
+pub fn required_elements_len<V: Varint>(
+  buf: &[u8],
+  mut offset: usize,
+  count: usize,
+) -> Result<usize, DecodeError> {
+  for scanned in 0..count {
+    if offset > buf.len() {
+      let remaining = count - scanned;
+      return Ok(offset.saturating_add(remaining.saturating_mul(V::MIN_ENCODED_LEN.get())));
+    }
+    offset = required_field_len::<V>(buf, offset)?;
+  }
+  Ok(offset)
+}
+
+#[cfg(all(test, feature = "derive"))]
+mod tests;
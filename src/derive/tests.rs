@@ -0,0 +1,229 @@
+use crate::{DecodeError, EncodeError, Varint};
+
+use core::{num::NonZeroUsize, time::Duration};
+
+use quickcheck_macros::quickcheck;
+
+#[derive(Debug, Clone, Copy, PartialEq, Varint)]
+#[varing(crate = "crate")]
+struct Header {
+  id: u32,
+  flags: u8,
+  offset: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Varint)]
+#[varing(crate = "crate")]
+struct Timed(char, Duration);
+
+#[derive(Debug, Clone, Copy, PartialEq, Varint)]
+#[varing(crate = "crate")]
+struct Pair<T>(T, T);
+
+#[derive(Debug, Clone, Copy, PartialEq, Varint)]
+#[varing(crate = "crate")]
+enum Level {
+  Debug,
+  Info,
+  Warn,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Varint)]
+#[varing(crate = "crate")]
+#[repr(i16)]
+enum Code {
+  Low = -1,
+  Zero,
+  High = 1000,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Varint)]
+#[varing(crate = "crate")]
+enum Shape {
+  Empty,
+  Circle(u32),
+  Rect(u32, u32),
+  Line { from: Pair<i8>, to: Pair<i8> },
+}
+
+fn roundtrip<V: Varint + PartialEq + core::fmt::Debug>(value: V) -> bool {
+  let mut buf = [0u8; 64];
+  let Ok(written) = value.encode(&mut buf) else {
+    return false;
+  };
+  if written != value.encoded_len() || !V::ENCODED_LEN_RANGE.contains(&written) {
+    return false;
+  }
+
+  matches!(V::decode(&buf[..written.get()]), Ok((read, decoded)) if read == written && decoded == value)
+    && V::required_len(&buf) == Ok(written)
+    && (0..written.get())
+      .all(|end| matches!(V::required_len(&buf[..end]), Ok(required) if required.get() > end))
+}
+
+#[test]
+fn struct_encoded_len_range() {
+  assert_eq!(Header::MIN_ENCODED_LEN.get(), 3);
+  assert_eq!(
+    Header::MAX_ENCODED_LEN.get(),
+    u32::MAX_ENCODED_LEN.get() + u8::MAX_ENCODED_LEN.get() + i64::MAX_ENCODED_LEN.get()
+  );
+  assert_eq!(Pair::<u16>::MIN_ENCODED_LEN.get(), 2);
+  assert_eq!(Pair::<u16>::MAX_ENCODED_LEN.get(), 6);
+}
+
+#[test]
+fn struct_fields_in_order() {
+  let header = Header {
+    id: 300,
+    flags: 1,
+    offset: -1,
+  };
+  let mut buf = [0u8; 16];
+  let written = header.encode(&mut buf).unwrap();
+  assert_eq!(&buf[..written.get()], &[0xAC, 0x02, 0x01, 0x01]);
+  assert!(roundtrip(header));
+}
+
+#[test]
+fn enum_encoded_len_range() {
+  assert_eq!(Level::MIN_ENCODED_LEN.get(), 1);
+  assert_eq!(Level::MAX_ENCODED_LEN.get(), 1);
+
+  // `1000` zigzags to `2000`, which takes two bytes.
+  assert_eq!(Code::MIN_ENCODED_LEN.get(), 1);
+  assert_eq!(Code::MAX_ENCODED_LEN.get(), 2);
+
+  assert_eq!(Shape::MIN_ENCODED_LEN.get(), 1);
+  assert_eq!(
+    Shape::MAX_ENCODED_LEN.get(),
+    1 + 2 * u32::MAX_ENCODED_LEN.get()
+  );
+}
+
+#[test]
+fn enum_discriminants() {
+  let mut buf = [0u8; 4];
+  assert_eq!(Level::Warn.encode(&mut buf).unwrap().get(), 1);
+  assert_eq!(buf[0], 2);
+
+  assert_eq!(Code::Zero.encode(&mut buf).unwrap().get(), 1);
+  assert_eq!(buf[0], 0);
+  assert_eq!(Code::decode(&[0x01]).unwrap().1, Code::Low);
+  assert_eq!(Code::decode(&[0xD0, 0x0F]).unwrap().1, Code::High);
+
+  for level in [Level::Debug, Level::Info, Level::Warn] {
+    assert!(roundtrip(level));
+  }
+  for code in [Code::Low, Code::Zero, Code::High] {
+    assert!(roundtrip(code));
+  }
+}
+
+#[test]
+fn enum_unknown_discriminant() {
+  assert!(matches!(Level::decode(&[3]), Err(DecodeError::Other(_))));
+  assert!(matches!(Code::decode(&[0x02]), Err(DecodeError::Other(_))));
+}
+
+#[test]
+fn encode_insufficient_space_writes_nothing() {
+  let value = Shape::Rect(u32::MAX, 1);
+  let mut buf = [0xFFu8; 6];
+  assert_eq!(
+    value.encode(&mut buf),
+    Err(EncodeError::insufficient_space(
+      NonZeroUsize::new(7).unwrap(),
+      6
+    ))
+  );
+  assert_eq!(buf, [0xFF; 6]);
+}
+
+#[test]
+fn decode_insufficient_data_is_relative_to_whole_buffer() {
+  let mut buf = [0u8; 16];
+  let written = Shape::Rect(300, 300).encode(&mut buf).unwrap().get();
+  assert_eq!(
+    Shape::decode(&buf[..written - 1]),
+    Err(DecodeError::insufficient_data(written - 1))
+  );
+}
+
+#[test]
+fn decode_canonical() {
+  assert_eq!(Level::decode(&[0x81, 0x00]).unwrap().1, Level::Info);
+  assert_eq!(
+    Level::decode_canonical(&[0x81, 0x00]),
+    Err(DecodeError::NonCanonical)
+  );
+}
+
+#[quickcheck]
+fn fuzzy_struct(id: u32, flags: u8, offset: i64, c: char, secs: u64, nanos: u32) -> bool {
+  roundtrip(Header { id, flags, offset })
+    && roundtrip(Timed(c, Duration::new(secs, nanos % 1_000_000_000)))
+    && roundtrip(Pair(id, flags as u32))
+}
+
+#[quickcheck]
+fn fuzzy_enum(tag: u8, a: u32, b: u32, line: (i8, i8, i8, i8)) -> bool {
+  let shape = match tag % 4 {
+    0 => Shape::Empty,
+    1 => Shape::Circle(a),
+    2 => Shape::Rect(a, b),
+    _ => Shape::Line {
+      from: Pair(line.0, line.1),
+      to: Pair(line.2, line.3),
+    },
+  };
+  roundtrip(shape)
+}
+
+/// A field without a useful upper bound on its encoded length.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Unbounded(u64);
+
+impl Varint for Unbounded {
+  const MIN_ENCODED_LEN: NonZeroUsize = u64::MIN_ENCODED_LEN;
+  const MAX_ENCODED_LEN: NonZeroUsize = NonZeroUsize::MAX;
+
+  fn encoded_len(&self) -> NonZeroUsize {
+    self.0.encoded_len()
+  }
+
+  fn encode(&self, buf: &mut [u8]) -> Result<NonZeroUsize, EncodeError> {
+    self.0.encode(buf)
+  }
+
+  fn decode(buf: &[u8]) -> Result<(NonZeroUsize, Self), DecodeError> {
+    u64::decode(buf).map(|(read, value)| (read, Self(value)))
+  }
+}
+
+#[test]
+fn unbounded_field_saturates_encoded_len() {
+  #[derive(Debug, Clone, Copy, PartialEq, Varint)]
+  #[varing(crate = "crate")]
+  struct Batch {
+    id: u32,
+    rows: Unbounded,
+  }
+
+  #[derive(Debug, Clone, Copy, PartialEq, Varint)]
+  #[varing(crate = "crate")]
+  enum Body {
+    Empty,
+    Batch(Batch),
+  }
+
+  // Summing without saturation overflows and fails to compile.
+  assert_eq!(Batch::MAX_ENCODED_LEN, NonZeroUsize::MAX);
+  assert_eq!(Body::MAX_ENCODED_LEN, NonZeroUsize::MAX);
+  assert_eq!(Body::MIN_ENCODED_LEN.get(), 1);
+  assert!(roundtrip(Body::Batch(Batch {
+    id: 7,
+    rows: Unbounded(u64::MAX),
+  })));
+  assert!(roundtrip(Body::Empty));
+}
@@ -232,6 +232,9 @@ pub enum ConstDecodeError {
   /// The buffer does not contain enough data to decode.
   #[error(transparent)]
   InsufficientData(#[from] InsufficientData),
+  /// The value is not encoded in its canonical (shortest) form.
+  #[error("value is not encoded in its canonical form")]
+  NonCanonical,
   /// A custom error message.
   #[error("{0}")]
   Other(&'static str),
@@ -242,7 +245,9 @@ pub enum ConstDecodeError {
 impl From<ConstDecodeError> for std::io::Error {
   fn from(err: ConstDecodeError) -> Self {
     match err {
-      ConstDecodeError::Overflow => std::io::Error::new(std::io::ErrorKind::InvalidData, err),
+      ConstDecodeError::Overflow | ConstDecodeError::NonCanonical => {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, err)
+      }
       ConstDecodeError::InsufficientData(err) => {
         std::io::Error::new(std::io::ErrorKind::UnexpectedEof, err)
       }
@@ -275,6 +280,13 @@ impl ConstDecodeError {
     Self::InsufficientData(InsufficientData::with_required(required, available))
   }
 
+  /// Creates a new `ConstDecodeError::NonCanonical` indicating that the value is not encoded
+  /// in its canonical (shortest) form.
+  #[inline]
+  pub const fn non_canonical() -> Self {
+    Self::NonCanonical
+  }
+
   /// Creates a new `ConstDecodeError::Other` with the given message.
   #[inline]
   pub const fn other(msg: &'static str) -> Self {
@@ -302,6 +314,7 @@ impl ConstDecodeError {
     match self {
       Self::Overflow => DecodeError::Overflow,
       Self::InsufficientData(e) => DecodeError::InsufficientData(e),
+      Self::NonCanonical => DecodeError::NonCanonical,
       #[cfg(any(feature = "std", feature = "alloc"))]
       Self::Other(msg) => DecodeError::Other(std::borrow::Cow::Borrowed(msg)),
       #[cfg(not(any(feature = "std", feature = "alloc")))]
@@ -403,6 +416,9 @@ pub enum DecodeError {
   /// The buffer does not contain enough data to decode.
   #[error(transparent)]
   InsufficientData(#[from] InsufficientData),
+  /// The value is not encoded in its canonical (shortest) form.
+  #[error("value is not encoded in its canonical form")]
+  NonCanonical,
   /// A custom error message.
   #[error("{0}")]
   #[cfg(not(any(feature = "std", feature = "alloc")))]
@@ -418,7 +434,9 @@ pub enum DecodeError {
 impl From<DecodeError> for std::io::Error {
   fn from(err: DecodeError) -> Self {
     match err {
-      DecodeError::Overflow => std::io::Error::new(std::io::ErrorKind::InvalidData, err),
+      DecodeError::Overflow | DecodeError::NonCanonical => {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, err)
+      }
       DecodeError::InsufficientData(err) => {
         std::io::Error::new(std::io::ErrorKind::UnexpectedEof, err)
       }
@@ -432,6 +450,7 @@ impl From<ConstDecodeError> for DecodeError {
     match err {
       ConstDecodeError::Overflow => Self::Overflow,
       ConstDecodeError::InsufficientData(e) => Self::InsufficientData(e),
+      ConstDecodeError::NonCanonical => Self::NonCanonical,
       ConstDecodeError::Other(msg) => Self::other(msg),
     }
   }
@@ -461,6 +480,13 @@ impl DecodeError {
     Self::InsufficientData(InsufficientData::with_required(required, available))
   }
 
+  /// Creates a new `DecodeError::NonCanonical` indicating that the value is not encoded
+  /// in its canonical (shortest) form.
+  #[inline]
+  pub const fn non_canonical() -> Self {
+    Self::NonCanonical
+  }
+
   /// Creates a new `DecodeError::Other` with the given message.
   #[inline]
   #[cfg(not(any(feature = "std", feature = "alloc")))]
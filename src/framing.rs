@@ -0,0 +1,215 @@
+use core::num::NonZeroUsize;
+
+use super::{
+  ConstDecodeError, DecodeError, EncodeError, InsufficientData, decode_u64_varint,
+  encode_u64_varint_to, encoded_u64_varint_len,
+};
+
+/// The default maximum payload size accepted by a [`FrameDecoder`], 8 MiB.
+pub const DEFAULT_MAX_FRAME_SIZE: usize = 8 * 1024 * 1024;
+
+/// Returns the encoded length of a length-delimited frame carrying a payload of `payload_len` bytes.
+///
+/// The length saturates at `usize::MAX`.
+#[inline]
+pub const fn encoded_length_delimited_len(payload_len: usize) -> NonZeroUsize {
+  encoded_u64_varint_len(payload_len as u64).saturating_add(payload_len)
+}
+
+/// Encodes `payload` as a length-delimited frame: the payload length as a `u64` varint,
+/// followed by the payload itself.
+///
+/// Returns the number of bytes written to the buffer. Nothing is written if the
+/// buffer is too small to hold the whole frame.
+///
+/// ## Example
+///
+/// ```rust
+/// use varing::framing::{decode_length_delimited, encode_length_delimited};
+///
+/// let mut buf = [0u8; 16];
+/// let written = encode_length_delimited(b"hello", &mut buf).unwrap();
+/// assert_eq!(&buf[..written.get()], b"\x05hello");
+///
+/// let (consumed, payload) = decode_length_delimited(&buf).unwrap();
+/// assert_eq!(consumed, written);
+/// assert_eq!(payload, b"hello");
+/// ```
+pub fn encode_length_delimited(
+  payload: &[u8],
+  buf: &mut [u8],
+) -> Result<NonZeroUsize, EncodeError> {
+  let encoded_len = encoded_length_delimited_len(payload.len());
+  let buf_len = buf.len();
+  if buf_len < encoded_len.get() {
+    return Err(EncodeError::insufficient_space(encoded_len, buf_len));
+  }
+
+  let prefix_len = encode_u64_varint_to(payload.len() as u64, buf)?.get();
+  buf[prefix_len..encoded_len.get()].copy_from_slice(payload);
+  Ok(encoded_len)
+}
+
+/// Decodes a length-delimited frame from the beginning of the buffer.
+///
+/// Returns the number of bytes consumed (length prefix and payload) and the payload,
+/// borrowed from the buffer. Bytes after the frame are left untouched.
+///
+/// This function does not limit the payload size beyond the size of the buffer, use a
+/// [`FrameDecoder`] to reject oversized frames before their payload has been received.
+///
+/// ## Errors
+///
+/// - [`DecodeError::InsufficientData`] if the buffer ends before the frame does. Once the
+///   length prefix is complete, the error carries the total length of the frame as
+///   [`InsufficientData::required`].
+/// - [`DecodeError::Overflow`] if the length prefix is not a valid `u64` varint.
+#[inline]
+pub fn decode_length_delimited(buf: &[u8]) -> Result<(NonZeroUsize, &[u8]), DecodeError> {
+  let (prefix_len, frame_len) = decode_header(buf, usize::MAX)?;
+  split_frame(buf, prefix_len, frame_len)
+}
+
+/// An incremental decoder for length-delimited frames.
+///
+/// Feed [`decode`](FrameDecoder::decode) the bytes received so far, always starting at
+/// the beginning of the current frame. While the frame is incomplete it returns
+/// [`DecodeError::InsufficientData`], and once the length prefix has been read the error
+/// reports the total frame length as [`InsufficientData::required`], so the caller knows
+/// exactly how much more to read. The length prefix is only parsed once per frame.
+///
+/// Frames whose payload is larger than the [maximum frame size](FrameDecoder::max_frame_size)
+/// are rejected as soon as their length prefix has been read.
+///
+/// ## Example
+///
+/// ```rust
+/// use varing::{DecodeError, framing::{FrameDecoder, encode_length_delimited}};
+///
+/// let mut frame = [0u8; 16];
+/// let len = encode_length_delimited(b"hello", &mut frame).unwrap().get();
+///
+/// let mut decoder = FrameDecoder::new();
+/// let Err(DecodeError::InsufficientData(e)) = decoder.decode(&frame[..3]) else {
+///   panic!("frame is incomplete");
+/// };
+/// assert_eq!(e.required().map(|n| n.get()), Some(len));
+///
+/// let (consumed, payload) = decoder.decode(&frame[..len]).unwrap();
+/// assert_eq!(consumed.get(), len);
+/// assert_eq!(payload, b"hello");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FrameDecoder {
+  max_frame_size: usize,
+  /// The length prefix and payload length of the current frame, once known.
+  header: Option<(NonZeroUsize, usize)>,
+}
+
+impl Default for FrameDecoder {
+  #[inline]
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl FrameDecoder {
+  /// Creates a decoder accepting payloads of at most [`DEFAULT_MAX_FRAME_SIZE`] bytes.
+  #[inline]
+  pub const fn new() -> Self {
+    Self::with_max_frame_size(DEFAULT_MAX_FRAME_SIZE)
+  }
+
+  /// Creates a decoder accepting payloads of at most `max_frame_size` bytes.
+  #[inline]
+  pub const fn with_max_frame_size(max_frame_size: usize) -> Self {
+    Self {
+      max_frame_size,
+      header: None,
+    }
+  }
+
+  /// Returns the maximum payload size accepted by the decoder.
+  #[inline]
+  pub const fn max_frame_size(&self) -> usize {
+    self.max_frame_size
+  }
+
+  /// Sets the maximum payload size accepted by the decoder.
+  #[inline]
+  pub const fn set_max_frame_size(&mut self, max_frame_size: usize) -> &mut Self {
+    self.max_frame_size = max_frame_size;
+    self
+  }
+
+  /// Forgets the partially decoded frame, if any.
+  ///
+  /// Call this when the buffer passed to [`decode`](FrameDecoder::decode) no longer
+  /// starts at the current frame, e.g. after an error was handled by skipping bytes.
+  #[inline]
+  pub const fn reset(&mut self) {
+    self.header = None;
+  }
+
+  /// Decodes a frame from the beginning of the buffer.
+  ///
+  /// Returns the number of bytes consumed (length prefix and payload) and the payload,
+  /// borrowed from the buffer. The next call is expected to start at the next frame.
+  ///
+  /// ## Errors
+  ///
+  /// - [`DecodeError::InsufficientData`] if the frame is incomplete. Once the length
+  ///   prefix is complete, the error carries the total length of the frame as
+  ///   [`InsufficientData::required`].
+  /// - [`DecodeError::Overflow`] if the length prefix is not a valid `u64` varint.
+  /// - [`DecodeError::Other`] if the payload is larger than the maximum frame size.
+  pub fn decode<'a>(&mut self, buf: &'a [u8]) -> Result<(NonZeroUsize, &'a [u8]), DecodeError> {
+    let (prefix_len, frame_len) = match self.header {
+      Some(header) => header,
+      None => {
+        let header = decode_header(buf, self.max_frame_size)?;
+        self.header = Some(header);
+        header
+      }
+    };
+
+    let frame = split_frame(buf, prefix_len, frame_len)?;
+    self.header = None;
+    Ok(frame)
+  }
+}
+
+/// Decodes the length prefix, returning its length and the payload length.
+#[inline]
+fn decode_header(buf: &[u8], max_frame_size: usize) -> Result<(NonZeroUsize, usize), DecodeError> {
+  let (prefix_len, frame_len) = match decode_u64_varint(buf) {
+    Ok(header) => header,
+    Err(ConstDecodeError::InsufficientData(_)) => {
+      return Err(DecodeError::insufficient_data(buf.len()));
+    }
+    Err(e) => return Err(e.into()),
+  };
+
+  match usize::try_from(frame_len) {
+    Ok(frame_len) if frame_len <= max_frame_size => Ok((prefix_len, frame_len)),
+    _ => Err(DecodeError::other(
+      "frame length exceeds the maximum frame size",
+    )),
+  }
+}
+
+#[inline]
+fn split_frame(
+  buf: &[u8],
+  prefix_len: NonZeroUsize,
+  frame_len: usize,
+) -> Result<(NonZeroUsize, &[u8]), DecodeError> {
+  let required = prefix_len.saturating_add(frame_len);
+  if buf.len() < required.get() {
+    return Err(InsufficientData::with_required(required, buf.len()).into());
+  }
+  Ok((required, &buf[prefix_len.get()..required.get()]))
+}
+
+#[cfg(all(test, any(feature = "std", feature = "alloc")))]
+mod tests;
@@ -0,0 +1,133 @@
+use super::*;
+
+use std::{vec, vec::Vec};
+
+use quickcheck_macros::quickcheck;
+
+fn frame(payload: &[u8]) -> Vec<u8> {
+  let mut buf = vec![0; encoded_length_delimited_len(payload.len()).get()];
+  let written = encode_length_delimited(payload, &mut buf).unwrap();
+  assert_eq!(written.get(), buf.len());
+  buf
+}
+
+#[quickcheck]
+fn encode_decode_length_delimited(payload: Vec<u8>, trailing: Vec<u8>) -> bool {
+  let mut buf = frame(&payload);
+  let len = buf.len();
+  buf.extend_from_slice(&trailing);
+
+  matches!(
+    decode_length_delimited(&buf),
+    Ok((consumed, decoded)) if consumed.get() == len && decoded == payload.as_slice()
+  )
+}
+
+#[quickcheck]
+fn frame_decoder_byte_by_byte(payloads: Vec<Vec<u8>>) -> bool {
+  let stream = payloads.iter().flat_map(|p| frame(p)).collect::<Vec<_>>();
+
+  let mut decoder = FrameDecoder::new();
+  let mut start = 0;
+  let mut decoded = Vec::new();
+  for end in 0..=stream.len() {
+    match decoder.decode(&stream[start..end]) {
+      Ok((consumed, payload)) => {
+        decoded.push(payload.to_vec());
+        start += consumed.get();
+      }
+      Err(DecodeError::InsufficientData(_)) => {}
+      Err(_) => return false,
+    }
+  }
+
+  start == stream.len() && decoded == payloads
+}
+
+#[test]
+fn empty_payload() {
+  let buf = frame(&[]);
+  assert_eq!(buf, [0x00]);
+  let (consumed, payload) = decode_length_delimited(&buf).unwrap();
+  assert_eq!(consumed.get(), 1);
+  assert!(payload.is_empty());
+}
+
+#[test]
+fn insufficient_data_reports_required() {
+  let buf = frame(&[0xAA; 300]);
+  assert_eq!(buf.len(), 302);
+
+  // The length prefix itself is incomplete, so the frame length is unknown.
+  assert_eq!(
+    decode_length_delimited(&buf[..1]),
+    Err(DecodeError::insufficient_data(1))
+  );
+  assert_eq!(
+    decode_length_delimited(&[]),
+    Err(DecodeError::insufficient_data(0))
+  );
+
+  let mut decoder = FrameDecoder::new();
+  for available in [2, 100, 301] {
+    assert_eq!(
+      decoder.decode(&buf[..available]),
+      Err(DecodeError::insufficient_data_with_required(
+        NonZeroUsize::new(302).unwrap(),
+        available
+      ))
+    );
+  }
+  assert_eq!(decoder.decode(&buf).unwrap().0.get(), 302);
+}
+
+#[test]
+fn encode_insufficient_space() {
+  let mut buf = [0xFFu8; 5];
+  assert_eq!(
+    encode_length_delimited(b"hello", &mut buf),
+    Err(EncodeError::insufficient_space(
+      NonZeroUsize::new(6).unwrap(),
+      5
+    ))
+  );
+  assert_eq!(buf, [0xFF; 5]);
+}
+
+#[test]
+fn max_frame_size() {
+  let buf = frame(&[0; 17]);
+
+  let mut decoder = FrameDecoder::with_max_frame_size(16);
+  assert_eq!(decoder.max_frame_size(), 16);
+  // Rejected from the length prefix alone.
+  assert!(matches!(
+    decoder.decode(&buf[..1]),
+    Err(DecodeError::Other(_))
+  ));
+
+  decoder.set_max_frame_size(17);
+  assert_eq!(decoder.decode(&buf).unwrap().1.len(), 17);
+}
+
+#[test]
+fn invalid_length_prefix() {
+  assert_eq!(
+    decode_length_delimited(&[0xFF; 11]),
+    Err(DecodeError::Overflow)
+  );
+
+  let mut decoder = FrameDecoder::new();
+  assert_eq!(decoder.decode(&[0xFF; 11]), Err(DecodeError::Overflow));
+}
+
+#[test]
+fn reset_discards_header() {
+  let buf = frame(&[1, 2, 3]);
+  let mut decoder = FrameDecoder::new();
+  assert!(decoder.decode(&buf[..2]).is_err());
+
+  decoder.reset();
+  let other = frame(&[4]);
+  assert_eq!(decoder.decode(&other).unwrap().1, &[4]);
+}
@@ -0,0 +1,4 @@
+#[cfg(feature = "futures-io_0_3")]
+mod v03;
+#[cfg(feature = "futures-io_0_3")]
+pub use v03::*;
@@ -0,0 +1,186 @@
+use core::{
+  future::Future,
+  num::NonZeroUsize,
+  pin::Pin,
+  task::{Context, Poll, ready},
+};
+use std::io;
+
+use futures_io_0_3::{AsyncRead, AsyncWrite};
+
+use crate::{
+  DecodeError, Varint,
+  scratch::{PartialRead, Scratch},
+};
+
+/// Extension trait for reading varints from any [`futures::io::AsyncRead`](AsyncRead).
+///
+/// The reader is never asked for more bytes than [`Varint::required_len`] reports the
+/// value still needs, so no byte past the end of the varint is ever read. Wrap
+/// unbuffered sources (sockets, files) in a
+/// [`futures::io::BufReader`](https://docs.rs/futures/latest/futures/io/struct.BufReader.html)
+/// to avoid a syscall per byte.
+///
+/// ## Cancel safety
+///
+/// [`read_varint`](AsyncReadVarint::read_varint) is **not** cancellation safe. The
+/// bytes of a partially read varint are owned by the returned future, so if it is
+/// dropped before completion (e.g. it lost a `futures::select!` race) those bytes are
+/// lost and the stream is left in the middle of a value.
+///
+/// ## Example
+///
+/// ```rust
+/// use futures::io::Cursor;
+/// use varing::futures_io::{AsyncReadVarint, AsyncWriteVarint};
+///
+/// # futures::executor::block_on(async {
+/// let mut buf = Cursor::new(Vec::new());
+/// buf.write_varint(&300u32).await.unwrap();
+///
+/// buf.set_position(0);
+/// assert_eq!(buf.read_varint::<u32>().await.unwrap(), 300);
+/// # });
+/// ```
+pub trait AsyncReadVarint: AsyncRead {
+  /// Reads a single varint encoded value from the reader.
+  ///
+  /// Resolves to an [`UnexpectedEof`](io::ErrorKind::UnexpectedEof) error if the reader
+  /// ends before a complete value was read, and to an [`InvalidData`](io::ErrorKind::InvalidData)
+  /// error if no valid value can be decoded within [`Varint::MAX_ENCODED_LEN`] bytes.
+  #[inline]
+  fn read_varint<V: Varint>(&mut self) -> ReadVarintFuture<'_, Self, V>
+  where
+    Self: Unpin,
+  {
+    ReadVarintFuture {
+      reader: self,
+      partial: PartialRead::new(),
+    }
+  }
+}
+
+impl<R: AsyncRead + ?Sized> AsyncReadVarint for R {}
+
+/// Extension trait for writing varints to any [`futures::io::AsyncWrite`](AsyncWrite).
+///
+/// ## Cancel safety
+///
+/// [`write_varint`](AsyncWriteVarint::write_varint) is **not** cancellation safe. If the
+/// returned future is dropped before completion, an unknown prefix of the encoded
+/// value may already have been written.
+pub trait AsyncWriteVarint: AsyncWrite {
+  /// Encodes the value as a varint and writes it to the writer.
+  ///
+  /// Resolves to the number of bytes written to the writer.
+  #[inline]
+  fn write_varint<V: Varint + ?Sized>(&mut self, value: &V) -> WriteVarintFuture<'_, Self>
+  where
+    Self: Unpin,
+  {
+    let (buf, error) = match Scratch::encode(value) {
+      Ok(buf) => (buf, None),
+      Err(e) => (Scratch::new(0), Some(e.into())),
+    };
+    WriteVarintFuture {
+      writer: self,
+      buf,
+      error,
+      written: 0,
+    }
+  }
+}
+
+impl<W: AsyncWrite + ?Sized> AsyncWriteVarint for W {}
+
+/// Future returned by [`AsyncReadVarint::read_varint`].
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct ReadVarintFuture<'a, R: ?Sized, V> {
+  reader: &'a mut R,
+  partial: PartialRead<V>,
+}
+
+impl<R: ?Sized, V> core::fmt::Debug for ReadVarintFuture<'_, R, V> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    f.debug_struct("ReadVarintFuture")
+      .field("filled", &self.partial.filled())
+      .finish_non_exhaustive()
+  }
+}
+
+impl<R, V> Future for ReadVarintFuture<'_, R, V>
+where
+  R: AsyncRead + Unpin + ?Sized,
+  V: Varint,
+{
+  type Output = io::Result<V>;
+
+  fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+    let this = self.get_mut();
+    loop {
+      let n = match ready!(Pin::new(&mut *this.reader).poll_read(cx, this.partial.unfilled())) {
+        Ok(0) => {
+          let filled = this.partial.filled();
+          return Poll::Ready(Err(DecodeError::insufficient_data(filled).into()));
+        }
+        Ok(n) => n,
+        Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+        Err(e) => return Poll::Ready(Err(e)),
+      };
+
+      if let Some(res) = this.partial.advance(n) {
+        return Poll::Ready(res.map(|(_, value)| value).map_err(Into::into));
+      }
+    }
+  }
+}
+
+/// Future returned by [`AsyncWriteVarint::write_varint`].
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct WriteVarintFuture<'a, W: ?Sized> {
+  writer: &'a mut W,
+  buf: Scratch,
+  error: Option<io::Error>,
+  written: usize,
+}
+
+impl<W: ?Sized> core::fmt::Debug for WriteVarintFuture<'_, W> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    f.debug_struct("WriteVarintFuture")
+      .field("written", &self.written)
+      .finish_non_exhaustive()
+  }
+}
+
+impl<W> Future for WriteVarintFuture<'_, W>
+where
+  W: AsyncWrite + Unpin + ?Sized,
+{
+  type Output = io::Result<NonZeroUsize>;
+
+  fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+    let this = self.get_mut();
+    if let Some(e) = this.error.take() {
+      return Poll::Ready(Err(e));
+    }
+
+    let buf = &this.buf;
+    while this.written < buf.len() {
+      match ready!(Pin::new(&mut *this.writer).poll_write(cx, &buf[this.written..])) {
+        Ok(0) => return Poll::Ready(Err(io::ErrorKind::WriteZero.into())),
+        Ok(n) => this.written += n,
+        Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+        Err(e) => return Poll::Ready(Err(e)),
+      }
+    }
+
+    // Only empty after the encoding error has already been returned.
+    Poll::Ready(
+      NonZeroUsize::new(buf.len())
+        .ok_or_else(|| io::Error::other("`WriteVarintFuture` polled after completion")),
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,139 @@
+use super::*;
+
+use core::time::Duration;
+use std::vec::Vec;
+
+use futures::{FutureExt, executor::block_on, io::Cursor};
+use quickcheck_macros::quickcheck;
+
+/// A reader that is pending (and interrupted) before every byte it hands out, to
+/// exercise suspension in the middle of a value.
+struct Trickle<'a> {
+  data: &'a [u8],
+  step: u8,
+}
+
+impl AsyncRead for Trickle<'_> {
+  fn poll_read(
+    mut self: Pin<&mut Self>,
+    cx: &mut Context<'_>,
+    buf: &mut [u8],
+  ) -> Poll<io::Result<usize>> {
+    self.step = (self.step + 1) % 3;
+    match self.step {
+      1 => {
+        cx.waker().wake_by_ref();
+        Poll::Pending
+      }
+      2 => Poll::Ready(Err(io::ErrorKind::Interrupted.into())),
+      _ => match self.data.split_first() {
+        Some((byte, rest)) if !buf.is_empty() => {
+          buf[0] = *byte;
+          self.data = rest;
+          Poll::Ready(Ok(1))
+        }
+        _ => Poll::Ready(Ok(0)),
+      },
+    }
+  }
+}
+
+macro_rules! roundtrip {
+  ($($ty:ty), +$(,)?) => {
+    $(
+      paste::paste! {
+        #[quickcheck]
+        fn [< read_write_ $ty:snake >](values: Vec<$ty>) -> bool {
+          block_on(async {
+            let mut buf = Cursor::new(Vec::new());
+            for value in &values {
+              let written = buf.write_varint(value).await.unwrap();
+              if written != value.encoded_len() {
+                return false;
+              }
+            }
+
+            let buf = buf.into_inner();
+            let mut reader = Trickle { data: &buf, step: 0 };
+            for value in &values {
+              match reader.read_varint::<$ty>().await {
+                Ok(decoded) if decoded == *value => {}
+                _ => return false,
+              }
+            }
+            reader.data.is_empty()
+          })
+        }
+      }
+    )*
+  };
+}
+
+roundtrip!(
+  u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, bool, char, Duration
+);
+
+#[test]
+fn read_unexpected_eof() {
+  let mut reader = Trickle {
+    data: &[0x80, 0x80],
+    step: 0,
+  };
+  let err = block_on(reader.read_varint::<u64>()).unwrap_err();
+  assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn read_overflow() {
+  let mut reader = Trickle {
+    data: &[0x80; 16],
+    step: 0,
+  };
+  let err = block_on(reader.read_varint::<u32>()).unwrap_err();
+  assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+  assert_eq!(reader.data.len(), 16 - u32::MAX_ENCODED_LEN.get());
+}
+
+/// A reader whose data has only partly arrived: it is pending, instead of at EOF, once
+/// the data runs out.
+struct Arriving {
+  data: Vec<u8>,
+}
+
+impl AsyncRead for Arriving {
+  fn poll_read(
+    mut self: Pin<&mut Self>,
+    _: &mut Context<'_>,
+    buf: &mut [u8],
+  ) -> Poll<io::Result<usize>> {
+    if self.data.is_empty() {
+      return Poll::Pending;
+    }
+
+    let n = buf.len().min(self.data.len());
+    buf[..n].copy_from_slice(&self.data[..n]);
+    self.data.drain(..n);
+    Poll::Ready(Ok(n))
+  }
+}
+
+#[test]
+fn read_cancelled_mid_value() {
+  // 300 encodes to [0xAC, 0x02], only the first byte has arrived.
+  let mut reader = Arriving { data: vec![0xAC] };
+  assert!(reader.read_varint::<u32>().now_or_never().is_none());
+
+  // The dropped future took the first byte with it, the stream resumes mid-value.
+  assert!(reader.data.is_empty());
+  reader.data.extend([0x02, 0x07]);
+  assert_eq!(block_on(reader.read_varint::<u32>()).unwrap(), 2);
+  assert_eq!(block_on(reader.read_varint::<u8>()).unwrap(), 7);
+}
+
+#[test]
+fn write_zero() {
+  let mut buf = [0u8; 1];
+  let mut writer = Cursor::new(&mut buf[..]);
+  let err = block_on(writer.write_varint(&300u32)).unwrap_err();
+  assert_eq!(err.kind(), io::ErrorKind::WriteZero);
+}
@@ -27,6 +27,34 @@ macro_rules! fuzzy {
       )*
     }
   };
+  (@canonical ($($ty:ident), +$(,)?)) => {
+    paste::paste! {
+      $(
+        #[quickcheck_macros::quickcheck]
+        fn [< fuzzy_ $ty _canonical >](value: $ty) -> bool {
+          let encoded = [< encode_ $ty _varint >](value);
+          match [< decode_ $ty _varint_canonical >](&encoded) {
+            Ok((bytes_read, decoded)) if decoded == value && bytes_read.get() == encoded.len() => {}
+            _ => return false,
+          }
+
+          // Pad the encoding with a redundant zero continuation byte.
+          let len = encoded.len();
+          if len == <$ty>::MAX_ENCODED_LEN.get() {
+            return true;
+          }
+          let mut padded = [0u8; 32];
+          padded[..len].copy_from_slice(&encoded);
+          padded[len - 1] |= 0x80;
+          let padded = &padded[..len + 1];
+
+          matches!([< decode_ $ty _varint >](padded), Ok((_, decoded)) if decoded == value)
+            && matches!([< decode_ $ty _varint_canonical >](padded), Err(ConstDecodeError::NonCanonical))
+            && matches!(<$ty>::decode_canonical(padded), Err(DecodeError::NonCanonical))
+        }
+      )*
+    }
+  };
   (@floats_varing ($($ty:ty $( => $suffix:ident)? ), +$(,)?)) => {
     paste::paste! {
       $(
@@ -246,9 +274,10 @@ macro_rules! fuzzy {
   };
 }
 
-fuzzy!(@varing(u8 => varint, u16 => varint, u32 => varint, u64 => varint, u128 => varint, i8 => varint, i16 => varint, i32 => varint, i64 => varint, i128 => varint));
+fuzzy!(@varing(u8 => varint, u16 => varint, u32 => varint, u64 => varint, u128 => varint, usize => varint, i8 => varint, i16 => varint, i32 => varint, i64 => varint, i128 => varint, isize => varint));
 fuzzy!(@floats_varing(f32 => varint, f64 => varint));
-fuzzy!(@varint(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, bool));
+fuzzy!(@canonical(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize));
+fuzzy!(@varint(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, bool));
 fuzzy!(@floats_varint(f32, f64));
 
 #[cfg(feature = "std")]
@@ -257,7 +286,7 @@ mod with_std {
 
   use std::{vec, vec::Vec};
 
-  fuzzy!(@sequence (u8, u16, u32, u64, u128, i8, i16, i32, i64, i128));
+  fuzzy!(@sequence (u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize));
   fuzzy!(@floats_sequence(f32, f64));
 
   #[quickcheck_macros::quickcheck]
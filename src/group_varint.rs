@@ -0,0 +1,295 @@
+use core::num::NonZeroUsize;
+
+use super::{ConstDecodeError, ConstEncodeError, DecodeError};
+
+/// The number of values in a group.
+const GROUP_SIZE: usize = 4;
+
+/// The maximum encoded length of a group, the tag byte and four 4-byte values.
+const MAX_GROUP_LEN: usize = 1 + 4 * GROUP_SIZE;
+
+/// The masks keeping the low `n + 1` bytes of a little-endian `u32`.
+const MASKS: [u32; 4] = [0xFF, 0xFFFF, 0xFF_FFFF, 0xFFFF_FFFF];
+
+/// Returns the number of bytes of `value` in group varint format, in `1..=4`.
+#[inline]
+const fn value_len(value: u32) -> usize {
+  match value {
+    0..=0xFF => 1,
+    0x100..=0xFFFF => 2,
+    0x1_0000..=0xFF_FFFF => 3,
+    _ => 4,
+  }
+}
+
+/// Returns the encoded length of a sequence of `u32` values in group varint format.
+#[inline]
+pub const fn encoded_u32_group_sequence_len(sequence: &[u32]) -> usize {
+  let mut total = sequence.len().div_ceil(GROUP_SIZE);
+  let mut i = 0;
+  while i < sequence.len() {
+    total += value_len(sequence[i]);
+    i += 1;
+  }
+  total
+}
+
+/// Encodes a sequence of `u32` values in group varint format, and writes it to the buffer.
+///
+/// Values are written in groups of four: a tag byte holding the byte length minus one of
+/// each value in two bits, starting from the least significant bits, followed by the values
+/// in little-endian order using only that many bytes. The last group holds the remaining
+/// one to four values, and the tag bits of its missing values are zero.
+///
+/// The number of values is not part of the encoding, it has to be known to decode the
+/// sequence, e.g. by storing it in front of the sequence.
+///
+/// Returns the number of bytes written. Nothing is written if the buffer is too small.
+///
+/// ## Example
+///
+/// ```rust
+/// use varing::group_varint::{decode_u32_group_sequence, encode_u32_group_sequence_to};
+///
+/// let values = [1, 300, 70_000, u32::MAX, 7];
+/// let mut buf = [0u8; 16];
+/// let written = encode_u32_group_sequence_to(&values, &mut buf).unwrap();
+/// assert_eq!(written, 13);
+/// assert_eq!(buf[0], 0b11_10_01_00);
+///
+/// let mut decoded = [0u32; 5];
+/// let read = decode_u32_group_sequence(&buf, &mut decoded).unwrap();
+/// assert_eq!(read, written);
+/// assert_eq!(decoded, values);
+/// ```
+pub const fn encode_u32_group_sequence_to(
+  sequence: &[u32],
+  buf: &mut [u8],
+) -> Result<usize, ConstEncodeError> {
+  let encoded_len = encoded_u32_group_sequence_len(sequence);
+  if buf.len() < encoded_len {
+    // Safety: the buffer is shorter than `encoded_len`, so it is non-zero
+    return Err(ConstEncodeError::insufficient_space(
+      unsafe { NonZeroUsize::new_unchecked(encoded_len) },
+      buf.len(),
+    ));
+  }
+
+  let mut offset = 0;
+  let mut idx = 0;
+  while idx < sequence.len() {
+    let tag_offset = offset;
+    let mut tag = 0u8;
+    offset += 1;
+
+    let mut i = 0;
+    while i < GROUP_SIZE && idx < sequence.len() {
+      let value = sequence[idx];
+      let len = value_len(value);
+      tag |= ((len - 1) as u8) << (2 * i);
+
+      let bytes = value.to_le_bytes();
+      let mut j = 0;
+      while j < len {
+        buf[offset + j] = bytes[j];
+        j += 1;
+      }
+      offset += len;
+      i += 1;
+      idx += 1;
+    }
+    buf[tag_offset] = tag;
+  }
+  Ok(offset)
+}
+
+/// Decodes a sequence of `u32` values in group varint format from the buffer, filling `out`.
+///
+/// Exactly `out.len()` values are decoded, see [`encode_u32_group_sequence_to`] for the format.
+///
+/// Returns the number of bytes read if successful.
+pub const fn decode_u32_group_sequence(
+  buf: &[u8],
+  out: &mut [u32],
+) -> Result<usize, ConstDecodeError> {
+  let mut group = [0u32; GROUP_SIZE];
+  let mut offset = 0;
+  let mut idx = 0;
+  while idx < out.len() {
+    let remaining = out.len() - idx;
+    let count = if remaining < GROUP_SIZE {
+      remaining
+    } else {
+      GROUP_SIZE
+    };
+
+    offset += match decode_group(buf, offset, count, &mut group) {
+      Ok(read) => read,
+      Err(e) => return Err(e),
+    };
+
+    let mut i = 0;
+    while i < count {
+      out[idx + i] = group[i];
+      i += 1;
+    }
+    idx += count;
+  }
+  Ok(offset)
+}
+
+/// Decodes a group of `count` values from `buf[offset..]` into `out`, returning the
+/// number of bytes read.
+#[inline]
+const fn decode_group(
+  buf: &[u8],
+  offset: usize,
+  count: usize,
+  out: &mut [u32; GROUP_SIZE],
+) -> Result<usize, ConstDecodeError> {
+  let (_, src) = buf.split_at(offset);
+  if src.is_empty() {
+    return Err(ConstDecodeError::insufficient_data(buf.len()));
+  }
+
+  let tag = src[0];
+  // Fast path: a full group with enough bytes after it to load every value as a `u32`.
+  if count == GROUP_SIZE && src.len() >= MAX_GROUP_LEN {
+    let mut pos = 1;
+    let mut i = 0;
+    while i < GROUP_SIZE {
+      let len_bits = ((tag >> (2 * i)) & 0b11) as usize;
+      let (_, rest) = src.split_at(pos);
+      if let Some(bytes) = rest.first_chunk::<4>() {
+        out[i] = u32::from_le_bytes(*bytes) & MASKS[len_bits];
+      }
+      pos += len_bits + 1;
+      i += 1;
+    }
+    return Ok(pos);
+  }
+
+  if count < GROUP_SIZE && tag >> (2 * count) != 0 {
+    return Err(ConstDecodeError::other(
+      "group varint tag describes missing values",
+    ));
+  }
+
+  let mut pos = 1;
+  let mut i = 0;
+  while i < count {
+    let len = ((tag >> (2 * i)) & 0b11) as usize + 1;
+    if src.len() < pos + len {
+      return Err(ConstDecodeError::insufficient_data(buf.len()));
+    }
+
+    let mut value = 0u32;
+    let mut j = 0;
+    while j < len {
+      value |= (src[pos + j] as u32) << (8 * j);
+      j += 1;
+    }
+    out[i] = value;
+    pos += len;
+    i += 1;
+  }
+  Ok(pos)
+}
+
+/// An iterator that decodes a sequence of `u32` values in group varint format from a buffer.
+///
+/// Values are decoded a group at a time. See [`encode_u32_group_sequence_to`] for the format.
+///
+/// ## Example
+///
+/// ```rust
+/// use varing::group_varint::{GroupVarintDecoder, encode_u32_group_sequence_to};
+///
+/// let values = [1, 300, 70_000, u32::MAX, 7];
+/// let mut buf = [0u8; 16];
+/// let written = encode_u32_group_sequence_to(&values, &mut buf).unwrap();
+///
+/// let mut decoder = GroupVarintDecoder::new(&buf, values.len());
+/// for value in values {
+///   assert_eq!(decoder.next().unwrap().unwrap(), value);
+/// }
+/// assert!(decoder.next().is_none());
+/// assert_eq!(decoder.position(), written);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct GroupVarintDecoder<'a> {
+  buf: &'a [u8],
+  offset: usize,
+  remaining: usize,
+  group: [u32; GROUP_SIZE],
+  group_len: usize,
+  group_idx: usize,
+}
+
+impl<'a> GroupVarintDecoder<'a> {
+  /// Creates a decoder for a sequence of `count` values at the beginning of `buf`.
+  #[inline]
+  pub const fn new(buf: &'a [u8], count: usize) -> Self {
+    Self {
+      buf,
+      offset: 0,
+      remaining: count,
+      group: [0; GROUP_SIZE],
+      group_len: 0,
+      group_idx: 0,
+    }
+  }
+
+  /// Returns the current position of the buffer, the end of the last decoded group.
+  #[inline]
+  pub const fn position(&self) -> usize {
+    self.offset
+  }
+
+  /// Returns the number of values left to decode.
+  #[inline]
+  pub const fn remaining(&self) -> usize {
+    self.remaining + self.group_len - self.group_idx
+  }
+}
+
+impl Iterator for GroupVarintDecoder<'_> {
+  type Item = Result<u32, DecodeError>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    if self.group_idx == self.group_len {
+      if self.remaining == 0 {
+        return None;
+      }
+
+      let count = self.remaining.min(GROUP_SIZE);
+      match decode_group(self.buf, self.offset, count, &mut self.group) {
+        Ok(read) => {
+          self.offset += read;
+          self.remaining -= count;
+          self.group_len = count;
+          self.group_idx = 0;
+        }
+        Err(e) => {
+          self.remaining = 0;
+          return Some(Err(e.into()));
+        }
+      }
+    }
+
+    let value = self.group[self.group_idx];
+    self.group_idx += 1;
+    Some(Ok(value))
+  }
+
+  #[inline]
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let remaining = self.remaining();
+    (remaining.min(1), Some(remaining))
+  }
+}
+
+impl core::iter::FusedIterator for GroupVarintDecoder<'_> {}
+
+#[cfg(all(test, feature = "std"))]
+mod tests;
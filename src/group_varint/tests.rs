@@ -0,0 +1,107 @@
+use super::*;
+
+use std::{vec, vec::Vec};
+
+use quickcheck_macros::quickcheck;
+
+/// Narrows every value to a random byte length, so all four lengths are exercised.
+fn narrow(values: Vec<(u32, u8)>) -> Vec<u32> {
+  values
+    .into_iter()
+    .map(|(value, shift)| value >> (8 * (shift % 4)))
+    .collect()
+}
+
+fn encode(values: &[u32]) -> Vec<u8> {
+  let mut buf = vec![0; encoded_u32_group_sequence_len(values)];
+  let written = encode_u32_group_sequence_to(values, &mut buf).unwrap();
+  assert_eq!(written, buf.len());
+  buf
+}
+
+#[quickcheck]
+fn group_sequence_roundtrip(values: Vec<(u32, u8)>, trailing: Vec<u8>) -> bool {
+  let values = narrow(values);
+  let mut buf = encode(&values);
+  let len = buf.len();
+  // Trailing bytes let the fast path kick in for the last groups too.
+  buf.extend_from_slice(&trailing);
+
+  let mut decoded = vec![0; values.len()];
+  decode_u32_group_sequence(&buf, &mut decoded) == Ok(len) && decoded == values
+}
+
+#[quickcheck]
+fn group_varint_decoder(values: Vec<(u32, u8)>) -> bool {
+  let values = narrow(values);
+  let buf = encode(&values);
+
+  let mut decoder = GroupVarintDecoder::new(&buf, values.len());
+  let decoded = decoder.by_ref().collect::<Result<Vec<_>, _>>().unwrap();
+  decoded == values && decoder.position() == buf.len() && decoder.remaining() == 0
+}
+
+#[quickcheck]
+fn group_sequence_insufficient_data(values: Vec<(u32, u8)>) -> bool {
+  let values = narrow(values);
+  let buf = encode(&values);
+  let mut decoded = vec![0; values.len()];
+  (0..buf.len()).all(|available| {
+    matches!(
+      decode_u32_group_sequence(&buf[..available], &mut decoded),
+      Err(ConstDecodeError::InsufficientData(_))
+    ) && encode_u32_group_sequence_to(&values, &mut vec![0; available]).is_err()
+  })
+}
+
+#[test]
+fn known_vectors() {
+  let buf = encode(&[0xAA, 0xBBCC, 0xDDEEFF, 0x11223344]);
+  assert_eq!(
+    buf,
+    [
+      0b11_10_01_00,
+      0xAA,
+      0xCC,
+      0xBB,
+      0xFF,
+      0xEE,
+      0xDD,
+      0x44,
+      0x33,
+      0x22,
+      0x11
+    ]
+  );
+
+  assert_eq!(encode(&[]), [0u8; 0]);
+  assert_eq!(encode(&[0]), [0x00, 0x00]);
+  assert_eq!(encode(&[0x100, 0]), [0b00_01, 0x00, 0x01, 0x00]);
+  assert_eq!(encoded_u32_group_sequence_len(&[0; 5]), 7);
+}
+
+#[test]
+fn missing_values_in_tag() {
+  // The tag of a one-value group describes a second, 2-byte value.
+  let mut decoded = [0u32; 1];
+  assert_eq!(
+    decode_u32_group_sequence(&[0b01_00, 0x01, 0x02, 0x03], &mut decoded),
+    Err(ConstDecodeError::other(
+      "group varint tag describes missing values"
+    ))
+  );
+}
+
+#[test]
+fn decoder_stops_after_error() {
+  let buf = encode(&[1, 2, 3, 4, 5]);
+  let mut decoder = GroupVarintDecoder::new(&buf[..buf.len() - 1], 5);
+  for value in 1..=4 {
+    assert_eq!(decoder.next().unwrap().unwrap(), value);
+  }
+  assert!(matches!(
+    decoder.next(),
+    Some(Err(DecodeError::InsufficientData(_)))
+  ));
+  assert!(decoder.next().is_none());
+}
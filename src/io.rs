@@ -0,0 +1,91 @@
+use core::num::NonZeroUsize;
+
+use std::io;
+
+use super::{
+  DecodeError, Varint,
+  scratch::{PartialRead, Scratch},
+};
+
+/// Extension trait for reading varints from any [`std::io::Read`].
+///
+/// The reader is never asked for more bytes than [`Varint::required_len`] reports the
+/// value still needs, so no byte past the end of the varint is ever read. This may take
+/// a read per byte for LEB128 integers: wrap unbuffered sources (sockets, files) in a
+/// [`std::io::BufReader`] to avoid a syscall per byte.
+///
+/// ## Example
+///
+/// ```rust
+/// use varing::{ReadVarint, WriteVarint};
+///
+/// let mut buf = Vec::new();
+/// buf.write_varint(&300u32).unwrap();
+/// buf.write_varint(&-1i64).unwrap();
+///
+/// let mut reader = buf.as_slice();
+/// assert_eq!(reader.read_varint::<u32>().unwrap(), 300);
+/// assert_eq!(reader.read_varint::<i64>().unwrap(), -1);
+/// assert!(reader.is_empty());
+/// ```
+pub trait ReadVarint: io::Read {
+  /// Reads a single varint encoded value from the reader.
+  ///
+  /// Returns an [`UnexpectedEof`](io::ErrorKind::UnexpectedEof) error if the reader
+  /// ends before a complete value was read, and an [`InvalidData`](io::ErrorKind::InvalidData)
+  /// error if no valid value can be decoded within [`Varint::MAX_ENCODED_LEN`] bytes.
+  #[inline]
+  fn read_varint<V: Varint>(&mut self) -> io::Result<V> {
+    self.read_varint_with_len::<V>().map(|(_, value)| value)
+  }
+
+  /// Reads a single varint encoded value from the reader.
+  ///
+  /// Returns the number of bytes read from the reader and the decoded value if successful.
+  fn read_varint_with_len<V: Varint>(&mut self) -> io::Result<(NonZeroUsize, V)> {
+    let mut partial = PartialRead::<V>::new();
+    loop {
+      match self.read(partial.unfilled()) {
+        Ok(0) => return Err(DecodeError::insufficient_data(partial.filled()).into()),
+        Ok(n) => {
+          if let Some(res) = partial.advance(n) {
+            return res.map_err(Into::into);
+          }
+        }
+        Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+        Err(e) => return Err(e),
+      }
+    }
+  }
+}
+
+impl<R: io::Read + ?Sized> ReadVarint for R {}
+
+/// Extension trait for writing varints to any [`std::io::Write`].
+///
+/// ## Example
+///
+/// ```rust
+/// use varing::WriteVarint;
+///
+/// let mut buf = Vec::new();
+/// let written = buf.write_varint(&150u64).unwrap();
+/// assert_eq!(written.get(), 2);
+/// assert_eq!(buf, [0x96, 0x01]);
+/// ```
+pub trait WriteVarint: io::Write {
+  /// Encodes the value as a varint and writes it to the writer.
+  ///
+  /// Returns the number of bytes written to the writer.
+  fn write_varint<V: Varint + ?Sized>(&mut self, value: &V) -> io::Result<NonZeroUsize> {
+    let buf = Scratch::encode(value)?;
+    self.write_all(&buf)?;
+    // Safety: `Varint::encode` never writes zero bytes.
+    Ok(unsafe { NonZeroUsize::new_unchecked(buf.len()) })
+  }
+}
+
+impl<W: io::Write + ?Sized> WriteVarint for W {}
+
+#[cfg(test)]
+mod tests;
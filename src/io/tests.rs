@@ -0,0 +1,155 @@
+use super::*;
+
+use core::time::Duration;
+use std::{io::Cursor, vec::Vec};
+
+use quickcheck_macros::quickcheck;
+
+/// A reader that hands out at most one byte per `read` call and is interrupted
+/// before every byte, to exercise the retry path.
+struct Trickle<'a> {
+  data: &'a [u8],
+  interrupt: bool,
+}
+
+impl io::Read for Trickle<'_> {
+  fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+    self.interrupt = !self.interrupt;
+    if self.interrupt {
+      return Err(io::ErrorKind::Interrupted.into());
+    }
+
+    match self.data.split_first() {
+      Some((byte, rest)) if !buf.is_empty() => {
+        buf[0] = *byte;
+        self.data = rest;
+        Ok(1)
+      }
+      _ => Ok(0),
+    }
+  }
+}
+
+macro_rules! roundtrip {
+  ($($ty:ty), +$(,)?) => {
+    $(
+      paste::paste! {
+        #[quickcheck]
+        fn [< read_write_ $ty:snake >](values: Vec<$ty>) -> bool {
+          let mut buf = Vec::new();
+          for value in &values {
+            let written = buf.write_varint(value).unwrap();
+            if written != value.encoded_len() {
+              return false;
+            }
+          }
+
+          let mut reader = Trickle { data: &buf, interrupt: false };
+          for value in &values {
+            match reader.read_varint::<$ty>() {
+              Ok(decoded) if decoded == *value => {}
+              _ => return false,
+            }
+          }
+          reader.data.is_empty()
+        }
+      }
+    )*
+  };
+}
+
+roundtrip!(
+  u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, bool, char, Duration
+);
+
+#[test]
+fn read_does_not_consume_past_value() {
+  let mut buf = Vec::new();
+  buf.write_varint(&300u32).unwrap();
+  buf.push(0xAA);
+
+  let mut reader = Cursor::new(buf);
+  let (read, value) = reader.read_varint_with_len::<u32>().unwrap();
+  assert_eq!(value, 300);
+  assert_eq!(read.get(), 2);
+  assert_eq!(reader.position(), 2);
+}
+
+std::thread_local! {
+  static DECODES: core::cell::Cell<usize> = const { core::cell::Cell::new(0) };
+}
+
+/// A `u64` which counts the calls to its `decode`.
+#[derive(Debug, PartialEq)]
+struct Counted(u64);
+
+impl Varint for Counted {
+  const MIN_ENCODED_LEN: NonZeroUsize = u64::MIN_ENCODED_LEN;
+  const MAX_ENCODED_LEN: NonZeroUsize = u64::MAX_ENCODED_LEN;
+
+  fn encoded_len(&self) -> NonZeroUsize {
+    self.0.encoded_len()
+  }
+
+  fn encode(&self, buf: &mut [u8]) -> Result<NonZeroUsize, crate::EncodeError> {
+    self.0.encode(buf)
+  }
+
+  fn decode(buf: &[u8]) -> Result<(NonZeroUsize, Self), DecodeError> {
+    DECODES.with(|decodes| decodes.set(decodes.get() + 1));
+    u64::decode(buf).map(|(read, value)| (read, Self(value)))
+  }
+
+  fn required_len(buf: &[u8]) -> Result<NonZeroUsize, DecodeError> {
+    u64::required_len(buf)
+  }
+}
+
+#[test]
+fn read_decodes_once() {
+  let mut buf = Vec::new();
+  buf.write_varint(&u64::MAX).unwrap();
+
+  DECODES.with(|decodes| decodes.set(0));
+  let mut reader = Trickle {
+    data: &buf,
+    interrupt: false,
+  };
+  assert_eq!(reader.read_varint::<Counted>().unwrap(), Counted(u64::MAX));
+  assert_eq!(DECODES.with(|decodes| decodes.get()), 1);
+}
+
+#[test]
+fn read_unexpected_eof() {
+  let mut reader: &[u8] = &[];
+  let err = reader.read_varint::<u64>().unwrap_err();
+  assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+
+  let mut reader: &[u8] = &[0x80, 0x80];
+  let err = reader.read_varint::<u64>().unwrap_err();
+  assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn read_overflow() {
+  let mut reader: &[u8] = &[0x80; 16];
+  let err = reader.read_varint::<u32>().unwrap_err();
+  assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+  // Stops after `MAX_ENCODED_LEN` bytes at most.
+  assert_eq!(reader.len(), 16 - u32::MAX_ENCODED_LEN.get());
+}
+
+#[test]
+fn read_invalid_value() {
+  let mut reader: &[u8] = &[0x02];
+  let err = reader.read_varint::<bool>().unwrap_err();
+  assert_eq!(err.kind(), io::ErrorKind::Other);
+}
+
+#[test]
+fn write_insufficient_space() {
+  let mut buf = [0u8; 1];
+  let mut writer = &mut buf[..];
+  let err = writer.write_varint(&300u32).unwrap_err();
+  assert_eq!(err.kind(), io::ErrorKind::WriteZero);
+}
@@ -15,15 +15,78 @@ use core::{num::NonZeroUsize, ops::RangeInclusive};
 pub use char::*;
 pub use duration::*;
 pub use error::*;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub use io::{ReadVarint, WriteVarint};
+pub use net::*;
+pub use non_zero::*;
 pub use primitives::*;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub use system_time::*;
+
+#[cfg(feature = "derive")]
+#[cfg_attr(docsrs, doc(cfg(feature = "derive")))]
+pub use varing_derive::Varint;
 
 /// Utilities for encoding and decoding LEB128 variable length integers.
 pub mod utils;
 
+/// Length-delimited framing of byte payloads, using a `u64` varint length prefix.
+pub mod framing;
+
+/// Protobuf wire-format field keys and field encoding helpers.
+///
+/// Protobuf only has 32- and 64-bit scalar types, so there are no `u128`/`i128` or
+/// `usize`/`isize` field helpers; encode those with their [`Varint`] impls instead.
+pub mod protobuf;
+
+/// Signed LEB128 encoding, the sign-extension based format used by DWARF and WebAssembly.
+pub mod sleb128;
+
+/// Prefix varint encoding, which stores the encoded length in the leading bits of the first byte.
+pub mod prefix;
+
+/// Order-preserving varint encoding, whose byte order matches the numeric order of the values.
+pub mod sortable;
+
+/// Group varint encoding of `u32` sequences, four values at a time behind a shared tag byte.
+pub mod group_varint;
+
+/// Stream VByte encoding of `u32` sequences, with SIMD accelerated decoding.
+pub mod stream_vbyte;
+
+/// Delta and delta-of-delta encoding of sequences, for sorted IDs and timestamps.
+pub mod delta;
+
+/// Length-prefixed `Varint` encoding of `Vec`, `String` and the other `alloc` collections.
+#[cfg(any(feature = "std", feature = "alloc"))]
+#[cfg_attr(docsrs, doc(cfg(any(feature = "std", feature = "alloc"))))]
+pub mod collections;
+
+/// Implementation details of the `Varint` derive macro, not public API.
+#[cfg(feature = "derive")]
+#[doc(hidden)]
+pub mod __private {
+  pub use super::derive::{
+    check_space, decode_field, encode_field, max, min, non_zero, required_field_len,
+  };
+}
+
+mod bytes;
 mod char;
+mod composite;
+mod derive;
 mod duration;
 mod error;
+#[cfg(feature = "std")]
+mod io;
+mod net;
 mod primitives;
+#[cfg(any(feature = "std", feature = "bytes_1"))]
+mod scratch;
+#[cfg(feature = "std")]
+mod system_time;
 
 // Safety: This is safe because 1 is non-zero.
 const NON_ZERO_USIZE_ONE: NonZeroUsize = NonZeroUsize::new(1).unwrap();
@@ -47,6 +110,7 @@ pub trait Varint {
   /// - For `u16` and `i16`, this is `3`.
   /// - For `u32` and `i32`, this is `5`.
   /// - For `u64` and `i64`, this is `10`.
+  /// - For `usize` and `isize`, this is `10` on every target.
   /// - For `u128` and `i128`, this is `19`.
   const MAX_ENCODED_LEN: NonZeroUsize;
 
@@ -57,6 +121,7 @@ pub trait Varint {
   /// - For `u16` and `i16`, this range is `1..=3`, representing possible encoded lengths of 1, 2, or 3 bytes.
   /// - For `u32` and `i32`, this range is `1..=5`, representing possible encoded lengths of 1, 2, 3, 4, or 5 bytes.
   /// - For `u64` and `i64`, this range is `1..=10`, representing possible encoded lengths of 1 to 10 bytes.
+  /// - For `usize` and `isize`, this range is `1..=10`, the same as `u64` and `i64`.
   /// - For `u128` and `i128`, this range is `1..=19`, representing possible encoded lengths of 1 to 19 bytes.
   const ENCODED_LEN_RANGE: RangeInclusive<NonZeroUsize> =
     Self::MIN_ENCODED_LEN..=Self::MAX_ENCODED_LEN;
@@ -76,6 +141,131 @@ pub trait Varint {
   fn decode(buf: &[u8]) -> Result<(NonZeroUsize, Self), DecodeError>
   where
     Self: Sized;
+
+  /// Decodes the value from the buffer, rejecting encodings that are not canonical.
+  ///
+  /// [`decode`](Varint::decode) accepts overlong (zero-padded) encodings, e.g. `[0x80, 0x00]`
+  /// for `0u32`, so two different byte strings may decode to the same value. This method
+  /// returns [`DecodeError::NonCanonical`] for any such encoding, which makes it suitable
+  /// for content-addressed data and signatures.
+  ///
+  /// The default implementation rejects a value which was read from more bytes than its
+  /// [`encoded_len`](Varint::encoded_len). Implementations whose encoding has other
+  /// sources of redundancy should override it.
+  ///
+  /// Returns the number of bytes read from the buffer and the decoded value if successful.
+  ///
+  /// ## Example
+  ///
+  /// ```rust
+  /// use varing::{DecodeError, Varint};
+  ///
+  /// assert_eq!(u32::decode(&[0x80, 0x00]).unwrap().1, 0);
+  /// assert_eq!(u32::decode_canonical(&[0x80, 0x00]), Err(DecodeError::NonCanonical));
+  /// assert_eq!(u32::decode_canonical(&[0x00]).unwrap().1, 0);
+  /// ```
+  fn decode_canonical(buf: &[u8]) -> Result<(NonZeroUsize, Self), DecodeError>
+  where
+    Self: Sized,
+  {
+    let (read, value) = Self::decode(buf)?;
+    if read != value.encoded_len() {
+      return Err(DecodeError::NonCanonical);
+    }
+    Ok((read, value))
+  }
+
+  /// Returns the number of bytes needed to decode the value at the start of the buffer.
+  ///
+  /// If the buffer holds the complete value, this is its exact encoded length. Otherwise it
+  /// is a lower bound greater than `buf.len()`: readers of non-contiguous sources use it to
+  /// know how many more bytes to gather before calling [`decode`](Varint::decode), without
+  /// reading past the end of the value.
+  ///
+  /// The default implementation calls [`decode`](Varint::decode) and asks for one more byte
+  /// than the buffer holds, or for the required length reported by [`InsufficientData`] if
+  /// it is larger. Implementations whose length can be found without decoding the value
+  /// (e.g. by scanning continuation bits or a length prefix) should override it.
+  ///
+  /// ## Example
+  ///
+  /// ```rust
+  /// use varing::Varint;
+  ///
+  /// // 300 encodes to [0xAC, 0x02].
+  /// assert_eq!(u32::required_len(&[0xAC, 0x02, 0x7F]).unwrap().get(), 2);
+  /// assert_eq!(u32::required_len(&[0xAC]).unwrap().get(), 2);
+  /// ```
+  fn required_len(buf: &[u8]) -> Result<NonZeroUsize, DecodeError>
+  where
+    Self: Sized,
+  {
+    match Self::decode(buf) {
+      Ok((read, _)) => Ok(read),
+      // `required` may be relative to a nested value, so it is only trusted as a
+      // lower bound.
+      Err(DecodeError::InsufficientData(e)) => Ok(
+        NON_ZERO_USIZE_ONE
+          .saturating_add(buf.len())
+          .max(e.required().unwrap_or(NON_ZERO_USIZE_ONE)),
+      ),
+      Err(e) => Err(e),
+    }
+  }
+
+  /// Encodes the value as a varint and writes it to the [`BufMut`](bytes_1::BufMut).
+  ///
+  /// Returns the number of bytes written to the buffer.
+  ///
+  /// ## Example
+  ///
+  /// ```rust
+  /// use bytes_1::BytesMut;
+  /// use varing::Varint;
+  ///
+  /// let mut buf = BytesMut::new();
+  /// let written = 300u32.encode_to_buf(&mut buf).unwrap();
+  /// assert_eq!(written.get(), 2);
+  /// assert_eq!(&buf[..], [0xAC, 0x02]);
+  /// ```
+  #[cfg(feature = "bytes_1")]
+  #[cfg_attr(docsrs, doc(cfg(feature = "bytes_1")))]
+  #[inline]
+  fn encode_to_buf(&self, buf: &mut impl bytes_1::BufMut) -> Result<NonZeroUsize, EncodeError> {
+    bytes::encode_to_buf(self, buf)
+  }
+
+  /// Decodes the value from the [`Buf`](bytes_1::Buf), advancing it past the decoded bytes.
+  ///
+  /// Unlike [`decode`](Varint::decode), the value may span several chunks of a
+  /// non-contiguous buffer (e.g. a [`Chain`](bytes_1::buf::Chain)).
+  ///
+  /// Returns the number of bytes read from the buffer and the decoded value if successful.
+  /// On error, the buffer is left untouched if the failing value started and ended within
+  /// the current [`chunk`](bytes_1::Buf::chunk); otherwise it may have been partially advanced.
+  ///
+  /// ## Example
+  ///
+  /// ```rust
+  /// use bytes_1::Buf;
+  /// use varing::Varint;
+  ///
+  /// // 300 encodes to [0xAC, 0x02], split across two chunks.
+  /// let mut buf = (&[0xACu8][..]).chain(&[0x02u8, 0x7F][..]);
+  /// let (read, value) = u32::decode_from_buf(&mut buf).unwrap();
+  /// assert_eq!(value, 300);
+  /// assert_eq!(read.get(), 2);
+  /// assert_eq!(buf.remaining(), 1);
+  /// ```
+  #[cfg(feature = "bytes_1")]
+  #[cfg_attr(docsrs, doc(cfg(feature = "bytes_1")))]
+  #[inline]
+  fn decode_from_buf(buf: &mut impl bytes_1::Buf) -> Result<(NonZeroUsize, Self), DecodeError>
+  where
+    Self: Sized,
+  {
+    bytes::decode_from_buf(buf)
+  }
 }
 
 #[inline]
@@ -83,6 +273,18 @@ fn invalid_consumed_length() -> DecodeError {
   DecodeError::other("Varint::decode returned an invalid consumed length")
 }
 
+/// [`Varint::required_len`] of a single LEB128 varint of at most `max` bytes, found by
+/// scanning for the first byte without the continuation bit.
+#[inline]
+fn varint_required_len(buf: &[u8], max: NonZeroUsize) -> Result<NonZeroUsize, DecodeError> {
+  let scanned = &buf[..buf.len().min(max.get())];
+  match scanned.iter().position(|byte| byte & 0x80 == 0) {
+    Some(idx) => Ok(NON_ZERO_USIZE_ONE.saturating_add(idx)),
+    None if scanned.len() == max.get() => Err(DecodeError::Overflow),
+    None => Ok(NON_ZERO_USIZE_ONE.saturating_add(scanned.len())),
+  }
+}
+
 #[inline]
 fn checked_decode_offset(
   offset: usize,
@@ -371,6 +573,262 @@ where
   Ok((decoder.position(), output))
 }
 
+/// How a length-prefixed sequence or map records where it ends.
+///
+/// The prefix is written as a `u64` varint in front of the elements.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LengthPrefix {
+  /// The number of elements, or entries of a map.
+  Count,
+  /// The number of bytes of the encoded elements, the prefix excluded. A decoder can skip
+  /// the whole sequence or map without decoding it.
+  Bytes,
+}
+
+impl LengthPrefix {
+  /// Returns the prefix of `count` elements encoded in `bytes` bytes.
+  #[inline]
+  const fn value(&self, count: usize, bytes: usize) -> u64 {
+    match self {
+      Self::Count => count as u64,
+      Self::Bytes => bytes as u64,
+    }
+  }
+}
+
+/// Encodes the prefix of `count` elements encoded in `bytes` bytes, checking that the
+/// buffer can also hold the elements.
+///
+/// Returns the number of bytes written.
+fn encode_length_prefix(
+  prefix: LengthPrefix,
+  count: usize,
+  bytes: usize,
+  buf: &mut [u8],
+) -> Result<usize, EncodeError> {
+  let value = prefix.value(count, bytes);
+  let encoded_len = encoded_u64_varint_len(value).saturating_add(bytes);
+  if buf.len() < encoded_len.get() {
+    return Err(EncodeError::insufficient_space(encoded_len, buf.len()));
+  }
+  Ok(encode_u64_varint_to(value, buf)?.get())
+}
+
+/// Decodes a length prefix, returning its length and value.
+///
+/// A byte length must fit in the rest of the buffer.
+fn decode_length_prefix(buf: &[u8], prefix: LengthPrefix) -> Result<(usize, usize), DecodeError> {
+  let (prefix_len, value) = decode_u64_varint(buf)?;
+  let value = usize::try_from(value).map_err(|_| DecodeError::overflow())?;
+  if prefix == LengthPrefix::Bytes {
+    let total = prefix_len.saturating_add(value);
+    if total.get() > buf.len() {
+      return Err(DecodeError::insufficient_data_with_required(
+        total,
+        buf.len(),
+      ));
+    }
+  }
+  Ok((prefix_len.get(), value))
+}
+
+/// Returns the total number of bytes needed to encode a sequence of values behind a length prefix.
+pub fn encoded_sequence_with_len_len<'i, V>(
+  sequence: impl Iterator<Item = &'i V>,
+  prefix: LengthPrefix,
+) -> usize
+where
+  V: ?Sized + Varint + 'i,
+{
+  let (count, bytes) = sequence.fold((0, 0), |(count, bytes), item| {
+    (count + 1, bytes + item.encoded_len().get())
+  });
+  encoded_u64_varint_len(prefix.value(count, bytes)).get() + bytes
+}
+
+/// Encodes a sequence of values as varints behind a length prefix, and writes them to the buffer.
+///
+/// The prefix holds either the number of values or the number of bytes they take, see
+/// [`LengthPrefix`], so the sequence can be followed by other data. The iterator is cloned
+/// to measure the sequence before it is written, nothing is written if the buffer is too small.
+///
+/// Returns the total number of bytes written to the buffer.
+///
+/// ## Example
+///
+/// ```rust
+/// # #[cfg(feature = "std")]
+/// # {
+/// #
+/// use varing::{LengthPrefix, decode_sequence_with_len, encode_sequence_with_len, encoded_sequence_with_len_len};
+///
+/// let values = [1u32, 300, 70_000];
+///
+/// let encoded_len = encoded_sequence_with_len_len(values.iter(), LengthPrefix::Count);
+/// let mut buf = vec![0; encoded_len + 1];
+/// buf[encoded_len] = 0xFF; // data after the sequence
+///
+/// let bytes_written = encode_sequence_with_len(values.iter(), LengthPrefix::Count, &mut buf).unwrap();
+/// assert_eq!(bytes_written, encoded_len);
+/// assert_eq!(buf[0], 3);
+///
+/// let (readed, decoded) = decode_sequence_with_len::<u32, Vec<_>>(&buf, LengthPrefix::Count).unwrap();
+/// assert_eq!(decoded, values);
+/// assert_eq!(readed, encoded_len);
+/// # }
+/// ```
+pub fn encode_sequence_with_len<'i, V>(
+  sequence: impl Iterator<Item = &'i V> + Clone,
+  prefix: LengthPrefix,
+  buf: &mut [u8],
+) -> Result<usize, EncodeError>
+where
+  V: ?Sized + Varint + 'i,
+{
+  let (count, bytes) = sequence.clone().fold((0, 0), |(count, bytes), item| {
+    (count + 1, bytes + item.encoded_len().get())
+  });
+  let prefix_len = encode_length_prefix(prefix, count, bytes, buf)?;
+  Ok(prefix_len + encode_sequence(sequence, &mut buf[prefix_len..])?)
+}
+
+/// Returns a sequence decoder for a sequence encoded by [`encode_sequence_with_len`].
+///
+/// See [`SequenceDecoder::with_len`] for details.
+#[inline]
+pub fn sequence_decoder_with_len<V>(
+  buf: &[u8],
+  prefix: LengthPrefix,
+) -> Result<SequenceDecoder<'_, V>, DecodeError>
+where
+  V: ?Sized,
+{
+  SequenceDecoder::with_len(buf, prefix)
+}
+
+/// Decodes a sequence encoded by [`encode_sequence_with_len`] from the buffer.
+///
+/// Returns the number of bytes read from the buffer, the prefix included, and a collection of
+/// the decoded values if successful. Bytes after the sequence are left untouched.
+pub fn decode_sequence_with_len<V, O>(
+  buf: &[u8],
+  prefix: LengthPrefix,
+) -> Result<(usize, O), DecodeError>
+where
+  V: Varint,
+  O: core::iter::FromIterator<V>,
+{
+  let mut decoder = sequence_decoder_with_len::<V>(buf, prefix)?;
+  let output = decoder
+    .by_ref()
+    .map(|result| result.map(|(_, value)| value))
+    .collect::<Result<O, _>>()?;
+  Ok((decoder.position(), output))
+}
+
+/// Returns the total number of bytes needed to encode a map behind a length prefix.
+pub fn encoded_map_with_len_len<'a, K, V>(
+  map: impl Iterator<Item = (&'a K, &'a V)>,
+  prefix: LengthPrefix,
+) -> usize
+where
+  K: Varint + 'a,
+  V: Varint + 'a,
+{
+  let (count, bytes) = map.fold((0, 0), |(count, bytes), (key, value)| {
+    (
+      count + 1,
+      bytes + key.encoded_len().get() + value.encoded_len().get(),
+    )
+  });
+  encoded_u64_varint_len(prefix.value(count, bytes)).get() + bytes
+}
+
+/// Encodes a map behind a length prefix, and writes it to the buffer.
+///
+/// The prefix holds either the number of entries or the number of bytes they take, see
+/// [`LengthPrefix`]. The iterator is cloned to measure the map before it is written,
+/// nothing is written if the buffer is too small.
+///
+/// Returns the total number of bytes written to the buffer.
+///
+/// ## Example
+///
+/// ```rust
+/// # #[cfg(feature = "std")]
+/// # {
+/// #
+/// use varing::{LengthPrefix, decode_map_with_len, encode_map_with_len, encoded_map_with_len_len};
+/// use std::collections::BTreeMap;
+///
+/// let values = (0..100u64).map(|v| (v, v * v)).collect::<BTreeMap<_, _>>();
+///
+/// let encoded_len = encoded_map_with_len_len(values.iter(), LengthPrefix::Bytes);
+/// let mut buf = vec![0; encoded_len + 4];
+///
+/// let bytes_written = encode_map_with_len(values.iter(), LengthPrefix::Bytes, &mut buf).unwrap();
+/// assert_eq!(bytes_written, encoded_len);
+///
+/// let (readed, decoded) = decode_map_with_len::<u64, u64, BTreeMap<_, _>>(&buf, LengthPrefix::Bytes).unwrap();
+/// assert_eq!(decoded, values);
+/// assert_eq!(readed, encoded_len);
+/// # }
+/// ```
+pub fn encode_map_with_len<'a, K, V>(
+  map: impl Iterator<Item = (&'a K, &'a V)> + Clone,
+  prefix: LengthPrefix,
+  buf: &mut [u8],
+) -> Result<usize, EncodeError>
+where
+  K: Varint + 'a,
+  V: Varint + 'a,
+{
+  let (count, bytes) = map.clone().fold((0, 0), |(count, bytes), (key, value)| {
+    (
+      count + 1,
+      bytes + key.encoded_len().get() + value.encoded_len().get(),
+    )
+  });
+  let prefix_len = encode_length_prefix(prefix, count, bytes, buf)?;
+  Ok(prefix_len + encode_map(map, &mut buf[prefix_len..])?)
+}
+
+/// Returns a map decoder for a map encoded by [`encode_map_with_len`].
+///
+/// See [`MapDecoder::with_len`] for details.
+#[inline]
+pub fn map_decoder_with_len<K, V>(
+  buf: &[u8],
+  prefix: LengthPrefix,
+) -> Result<MapDecoder<'_, K, V>, DecodeError>
+where
+  K: ?Sized,
+  V: ?Sized,
+{
+  MapDecoder::with_len(buf, prefix)
+}
+
+/// Decodes a map encoded by [`encode_map_with_len`] from the buffer.
+///
+/// Returns the number of bytes read from the buffer, the prefix included, and a collection of
+/// the decoded entries if successful. Bytes after the map are left untouched.
+pub fn decode_map_with_len<K, V, O>(
+  buf: &[u8],
+  prefix: LengthPrefix,
+) -> Result<(usize, O), DecodeError>
+where
+  K: Varint + Sized,
+  V: Varint + Sized,
+  O: core::iter::FromIterator<(K, V)>,
+{
+  let mut decoder = map_decoder_with_len::<K, V>(buf, prefix)?;
+  let output = decoder
+    .by_ref()
+    .map(|result| result.map(|(_, entry)| entry))
+    .collect::<Result<O, _>>()?;
+  Ok((decoder.position(), output))
+}
+
 /// Calculates the number of bytes occupied by a varint encoded value in the buffer.
 ///
 /// In varint encoding, each byte uses 7 bits for the value and the highest bit (MSB)
@@ -528,6 +986,8 @@ pub const fn try_consume_varint(buf: &[u8]) -> Result<NonZeroUsize, ConstDecodeE
 pub struct SequenceDecoder<'a, V: ?Sized> {
   buf: &'a [u8],
   offset: usize,
+  /// The number of items left to decode, if the sequence is prefixed by its count.
+  remaining: Option<usize>,
   _m: core::marker::PhantomData<V>,
 }
 
@@ -545,10 +1005,39 @@ impl<'a, V: ?Sized> SequenceDecoder<'a, V> {
     Self {
       buf: src,
       offset: 0,
+      remaining: None,
       _m: core::marker::PhantomData,
     }
   }
 
+  /// Creates a decoder for a sequence encoded by [`encode_sequence_with_len`], starting with its
+  /// length prefix.
+  ///
+  /// The decoder stops at the end of the sequence, so anything after it in `buf` is left
+  /// for the caller, at [`position`](Self::position).
+  ///
+  /// ## Errors
+  ///
+  /// Returns an error if the prefix cannot be decoded, or if the byte length it records
+  /// is larger than the rest of the buffer.
+  pub fn with_len(buf: &'a [u8], prefix: LengthPrefix) -> Result<Self, DecodeError> {
+    let (offset, len) = decode_length_prefix(buf, prefix)?;
+    Ok(match prefix {
+      LengthPrefix::Count => Self {
+        buf,
+        offset,
+        remaining: Some(len),
+        _m: core::marker::PhantomData,
+      },
+      LengthPrefix::Bytes => Self {
+        buf: &buf[..offset + len],
+        offset,
+        remaining: None,
+        _m: core::marker::PhantomData,
+      },
+    })
+  }
+
   /// Returns the current position of the buffer.
   #[inline]
   pub const fn position(&self) -> usize {
@@ -560,22 +1049,28 @@ impl<V: Varint> Iterator for SequenceDecoder<'_, V> {
   type Item = Result<(NonZeroUsize, V), DecodeError>;
 
   fn next(&mut self) -> Option<Self::Item> {
-    if self.offset < self.buf.len() {
+    if self
+      .remaining
+      .map_or(self.offset < self.buf.len(), |n| n > 0)
+    {
       match V::decode(&self.buf[self.offset..]) {
         Ok((bytes_read, value)) => {
           match checked_decode_offset(self.offset, bytes_read, self.buf.len()) {
             Ok(next_offset) => {
               self.offset = next_offset;
+              self.remaining = self.remaining.map(|n| n - 1);
               Some(Ok((bytes_read, value)))
             }
             Err(e) => {
               self.offset = self.buf.len();
+              self.remaining = self.remaining.map(|_| 0);
               Some(Err(e))
             }
           }
         }
         Err(e) => {
           self.offset = self.buf.len();
+          self.remaining = self.remaining.map(|_| 0);
           Some(Err(e))
         }
       }
@@ -585,11 +1080,13 @@ impl<V: Varint> Iterator for SequenceDecoder<'_, V> {
   }
 }
 
-/// An iterator that decodes a sequence of varint values from a buffer.
+/// An iterator that decodes the key-value entries of a map from a buffer.
 #[derive(Debug)]
 pub struct MapDecoder<'a, K: ?Sized, V: ?Sized> {
   buf: &'a [u8],
   offset: usize,
+  /// The number of entries left to decode, if the map is prefixed by its count.
+  remaining: Option<usize>,
   #[allow(clippy::type_complexity)]
   _m: core::marker::PhantomData<(fn() -> &'a K, fn() -> &'a V)>,
 }
@@ -608,10 +1105,39 @@ impl<'a, K: ?Sized, V: ?Sized> MapDecoder<'a, K, V> {
     Self {
       buf: src,
       offset: 0,
+      remaining: None,
       _m: core::marker::PhantomData,
     }
   }
 
+  /// Creates a decoder for a map encoded by [`encode_map_with_len`], starting with its
+  /// length prefix.
+  ///
+  /// The decoder stops at the end of the map, so anything after it in `buf` is left
+  /// for the caller, at [`position`](Self::position).
+  ///
+  /// ## Errors
+  ///
+  /// Returns an error if the prefix cannot be decoded, or if the byte length it records
+  /// is larger than the rest of the buffer.
+  pub fn with_len(buf: &'a [u8], prefix: LengthPrefix) -> Result<Self, DecodeError> {
+    let (offset, len) = decode_length_prefix(buf, prefix)?;
+    Ok(match prefix {
+      LengthPrefix::Count => Self {
+        buf,
+        offset,
+        remaining: Some(len),
+        _m: core::marker::PhantomData,
+      },
+      LengthPrefix::Bytes => Self {
+        buf: &buf[..offset + len],
+        offset,
+        remaining: None,
+        _m: core::marker::PhantomData,
+      },
+    })
+  }
+
   /// Returns the current position of the buffer.
   #[inline]
   pub const fn position(&self) -> usize {
@@ -623,7 +1149,10 @@ impl<K: Varint, V: Varint> Iterator for MapDecoder<'_, K, V> {
   type Item = Result<(NonZeroUsize, (K, V)), DecodeError>;
 
   fn next(&mut self) -> Option<Self::Item> {
-    if self.offset < self.buf.len() {
+    if self
+      .remaining
+      .map_or(self.offset < self.buf.len(), |n| n > 0)
+    {
       let start_offset = self.offset;
       let result: Result<_, DecodeError> = (|| {
         let (klen, k) = K::decode(&self.buf[start_offset..])?;
@@ -641,10 +1170,12 @@ impl<K: Varint, V: Varint> Iterator for MapDecoder<'_, K, V> {
       match result {
         Ok((next_offset, consumed, entry)) => {
           self.offset = next_offset;
+          self.remaining = self.remaining.map(|n| n - 1);
           Some(Ok((consumed, entry)))
         }
         Err(e) => {
           self.offset = self.buf.len();
+          self.remaining = self.remaining.map(|_| 0);
           Some(Err(e))
         }
       }
@@ -717,11 +1248,36 @@ pub mod primitive_types;
 )]
 pub mod ethereum_types;
 
+/// Async varint reading/writing for [`tokio`](https://crates.io/crates/tokio) I/O types.
+#[cfg(feature = "tokio_1")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio_1")))]
+pub mod tokio;
+
+/// Async varint reading/writing for [`futures-io`](https://crates.io/crates/futures-io) I/O types.
+#[cfg(feature = "futures-io_0_3")]
+#[cfg_attr(docsrs, doc(cfg(feature = "futures-io_0_3")))]
+pub mod futures_io;
+
+/// [`tokio-util`](https://crates.io/crates/tokio-util) codecs for varint length-delimited frames and varint encoded values.
+#[cfg(feature = "tokio-util_0_7")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio-util_0_7")))]
+pub mod tokio_util;
+
+/// [`serde`](https://crates.io/crates/serde) helpers for serializing fields as varints, and a
+/// compact data format built on the varint encodings.
+#[cfg(feature = "serde_1")]
+#[cfg_attr(docsrs, doc(cfg(feature = "serde_1")))]
+pub mod serde;
+
 /// Packable trait for types that can be packed into a single value.
 pub mod packable;
 
 #[cfg(feature = "ruint_1")]
 mod ruint_impl;
 
-#[cfg(any(feature = "chrono_0_4", feature = "time_0_3"))]
+#[cfg(any(feature = "std", feature = "chrono_0_4", feature = "time_0_3"))]
+#[cfg_attr(
+  not(any(feature = "chrono_0_4", feature = "time_0_3")),
+  allow(dead_code, unused_imports)
+)]
 mod time_utils;
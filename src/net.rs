@@ -0,0 +1,501 @@
+use crate::utils::Buffer;
+
+use super::{
+  ConstDecodeError, ConstEncodeError, DecodeError, EncodeError, Varint, decode_u16_varint,
+  decode_u32_varint, decode_u128_varint, encode_u16_varint_to, encode_u32_varint_to,
+  encode_u128_varint_to, encoded_u16_varint_len, encoded_u32_varint_len, encoded_u128_varint_len,
+};
+
+use core::{
+  net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6},
+  num::NonZeroUsize,
+};
+
+/// The tag of the V4 variant of [`IpAddr`] and [`SocketAddr`].
+const TAG_V4: u8 = 0;
+/// The tag of the V6 variant of [`IpAddr`] and [`SocketAddr`].
+const TAG_V6: u8 = 1;
+
+/// The tag is a single byte.
+const TAG_LEN: usize = 1;
+
+const SOCKET_ADDR_V4_MAX_LEN: usize = u32::MAX_ENCODED_LEN.get() + u16::MAX_ENCODED_LEN.get();
+
+const SOCKET_ADDR_V6_MAX_LEN: usize = u128::MAX_ENCODED_LEN.get()
+  + u16::MAX_ENCODED_LEN.get()
+  + u32::MAX_ENCODED_LEN.get()
+  + u32::MAX_ENCODED_LEN.get();
+
+/// A buffer for storing LEB128 encoded [`Ipv4Addr`] value.
+pub type Ipv4AddrBuffer = Buffer<{ u32::MAX_ENCODED_LEN.get() + 1 }>;
+
+/// A buffer for storing LEB128 encoded [`Ipv6Addr`] value.
+pub type Ipv6AddrBuffer = Buffer<{ u128::MAX_ENCODED_LEN.get() + 1 }>;
+
+/// A buffer for storing LEB128 encoded [`IpAddr`] value.
+pub type IpAddrBuffer = Buffer<{ TAG_LEN + u128::MAX_ENCODED_LEN.get() + 1 }>;
+
+/// A buffer for storing LEB128 encoded [`SocketAddrV4`] value.
+pub type SocketAddrV4Buffer = Buffer<{ SOCKET_ADDR_V4_MAX_LEN + 1 }>;
+
+/// A buffer for storing LEB128 encoded [`SocketAddrV6`] value.
+pub type SocketAddrV6Buffer = Buffer<{ SOCKET_ADDR_V6_MAX_LEN + 1 }>;
+
+/// A buffer for storing LEB128 encoded [`SocketAddr`] value.
+pub type SocketAddrBuffer = Buffer<{ TAG_LEN + SOCKET_ADDR_V6_MAX_LEN + 1 }>;
+
+/// Fills a `Buffer` with the output of an `encode_*_to` function, which cannot fail
+/// because the buffer holds the longest encoding.
+macro_rules! encode_to_buffer {
+  ($buffer:ty, $encode_to:ident($addr:expr)) => {{
+    let mut buf = [0; <$buffer>::CAPACITY.get() + 1];
+    let (data_buf, len_buf) = buf.split_at_mut(<$buffer>::CAPACITY.get());
+    let len = match $encode_to($addr, data_buf) {
+      Ok(len) => len,
+      Err(_) => panic!("buffer is too small for the encoded address"),
+    };
+    len_buf[0] = len.get() as u8;
+    <$buffer>::new(buf)
+  }};
+}
+
+/// Fails before anything is written if `len` bytes do not fit in the buffer.
+#[inline]
+const fn check_space(len: NonZeroUsize, buf: &[u8]) -> Result<(), ConstEncodeError> {
+  if buf.len() < len.get() {
+    return Err(ConstEncodeError::insufficient_space(len, buf.len()));
+  }
+  Ok(())
+}
+
+/// Reports a shortfall while decoding a component at `offset` relative to the start of
+/// the whole buffer.
+#[inline]
+const fn at_offset(err: ConstDecodeError, offset: usize) -> ConstDecodeError {
+  match err {
+    ConstDecodeError::InsufficientData(e) => {
+      let available = e.available().saturating_add(offset);
+      match e.required() {
+        Some(required) => ConstDecodeError::insufficient_data_with_required(
+          required.saturating_add(offset),
+          available,
+        ),
+        None => ConstDecodeError::insufficient_data(available),
+      }
+    }
+    err => err,
+  }
+}
+
+/// Returns the encoded length of the value in LEB128 variable length format.
+/// The returned value will be in range [`Ipv4Addr::ENCODED_LEN_RANGE`].
+#[inline]
+pub const fn encoded_ipv4_addr_len(addr: &Ipv4Addr) -> NonZeroUsize {
+  encoded_u32_varint_len(addr.to_bits())
+}
+
+/// Encodes an `Ipv4Addr` value into LEB128 variable length format, and writes it to the buffer.
+#[inline]
+pub const fn encode_ipv4_addr(addr: &Ipv4Addr) -> Ipv4AddrBuffer {
+  encode_to_buffer!(Ipv4AddrBuffer, encode_ipv4_addr_to(addr))
+}
+
+/// Encodes an `Ipv4Addr` value into LEB128 variable length format, and writes it to the buffer.
+///
+/// The address is encoded as its `u32` bit representation.
+#[inline]
+pub const fn encode_ipv4_addr_to(
+  addr: &Ipv4Addr,
+  buf: &mut [u8],
+) -> Result<NonZeroUsize, ConstEncodeError> {
+  encode_u32_varint_to(addr.to_bits(), buf)
+}
+
+/// Decodes an `Ipv4Addr` in LEB128 encoded format from the buffer.
+///
+/// Returns the bytes read and the decoded value if successful.
+#[inline]
+pub const fn decode_ipv4_addr(buf: &[u8]) -> Result<(NonZeroUsize, Ipv4Addr), ConstDecodeError> {
+  match decode_u32_varint(buf) {
+    Ok((bytes_read, bits)) => Ok((bytes_read, Ipv4Addr::from_bits(bits))),
+    Err(e) => Err(e),
+  }
+}
+
+/// Returns the encoded length of the value in LEB128 variable length format.
+/// The returned value will be in range [`Ipv6Addr::ENCODED_LEN_RANGE`].
+#[inline]
+pub const fn encoded_ipv6_addr_len(addr: &Ipv6Addr) -> NonZeroUsize {
+  encoded_u128_varint_len(addr.to_bits())
+}
+
+/// Encodes an `Ipv6Addr` value into LEB128 variable length format, and writes it to the buffer.
+#[inline]
+pub const fn encode_ipv6_addr(addr: &Ipv6Addr) -> Ipv6AddrBuffer {
+  encode_to_buffer!(Ipv6AddrBuffer, encode_ipv6_addr_to(addr))
+}
+
+/// Encodes an `Ipv6Addr` value into LEB128 variable length format, and writes it to the buffer.
+///
+/// The address is encoded as its `u128` bit representation.
+#[inline]
+pub const fn encode_ipv6_addr_to(
+  addr: &Ipv6Addr,
+  buf: &mut [u8],
+) -> Result<NonZeroUsize, ConstEncodeError> {
+  encode_u128_varint_to(addr.to_bits(), buf)
+}
+
+/// Decodes an `Ipv6Addr` in LEB128 encoded format from the buffer.
+///
+/// Returns the bytes read and the decoded value if successful.
+#[inline]
+pub const fn decode_ipv6_addr(buf: &[u8]) -> Result<(NonZeroUsize, Ipv6Addr), ConstDecodeError> {
+  match decode_u128_varint(buf) {
+    Ok((bytes_read, bits)) => Ok((bytes_read, Ipv6Addr::from_bits(bits))),
+    Err(e) => Err(e),
+  }
+}
+
+/// Returns the encoded length of the value in LEB128 variable length format.
+/// The returned value will be in range [`IpAddr::ENCODED_LEN_RANGE`].
+#[inline]
+pub const fn encoded_ip_addr_len(addr: &IpAddr) -> NonZeroUsize {
+  match addr {
+    IpAddr::V4(addr) => encoded_ipv4_addr_len(addr).saturating_add(TAG_LEN),
+    IpAddr::V6(addr) => encoded_ipv6_addr_len(addr).saturating_add(TAG_LEN),
+  }
+}
+
+/// Encodes an `IpAddr` value into LEB128 variable length format, and writes it to the buffer.
+#[inline]
+pub const fn encode_ip_addr(addr: &IpAddr) -> IpAddrBuffer {
+  encode_to_buffer!(IpAddrBuffer, encode_ip_addr_to(addr))
+}
+
+/// Encodes an `IpAddr` value into LEB128 variable length format, and writes it to the buffer.
+///
+/// A tag byte, `0` for V4 and `1` for V6, is followed by the address.
+#[inline]
+pub const fn encode_ip_addr_to(
+  addr: &IpAddr,
+  buf: &mut [u8],
+) -> Result<NonZeroUsize, ConstEncodeError> {
+  let len = encoded_ip_addr_len(addr);
+  if let Err(e) = check_space(len, buf) {
+    return Err(e);
+  }
+
+  let (tag, rest) = buf.split_at_mut(TAG_LEN);
+  let written = match addr {
+    IpAddr::V4(addr) => {
+      tag[0] = TAG_V4;
+      encode_ipv4_addr_to(addr, rest)
+    }
+    IpAddr::V6(addr) => {
+      tag[0] = TAG_V6;
+      encode_ipv6_addr_to(addr, rest)
+    }
+  };
+  match written {
+    Ok(written) => Ok(written.saturating_add(TAG_LEN)),
+    Err(e) => Err(e),
+  }
+}
+
+/// Decodes an `IpAddr` in LEB128 encoded format from the buffer.
+///
+/// Returns the bytes read and the decoded value if successful.
+#[inline]
+pub const fn decode_ip_addr(buf: &[u8]) -> Result<(NonZeroUsize, IpAddr), ConstDecodeError> {
+  let Some((tag, rest)) = buf.split_first() else {
+    return Err(ConstDecodeError::insufficient_data(0));
+  };
+
+  match *tag {
+    TAG_V4 => match decode_ipv4_addr(rest) {
+      Ok((read, addr)) => Ok((read.saturating_add(TAG_LEN), IpAddr::V4(addr))),
+      Err(e) => Err(at_offset(e, TAG_LEN)),
+    },
+    TAG_V6 => match decode_ipv6_addr(rest) {
+      Ok((read, addr)) => Ok((read.saturating_add(TAG_LEN), IpAddr::V6(addr))),
+      Err(e) => Err(at_offset(e, TAG_LEN)),
+    },
+    _ => Err(ConstDecodeError::other("invalid IpAddr tag")),
+  }
+}
+
+/// Returns the encoded length of the value in LEB128 variable length format.
+/// The returned value will be in range [`SocketAddrV4::ENCODED_LEN_RANGE`].
+#[inline]
+pub const fn encoded_socket_addr_v4_len(addr: &SocketAddrV4) -> NonZeroUsize {
+  encoded_ipv4_addr_len(addr.ip()).saturating_add(encoded_u16_varint_len(addr.port()).get())
+}
+
+/// Encodes a `SocketAddrV4` value into LEB128 variable length format, and writes it to the buffer.
+#[inline]
+pub const fn encode_socket_addr_v4(addr: &SocketAddrV4) -> SocketAddrV4Buffer {
+  encode_to_buffer!(SocketAddrV4Buffer, encode_socket_addr_v4_to(addr))
+}
+
+/// Encodes a `SocketAddrV4` value into LEB128 variable length format, and writes it to the buffer.
+///
+/// The address is followed by the port as a `u16` varint.
+#[inline]
+pub const fn encode_socket_addr_v4_to(
+  addr: &SocketAddrV4,
+  buf: &mut [u8],
+) -> Result<NonZeroUsize, ConstEncodeError> {
+  let len = encoded_socket_addr_v4_len(addr);
+  if let Err(e) = check_space(len, buf) {
+    return Err(e);
+  }
+
+  let ip_len = match encode_ipv4_addr_to(addr.ip(), buf) {
+    Ok(written) => written.get(),
+    Err(e) => return Err(e),
+  };
+  let (_, rest) = buf.split_at_mut(ip_len);
+  match encode_u16_varint_to(addr.port(), rest) {
+    Ok(written) => Ok(written.saturating_add(ip_len)),
+    Err(e) => Err(e),
+  }
+}
+
+/// Decodes a `SocketAddrV4` in LEB128 encoded format from the buffer.
+///
+/// Returns the bytes read and the decoded value if successful.
+#[inline]
+pub const fn decode_socket_addr_v4(
+  buf: &[u8],
+) -> Result<(NonZeroUsize, SocketAddrV4), ConstDecodeError> {
+  let (ip_len, ip) = match decode_ipv4_addr(buf) {
+    Ok((read, ip)) => (read.get(), ip),
+    Err(e) => return Err(e),
+  };
+  let (_, rest) = buf.split_at(ip_len);
+  match decode_u16_varint(rest) {
+    Ok((read, port)) => Ok((read.saturating_add(ip_len), SocketAddrV4::new(ip, port))),
+    Err(e) => Err(at_offset(e, ip_len)),
+  }
+}
+
+/// Returns the encoded length of the value in LEB128 variable length format.
+/// The returned value will be in range [`SocketAddrV6::ENCODED_LEN_RANGE`].
+#[inline]
+pub const fn encoded_socket_addr_v6_len(addr: &SocketAddrV6) -> NonZeroUsize {
+  encoded_ipv6_addr_len(addr.ip())
+    .saturating_add(encoded_u16_varint_len(addr.port()).get())
+    .saturating_add(encoded_u32_varint_len(addr.flowinfo()).get())
+    .saturating_add(encoded_u32_varint_len(addr.scope_id()).get())
+}
+
+/// Encodes a `SocketAddrV6` value into LEB128 variable length format, and writes it to the buffer.
+#[inline]
+pub const fn encode_socket_addr_v6(addr: &SocketAddrV6) -> SocketAddrV6Buffer {
+  encode_to_buffer!(SocketAddrV6Buffer, encode_socket_addr_v6_to(addr))
+}
+
+/// Encodes a `SocketAddrV6` value into LEB128 variable length format, and writes it to the buffer.
+///
+/// The address is followed by the port as a `u16` varint, then the flow info and the
+/// scope ID as `u32` varints, which take a byte each when they are zero.
+#[inline]
+pub const fn encode_socket_addr_v6_to(
+  addr: &SocketAddrV6,
+  buf: &mut [u8],
+) -> Result<NonZeroUsize, ConstEncodeError> {
+  let len = encoded_socket_addr_v6_len(addr);
+  if let Err(e) = check_space(len, buf) {
+    return Err(e);
+  }
+
+  let mut offset = match encode_ipv6_addr_to(addr.ip(), buf) {
+    Ok(written) => written.get(),
+    Err(e) => return Err(e),
+  };
+  let (_, rest) = buf.split_at_mut(offset);
+  offset += match encode_u16_varint_to(addr.port(), rest) {
+    Ok(written) => written.get(),
+    Err(e) => return Err(e),
+  };
+  let (_, rest) = buf.split_at_mut(offset);
+  offset += match encode_u32_varint_to(addr.flowinfo(), rest) {
+    Ok(written) => written.get(),
+    Err(e) => return Err(e),
+  };
+  let (_, rest) = buf.split_at_mut(offset);
+  match encode_u32_varint_to(addr.scope_id(), rest) {
+    Ok(written) => Ok(written.saturating_add(offset)),
+    Err(e) => Err(e),
+  }
+}
+
+/// Decodes a `SocketAddrV6` in LEB128 encoded format from the buffer.
+///
+/// Returns the bytes read and the decoded value if successful.
+#[inline]
+pub const fn decode_socket_addr_v6(
+  buf: &[u8],
+) -> Result<(NonZeroUsize, SocketAddrV6), ConstDecodeError> {
+  let (mut offset, ip) = match decode_ipv6_addr(buf) {
+    Ok((read, ip)) => (read.get(), ip),
+    Err(e) => return Err(e),
+  };
+  let (_, rest) = buf.split_at(offset);
+  let port = match decode_u16_varint(rest) {
+    Ok((read, port)) => {
+      offset += read.get();
+      port
+    }
+    Err(e) => return Err(at_offset(e, offset)),
+  };
+  let (_, rest) = buf.split_at(offset);
+  let flowinfo = match decode_u32_varint(rest) {
+    Ok((read, flowinfo)) => {
+      offset += read.get();
+      flowinfo
+    }
+    Err(e) => return Err(at_offset(e, offset)),
+  };
+  let (_, rest) = buf.split_at(offset);
+  match decode_u32_varint(rest) {
+    Ok((read, scope_id)) => Ok((
+      read.saturating_add(offset),
+      SocketAddrV6::new(ip, port, flowinfo, scope_id),
+    )),
+    Err(e) => Err(at_offset(e, offset)),
+  }
+}
+
+/// Returns the encoded length of the value in LEB128 variable length format.
+/// The returned value will be in range [`SocketAddr::ENCODED_LEN_RANGE`].
+#[inline]
+pub const fn encoded_socket_addr_len(addr: &SocketAddr) -> NonZeroUsize {
+  match addr {
+    SocketAddr::V4(addr) => encoded_socket_addr_v4_len(addr).saturating_add(TAG_LEN),
+    SocketAddr::V6(addr) => encoded_socket_addr_v6_len(addr).saturating_add(TAG_LEN),
+  }
+}
+
+/// Encodes a `SocketAddr` value into LEB128 variable length format, and writes it to the buffer.
+#[inline]
+pub const fn encode_socket_addr(addr: &SocketAddr) -> SocketAddrBuffer {
+  encode_to_buffer!(SocketAddrBuffer, encode_socket_addr_to(addr))
+}
+
+/// Encodes a `SocketAddr` value into LEB128 variable length format, and writes it to the buffer.
+///
+/// A tag byte, `0` for V4 and `1` for V6, is followed by the socket address.
+#[inline]
+pub const fn encode_socket_addr_to(
+  addr: &SocketAddr,
+  buf: &mut [u8],
+) -> Result<NonZeroUsize, ConstEncodeError> {
+  let len = encoded_socket_addr_len(addr);
+  if let Err(e) = check_space(len, buf) {
+    return Err(e);
+  }
+
+  let (tag, rest) = buf.split_at_mut(TAG_LEN);
+  let written = match addr {
+    SocketAddr::V4(addr) => {
+      tag[0] = TAG_V4;
+      encode_socket_addr_v4_to(addr, rest)
+    }
+    SocketAddr::V6(addr) => {
+      tag[0] = TAG_V6;
+      encode_socket_addr_v6_to(addr, rest)
+    }
+  };
+  match written {
+    Ok(written) => Ok(written.saturating_add(TAG_LEN)),
+    Err(e) => Err(e),
+  }
+}
+
+/// Decodes a `SocketAddr` in LEB128 encoded format from the buffer.
+///
+/// Returns the bytes read and the decoded value if successful.
+#[inline]
+pub const fn decode_socket_addr(
+  buf: &[u8],
+) -> Result<(NonZeroUsize, SocketAddr), ConstDecodeError> {
+  let Some((tag, rest)) = buf.split_first() else {
+    return Err(ConstDecodeError::insufficient_data(0));
+  };
+
+  match *tag {
+    TAG_V4 => match decode_socket_addr_v4(rest) {
+      Ok((read, addr)) => Ok((read.saturating_add(TAG_LEN), SocketAddr::V4(addr))),
+      Err(e) => Err(at_offset(e, TAG_LEN)),
+    },
+    TAG_V6 => match decode_socket_addr_v6(rest) {
+      Ok((read, addr)) => Ok((read.saturating_add(TAG_LEN), SocketAddr::V6(addr))),
+      Err(e) => Err(at_offset(e, TAG_LEN)),
+    },
+    _ => Err(ConstDecodeError::other("invalid SocketAddr tag")),
+  }
+}
+
+macro_rules! impl_varint_for_net {
+  ($($ty:ident($min:expr, $max:expr).$fn:ident), +$(,)?) => {
+    paste::paste! {
+      $(
+        impl Varint for $ty {
+          const MIN_ENCODED_LEN: NonZeroUsize = $min;
+          const MAX_ENCODED_LEN: NonZeroUsize = $max;
+
+          #[inline]
+          fn encoded_len(&self) -> NonZeroUsize {
+            [< encoded_ $fn _len >](self)
+          }
+
+          #[inline]
+          fn encode(&self, buf: &mut [u8]) -> Result<NonZeroUsize, EncodeError> {
+            [< encode_ $fn _to >](self, buf).map_err(Into::into)
+          }
+
+          #[inline]
+          fn decode(buf: &[u8]) -> Result<(NonZeroUsize, Self), DecodeError>
+          where
+            Self: Sized,
+          {
+            [< decode_ $fn >](buf).map_err(Into::into)
+          }
+        }
+      )*
+    }
+  };
+}
+
+impl_varint_for_net!(
+  Ipv4Addr(u32::MIN_ENCODED_LEN, u32::MAX_ENCODED_LEN).ipv4_addr,
+  Ipv6Addr(u128::MIN_ENCODED_LEN, u128::MAX_ENCODED_LEN).ipv6_addr,
+  IpAddr(
+    u32::MIN_ENCODED_LEN.saturating_add(TAG_LEN),
+    u128::MAX_ENCODED_LEN.saturating_add(TAG_LEN)
+  )
+  .ip_addr,
+  SocketAddrV4(
+    u32::MIN_ENCODED_LEN.saturating_add(u16::MIN_ENCODED_LEN.get()),
+    SocketAddrV4Buffer::CAPACITY
+  )
+  .socket_addr_v4,
+  SocketAddrV6(
+    u128::MIN_ENCODED_LEN
+      .saturating_add(u16::MIN_ENCODED_LEN.get())
+      .saturating_add(u32::MIN_ENCODED_LEN.get())
+      .saturating_add(u32::MIN_ENCODED_LEN.get()),
+    SocketAddrV6Buffer::CAPACITY
+  )
+  .socket_addr_v6,
+  SocketAddr(
+    SocketAddrV4::MIN_ENCODED_LEN.saturating_add(TAG_LEN),
+    SocketAddrBuffer::CAPACITY
+  )
+  .socket_addr,
+);
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,110 @@
+use super::*;
+
+use quickcheck_macros::quickcheck;
+
+macro_rules! fuzzy {
+  ($($ty:ident.$fn:ident), +$(,)?) => {
+    $(
+      paste::paste! {
+        #[quickcheck]
+        fn [< encode_decode_ $fn >](value: $ty) -> bool {
+          let encoded = [< encode_ $fn >](&value);
+          if encoded.len() != [< encoded_ $fn _len >](&value).get()
+            || !$ty::ENCODED_LEN_RANGE.contains(&[< encoded_ $fn _len >](&value))
+          {
+            return false;
+          }
+
+          let truncated = &encoded.as_slice()[..encoded.len() - 1];
+          matches!([< decode_ $fn >](truncated), Err(ConstDecodeError::InsufficientData(_)))
+            && matches!(
+              [< decode_ $fn >](encoded.as_slice()),
+              Ok((bytes_read, decoded)) if decoded == value && bytes_read.get() == encoded.len()
+            )
+        }
+
+        #[quickcheck]
+        fn [< encode_decode_ $fn _varint >](value: $ty) -> bool {
+          let mut buf = [0; <$ty>::MAX_ENCODED_LEN.get()];
+          let Ok(encoded_len) = value.encode(&mut buf) else {
+            return false;
+          };
+          if encoded_len != value.encoded_len()
+            || value.encode(&mut buf[..encoded_len.get() - 1]).is_ok()
+          {
+            return false;
+          }
+
+          matches!(<$ty>::decode(&buf), Ok((bytes_read, decoded)) if decoded == value && bytes_read == encoded_len)
+        }
+      }
+    )*
+  };
+}
+
+fuzzy!(
+  Ipv4Addr.ipv4_addr,
+  Ipv6Addr.ipv6_addr,
+  IpAddr.ip_addr,
+  SocketAddrV4.socket_addr_v4,
+  SocketAddrV6.socket_addr_v6,
+  SocketAddr.socket_addr,
+);
+
+fn range(min: usize, max: usize) -> core::ops::RangeInclusive<NonZeroUsize> {
+  NonZeroUsize::new(min).unwrap()..=NonZeroUsize::new(max).unwrap()
+}
+
+#[test]
+fn encoded_len_bounds() {
+  assert_eq!(Ipv4Addr::ENCODED_LEN_RANGE, range(1, 5));
+  assert_eq!(Ipv6Addr::ENCODED_LEN_RANGE, range(1, 19));
+  assert_eq!(IpAddr::ENCODED_LEN_RANGE, range(2, 20));
+  assert_eq!(SocketAddrV4::ENCODED_LEN_RANGE, range(2, 8));
+  assert_eq!(SocketAddrV6::ENCODED_LEN_RANGE, range(4, 32));
+  assert_eq!(SocketAddr::ENCODED_LEN_RANGE, range(3, 33));
+
+  let max = SocketAddr::V6(SocketAddrV6::new(
+    Ipv6Addr::from_bits(u128::MAX),
+    u16::MAX,
+    u32::MAX,
+    u32::MAX,
+  ));
+  assert_eq!(max.encoded_len(), SocketAddr::MAX_ENCODED_LEN);
+  let min = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, 0));
+  assert_eq!(min.encoded_len(), SocketAddr::MIN_ENCODED_LEN);
+}
+
+#[test]
+fn encoding() {
+  let addr = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::new(0, 0, 1, 44), 300));
+  assert_eq!(
+    encode_socket_addr(&addr).as_slice(),
+    [0, 0xAC, 0x02, 0xAC, 0x02]
+  );
+
+  let addr = IpAddr::V6(Ipv6Addr::LOCALHOST);
+  assert_eq!(encode_ip_addr(&addr).as_slice(), [1, 1]);
+}
+
+#[test]
+fn decode_errors() {
+  assert!(matches!(
+    decode_ip_addr(&[2, 0]),
+    Err(ConstDecodeError::Other(_))
+  ));
+  assert!(matches!(
+    decode_socket_addr(&[7, 0, 0]),
+    Err(ConstDecodeError::Other(_))
+  ));
+  assert!(matches!(
+    decode_socket_addr(&[]),
+    Err(ConstDecodeError::InsufficientData(_))
+  ));
+
+  // Shortfalls are reported against the whole buffer.
+  let Err(ConstDecodeError::InsufficientData(e)) = decode_socket_addr(&[0, 1, 0x80]) else {
+    panic!("expected insufficient data");
+  };
+  assert_eq!(e.available(), 3);
+}
@@ -1,5 +1,7 @@
 use core::num::*;
 
+use crate::{ConstDecodeError, ConstEncodeError, DecodeError, EncodeError, Varint, utils::Buffer};
+
 macro_rules! impl_for_non_zero {
   ($($ty:ident), +$(,)?) => {
     $(
@@ -36,5 +38,119 @@ macro_rules! impl_for_non_zero {
 
 impl_for_non_zero!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128,);
 
+/// A non-zero integer encoded as the LEB128 varint of `n - 1`.
+///
+/// The plain `Varint` impls of the `NonZero*` types write the same bytes as the integer,
+/// so the encoding of zero is never produced and is rejected when decoding. Storing
+/// `n - 1` instead puts that codepoint to use, so every value right above a 7-bit boundary
+/// (128, 16384, ...) takes one byte less. The encodings are not compatible with each other.
+///
+/// ## Example
+///
+/// ```rust
+/// use core::num::NonZeroU64;
+/// use varing::{NonZeroCompact, Varint};
+///
+/// let value = NonZeroU64::new(128).unwrap();
+/// assert_eq!(value.encoded_len().get(), 2);
+/// assert_eq!(NonZeroCompact(value).encoded_len().get(), 1);
+///
+/// let mut buf = [0u8; 10];
+/// let written = NonZeroCompact(value).encode(&mut buf).unwrap();
+/// assert_eq!(&buf[..written.get()], [0x7F]);
+/// assert_eq!(NonZeroCompact::<NonZeroU64>::decode(&buf).unwrap().1, NonZeroCompact(value));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[repr(transparent)]
+pub struct NonZeroCompact<T>(pub T);
+
+macro_rules! non_zero_compact {
+  ($($bits:literal), +$(,)?) => {
+    $(
+      paste::paste! {
+        #[doc = "Returns the encoded length of a `NonZeroU" $bits "` value stored as `n - 1` in LEB128 variable length format."]
+        #[doc = "The returned value will be in range of [`NonZeroCompact::<NonZeroU" $bits ">::ENCODED_LEN_RANGE`]."]
+        #[inline]
+        pub const fn [< encoded_nonzero_u $bits _varint_minus_one_len >](value: [< NonZeroU $bits >]) -> NonZeroUsize {
+          crate::[< encoded_u $bits _varint_len >](value.get() - 1)
+        }
+
+        #[doc = "Encodes a `NonZeroU" $bits "` value as the LEB128 varint of `n - 1`, and writes it to the buffer."]
+        #[inline]
+        pub const fn [< encode_nonzero_u $bits _varint_minus_one >](value: [< NonZeroU $bits >]) -> Buffer<{ [< u $bits >]::MAX_ENCODED_LEN.get() + 1 }> {
+          crate::[< encode_u $bits _varint >](value.get() - 1)
+        }
+
+        #[doc = "Encodes a `NonZeroU" $bits "` value as the LEB128 varint of `n - 1`, and writes it to the buffer."]
+        ///
+        /// Returns the number of bytes written to the buffer.
+        #[inline]
+        pub const fn [< encode_nonzero_u $bits _varint_minus_one_to >](value: [< NonZeroU $bits >], buf: &mut [u8]) -> Result<NonZeroUsize, ConstEncodeError> {
+          crate::[< encode_u $bits _varint_to >](value.get() - 1, buf)
+        }
+
+        #[doc = "Decodes a `NonZeroU" $bits "` stored as the LEB128 varint of `n - 1` from the buffer."]
+        ///
+        #[doc = "Fails with [`ConstDecodeError::Overflow`] for `u" $bits "::MAX`, the only value that is not `n - 1` of a `NonZeroU" $bits "`."]
+        ///
+        /// Returns the bytes read and the decoded value if successful.
+        #[inline]
+        pub const fn [< decode_nonzero_u $bits _varint_minus_one >](buf: &[u8]) -> Result<(NonZeroUsize, [< NonZeroU $bits >]), ConstDecodeError> {
+          match crate::[< decode_u $bits _varint >](buf) {
+            Ok((bytes_read, value)) => match value.checked_add(1) {
+              // Safety: `value + 1` did not overflow, so it is at least 1.
+              Some(value) => Ok((bytes_read, unsafe { [< NonZeroU $bits >]::new_unchecked(value) })),
+              None => Err(ConstDecodeError::Overflow),
+            },
+            Err(e) => Err(e),
+          }
+        }
+
+        impl Varint for NonZeroCompact<[< NonZeroU $bits >]> {
+          const MIN_ENCODED_LEN: NonZeroUsize = [< u $bits >]::MIN_ENCODED_LEN;
+
+          const MAX_ENCODED_LEN: NonZeroUsize = [< encoded_nonzero_u $bits _varint_minus_one_len >]([< NonZeroU $bits >]::MAX);
+
+          #[inline]
+          fn encoded_len(&self) -> NonZeroUsize {
+            [< encoded_nonzero_u $bits _varint_minus_one_len >](self.0)
+          }
+
+          #[inline]
+          fn encode(&self, buf: &mut [u8]) -> Result<NonZeroUsize, EncodeError> {
+            [< encode_nonzero_u $bits _varint_minus_one_to >](self.0, buf).map_err(Into::into)
+          }
+
+          #[inline]
+          fn decode(buf: &[u8]) -> Result<(NonZeroUsize, Self), DecodeError>
+          where
+            Self: Sized,
+          {
+            [< decode_nonzero_u $bits _varint_minus_one >](buf)
+              .map(|(read, value)| (read, Self(value)))
+              .map_err(Into::into)
+          }
+        }
+
+        impl From<[< NonZeroU $bits >]> for NonZeroCompact<[< NonZeroU $bits >]> {
+          #[inline]
+          fn from(value: [< NonZeroU $bits >]) -> Self {
+            Self(value)
+          }
+        }
+
+        impl From<NonZeroCompact<[< NonZeroU $bits >]>> for [< NonZeroU $bits >] {
+          #[inline]
+          fn from(value: NonZeroCompact<[< NonZeroU $bits >]>) -> Self {
+            value.0
+          }
+        }
+      }
+    )*
+  };
+}
+
+non_zero_compact!(8, 16, 32, 64, 128);
+
 #[cfg(test)]
 mod tests;
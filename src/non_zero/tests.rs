@@ -38,3 +38,80 @@ macro_rules! fuzzy {
 }
 
 fuzzy!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128,);
+
+macro_rules! fuzzy_compact {
+  ($($bits:literal), +$(,)?) => {
+    $(
+      paste::paste! {
+        #[quickcheck_macros::quickcheck]
+        fn [< fuzzy_non_zero_compact_u $bits >](value: [< u $bits >]) -> bool {
+          let Some(value) = [< NonZeroU $bits >]::new(value) else {
+            return true;
+          };
+
+          let encoded = crate::[< encode_nonzero_u $bits _varint_minus_one >](value);
+          if encoded.as_slice() != crate::[< encode_u $bits _varint >](value.get() - 1).as_slice() {
+            return false;
+          }
+
+          let value = crate::NonZeroCompact(value);
+          let mut buf = [0; { <crate::NonZeroCompact<[< NonZeroU $bits >]>>::MAX_ENCODED_LEN.get() }];
+          let Ok(encoded_len) = value.encode(&mut buf) else { return false; };
+          if encoded_len != value.encoded_len()
+            || encoded_len.get() != encoded.len()
+            || value.encoded_len() > value.0.encoded_len()
+          {
+            return false;
+          }
+
+          matches!(
+            <crate::NonZeroCompact<[< NonZeroU $bits >]>>::decode(&buf),
+            Ok((bytes_read, decoded)) if decoded == value && bytes_read == encoded_len
+          )
+        }
+
+        #[quickcheck_macros::quickcheck]
+        fn [< fuzzy_non_zero_compact_u $bits _decodes_any_varint >](value: [< u $bits >]) -> bool {
+          let encoded = crate::[< encode_u $bits _varint >](value);
+          match crate::[< decode_nonzero_u $bits _varint_minus_one >](encoded.as_slice()) {
+            Ok((_, decoded)) => decoded.get() - 1 == value,
+            Err(crate::ConstDecodeError::Overflow) => value == [< u $bits >]::MAX,
+            Err(_) => false,
+          }
+        }
+      }
+    )*
+  };
+}
+
+fuzzy_compact!(8, 16, 32, 64, 128);
+
+#[test]
+fn compact_saves_a_byte_at_boundaries() {
+  use crate::{NonZeroCompact, encoded_nonzero_u64_varint_minus_one_len};
+
+  for boundary in [1u64 << 7, 1 << 14, 1 << 21, 1 << 63] {
+    let value = NonZeroU64::new(boundary).unwrap();
+    assert_eq!(
+      encoded_nonzero_u64_varint_minus_one_len(value).get() + 1,
+      value.encoded_len().get()
+    );
+  }
+
+  assert_eq!(
+    NonZeroCompact::<NonZeroU64>::decode(&[0]).unwrap().1,
+    NonZeroCompact(NonZeroU64::MIN)
+  );
+  assert_eq!(
+    NonZeroCompact::<NonZeroU64>::MAX_ENCODED_LEN,
+    u64::MAX_ENCODED_LEN
+  );
+  assert_eq!(
+    NonZeroCompact::<NonZeroU8>::ENCODED_LEN_RANGE,
+    u8::ENCODED_LEN_RANGE
+  );
+  assert!(matches!(
+    NonZeroCompact::<NonZeroU8>::decode(&[0xFF, 0x01]),
+    Err(crate::DecodeError::Overflow)
+  ));
+}
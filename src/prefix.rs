@@ -0,0 +1,267 @@
+use core::num::NonZeroUsize;
+
+use super::{ConstDecodeError, ConstEncodeError, DecodeError, EncodeError, Varint, utils};
+
+macro_rules! prefix {
+  ($($bits:literal), +$(,)?) => {
+    $(
+      paste::paste! {
+        #[doc = "Returns the encoded length of an `u" $bits "` value in prefix varint format."]
+        #[doc = "The returned value will be in range of [`Prefix::<u" $bits ">::ENCODED_LEN_RANGE`]."]
+        #[inline]
+        pub const fn [< encoded_u $bits _prefix_len >](value: [< u $bits >]) -> NonZeroUsize {
+          const MAX_LEN: usize = max_len([< u $bits >]::BITS);
+
+          let bits = [< u $bits >]::BITS - value.leading_zeros();
+          let len = bits.div_ceil(7) as usize;
+          let len = if len == 0 {
+            1
+          } else if len > MAX_LEN {
+            MAX_LEN
+          } else {
+            len
+          };
+          // Safety: `len` is at least 1
+          unsafe { NonZeroUsize::new_unchecked(len) }
+        }
+
+        #[doc = "Encodes an `u" $bits "` value in prefix varint format, and writes it to the buffer."]
+        ///
+        /// Returns the number of bytes written. Nothing is written if the buffer is too small.
+        #[inline]
+        pub const fn [< encode_u $bits _prefix_to >](value: [< u $bits >], buf: &mut [u8]) -> Result<NonZeroUsize, ConstEncodeError> {
+          let len = [< encoded_u $bits _prefix_len >](value);
+          if buf.len() < len.get() {
+            return Err(ConstEncodeError::insufficient_space(len, buf.len()));
+          }
+
+          let len = len.get();
+          let mut i = 0;
+          while i < len {
+            let shift = 8 * (len - 1 - i) as u32;
+            let byte = if shift < [< u $bits >]::BITS { (value >> shift) as u8 } else { 0 };
+            buf[i] = byte | prefix_byte(len - 1, i);
+            i += 1;
+          }
+          // Safety: `len` is at least 1
+          Ok(unsafe { NonZeroUsize::new_unchecked(len) })
+        }
+
+        #[doc = "Decodes an `u" $bits "` in prefix varint format from the buffer."]
+        ///
+        /// The total length is known from the leading bytes, so if the buffer is too short the
+        /// error carries it as [`InsufficientData::required`](crate::InsufficientData::required).
+        ///
+        /// Returns the bytes read and the decoded value if successful.
+        pub const fn [< decode_u $bits _prefix >](buf: &[u8]) -> Result<(NonZeroUsize, [< u $bits >]), ConstDecodeError> {
+          const MAX_LEN: usize = max_len([< u $bits >]::BITS);
+
+          let (len, prefix_bits) = match decode_prefix(buf, MAX_LEN) {
+            Ok(res) => res,
+            Err(e) => return Err(e),
+          };
+
+          let mut result: [< u $bits >] = 0;
+          let mut i = 0;
+          while i < len.get() {
+            let byte = buf[i] & payload_mask(prefix_bits, i);
+            let shift = 8 * (len.get() - 1 - i) as u32;
+            if byte != 0 {
+              if shift >= [< u $bits >]::BITS
+                || (shift + 8 > [< u $bits >]::BITS && byte >> ([< u $bits >]::BITS - shift) != 0)
+              {
+                return Err(ConstDecodeError::Overflow);
+              }
+              result |= (byte as [< u $bits >]) << shift;
+            }
+            i += 1;
+          }
+          Ok((len, result))
+        }
+
+        #[doc = "Returns the encoded length of an `i" $bits "` value in prefix varint format."]
+        #[doc = "The returned value will be in range of [`Prefix::<i" $bits ">::ENCODED_LEN_RANGE`]."]
+        #[inline]
+        pub const fn [< encoded_i $bits _prefix_len >](value: [< i $bits >]) -> NonZeroUsize {
+          [< encoded_u $bits _prefix_len >](utils::[< zigzag_encode_i $bits >](value))
+        }
+
+        #[doc = "Encodes an `i" $bits "` value in zigzag and prefix varint format, and writes it to the buffer."]
+        ///
+        /// Returns the number of bytes written. Nothing is written if the buffer is too small.
+        #[inline]
+        pub const fn [< encode_i $bits _prefix_to >](value: [< i $bits >], buf: &mut [u8]) -> Result<NonZeroUsize, ConstEncodeError> {
+          [< encode_u $bits _prefix_to >](utils::[< zigzag_encode_i $bits >](value), buf)
+        }
+
+        #[doc = "Decodes an `i" $bits "` in zigzag and prefix varint format from the buffer."]
+        ///
+        /// Returns the bytes read and the decoded value if successful.
+        pub const fn [< decode_i $bits _prefix >](buf: &[u8]) -> Result<(NonZeroUsize, [< i $bits >]), ConstDecodeError> {
+          match [< decode_u $bits _prefix >](buf) {
+            Ok((read, value)) => Ok((read, utils::[< zigzag_decode_i $bits >](value))),
+            Err(e) => Err(e),
+          }
+        }
+
+        impl_prefix_varint!([< u $bits >], [< i $bits >]);
+      }
+    )*
+  };
+}
+
+macro_rules! impl_prefix_varint {
+  ($($ty:ident), +$(,)?) => {
+    $(
+      paste::paste! {
+        impl Varint for Prefix<$ty> {
+          const MIN_ENCODED_LEN: NonZeroUsize = [< encoded_ $ty _prefix_len >](0);
+          const MAX_ENCODED_LEN: NonZeroUsize = [< encoded_ $ty _prefix_len >]($ty::MAX);
+
+          #[inline]
+          fn encoded_len(&self) -> NonZeroUsize {
+            [< encoded_ $ty _prefix_len >](self.0)
+          }
+
+          #[inline]
+          fn encode(&self, buf: &mut [u8]) -> Result<NonZeroUsize, EncodeError> {
+            [< encode_ $ty _prefix_to >](self.0, buf).map_err(Into::into)
+          }
+
+          #[inline]
+          fn decode(buf: &[u8]) -> Result<(NonZeroUsize, Self), DecodeError>
+          where
+            Self: Sized,
+          {
+            [< decode_ $ty _prefix >](buf)
+              .map(|(read, value)| (read, Self(value)))
+              .map_err(Into::into)
+          }
+        }
+
+        impl From<$ty> for Prefix<$ty> {
+          #[inline]
+          fn from(value: $ty) -> Self {
+            Self(value)
+          }
+        }
+
+        impl From<Prefix<$ty>> for $ty {
+          #[inline]
+          fn from(value: Prefix<$ty>) -> Self {
+            value.0
+          }
+        }
+      }
+    )*
+  };
+}
+
+prefix!(8, 16, 32, 64, 128);
+
+/// An integer encoded in prefix varint format.
+///
+/// Instead of a continuation bit in every byte, the total length of a prefix varint is
+/// stored up front, like in UTF-8: an encoding of `n` bytes starts with `n - 1` one bits
+/// followed by a zero bit, and the remaining bits hold the value in big-endian order. A
+/// decoder learns the length from the first byte (or first few bytes for `u128`) and can
+/// then read the value without inspecting every byte. The zero bit is omitted from the
+/// longest encoding of a type, so a `u64` takes at most 9 bytes, like LEB128 would need 10.
+///
+/// Signed integers are zigzag encoded first, like the [`Varint`] implementations of the bare
+/// integers. `u8` values, which do not benefit from a length prefix, are always one byte.
+///
+/// ## Example
+///
+/// ```rust
+/// use varing::{Varint, prefix::Prefix};
+///
+/// let mut buf = [0u8; 9];
+/// assert_eq!(Prefix(300u64).encode(&mut buf).unwrap().get(), 2);
+/// assert_eq!(&buf[..2], [0x81, 0x2C]);
+/// assert_eq!(Prefix::<u64>::decode(&buf).unwrap().1, Prefix(300));
+///
+/// assert_eq!(Prefix(u64::MAX).encode(&mut buf).unwrap().get(), 9);
+/// assert_eq!(buf, [0xFF; 9]);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[repr(transparent)]
+pub struct Prefix<T>(pub T);
+
+/// Returns the maximum encoded length of a `bits` wide integer, the smallest `n` for which
+/// `n - 1` prefix bits leave room for the value in `n` bytes.
+#[inline]
+const fn max_len(bits: u32) -> usize {
+  (bits as usize - 1).div_ceil(7)
+}
+
+/// Returns the prefix bits of byte `idx` of an encoding with `ones` leading one bits.
+#[inline]
+const fn prefix_byte(ones: usize, idx: usize) -> u8 {
+  let start = 8 * idx;
+  if ones <= start {
+    0
+  } else if ones - start >= 8 {
+    0xFF
+  } else {
+    !(0xFF >> (ones - start))
+  }
+}
+
+/// Returns the mask of the value bits of byte `idx` of an encoding with `prefix_bits` prefix
+/// bits, the leading ones and the zero bit if any.
+#[inline]
+const fn payload_mask(prefix_bits: usize, idx: usize) -> u8 {
+  let start = 8 * idx;
+  if prefix_bits <= start {
+    0xFF
+  } else if prefix_bits - start >= 8 {
+    0
+  } else {
+    0xFF >> (prefix_bits - start)
+  }
+}
+
+/// Decodes the length prefix, returning the encoded length and the number of prefix bits.
+///
+/// Checks that the buffer holds the whole encoding.
+#[inline]
+const fn decode_prefix(
+  buf: &[u8],
+  max_len: usize,
+) -> Result<(NonZeroUsize, usize), ConstDecodeError> {
+  let max_ones = max_len - 1;
+  let mut ones = 0;
+  let mut i = 0;
+  while ones < max_ones {
+    if i >= buf.len() {
+      return Err(ConstDecodeError::insufficient_data(buf.len()));
+    }
+
+    let byte_ones = buf[i].leading_ones() as usize;
+    ones += byte_ones;
+    if byte_ones < 8 {
+      break;
+    }
+    i += 1;
+  }
+
+  let (ones, prefix_bits) = if ones >= max_ones {
+    (max_ones, max_ones)
+  } else {
+    (ones, ones + 1)
+  };
+
+  // Safety: +1 guaranteed to be non-zero
+  let len = unsafe { NonZeroUsize::new_unchecked(ones + 1) };
+  if buf.len() < len.get() {
+    return Err(ConstDecodeError::insufficient_data_with_required(
+      len,
+      buf.len(),
+    ));
+  }
+  Ok((len, prefix_bits))
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,131 @@
+use super::*;
+
+use quickcheck_macros::quickcheck;
+
+macro_rules! prefix_roundtrip {
+  ($($ty:ident), +$(,)?) => {
+    $(
+      paste::paste! {
+        #[quickcheck]
+        fn [< prefix_ $ty _roundtrip >](value: $ty) -> bool {
+          let mut buf = [0u8; Prefix::<$ty>::MAX_ENCODED_LEN.get()];
+          let len = [< encode_ $ty _prefix_to >](value, &mut buf).unwrap();
+          len == [< encoded_ $ty _prefix_len >](value)
+            && Prefix::<$ty>::ENCODED_LEN_RANGE.contains(&len)
+            && [< decode_ $ty _prefix >](&buf[..len.get()]) == Ok((len, value))
+            && Prefix::<$ty>::decode_canonical(&buf) == Ok((len, Prefix(value)))
+        }
+
+        #[quickcheck]
+        fn [< prefix_ $ty _insufficient_data >](value: $ty) -> bool {
+          let mut buf = [0u8; Prefix::<$ty>::MAX_ENCODED_LEN.get()];
+          let len = [< encode_ $ty _prefix_to >](value, &mut buf).unwrap().get();
+          (0..len).all(|available| {
+            [< decode_ $ty _prefix >](&buf[..available]).is_err()
+              && [< encode_ $ty _prefix_to >](value, &mut buf[..available]).is_err()
+          })
+        }
+      }
+    )*
+  };
+}
+
+prefix_roundtrip!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128);
+
+#[test]
+fn max_encoded_len() {
+  assert_eq!(Prefix::<u8>::MAX_ENCODED_LEN.get(), 1);
+  assert_eq!(Prefix::<u16>::MAX_ENCODED_LEN.get(), 3);
+  assert_eq!(Prefix::<u32>::MAX_ENCODED_LEN.get(), 5);
+  assert_eq!(Prefix::<u64>::MAX_ENCODED_LEN.get(), 9);
+  assert_eq!(Prefix::<u128>::MAX_ENCODED_LEN.get(), 19);
+  assert_eq!(Prefix::<i64>::MAX_ENCODED_LEN.get(), 9);
+  assert_eq!(Prefix::<u64>::MIN_ENCODED_LEN.get(), 1);
+}
+
+#[test]
+fn known_vectors() {
+  for (value, expected) in [
+    (0u64, &[0x00][..]),
+    (127, &[0x7F]),
+    (128, &[0x80, 0x80]),
+    (16383, &[0xBF, 0xFF]),
+    (16384, &[0xC0, 0x40, 0x00]),
+    (
+      (1 << 56) - 1,
+      &[0xFE, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF],
+    ),
+    (
+      1 << 56,
+      &[0xFF, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+    ),
+    (u64::MAX, &[0xFF; 9]),
+  ] {
+    let mut buf = [0u8; 9];
+    let len = encode_u64_prefix_to(value, &mut buf).unwrap().get();
+    assert_eq!(&buf[..len], expected, "{value}");
+    assert_eq!(decode_u64_prefix(expected).unwrap().1, value);
+  }
+
+  let mut buf = [0u8; 19];
+  assert_eq!(
+    encode_u128_prefix_to(u128::MAX, &mut buf).unwrap().get(),
+    19
+  );
+  assert_eq!(buf[..3], [0xFF, 0xFF, 0xC0]);
+  assert_eq!(buf[3..], [0xFF; 16]);
+
+  assert_eq!(encode_u8_prefix_to(0xFF, &mut buf).unwrap().get(), 1);
+  assert_eq!(buf[0], 0xFF);
+  assert_eq!(encode_i32_prefix_to(-1, &mut buf).unwrap().get(), 1);
+  assert_eq!(buf[0], 0x01);
+}
+
+#[test]
+fn overflow() {
+  // The value bits of the longest encoding are wider than the type.
+  assert_eq!(
+    decode_u16_prefix(&[0xC1, 0x00, 0x00]),
+    Err(ConstDecodeError::Overflow)
+  );
+  assert_eq!(decode_u16_prefix(&[0xC0, 0xFF, 0xFF]).unwrap().1, u16::MAX);
+  assert_eq!(
+    decode_u32_prefix(&[0xF1, 0x00, 0x00, 0x00, 0x00]),
+    Err(ConstDecodeError::Overflow)
+  );
+
+  let mut buf = [0u8; 19];
+  buf[..3].copy_from_slice(&[0xFF, 0xFF, 0xC1]);
+  assert_eq!(decode_u128_prefix(&buf), Err(ConstDecodeError::Overflow));
+}
+
+#[test]
+fn insufficient_data_reports_required() {
+  assert_eq!(
+    decode_u64_prefix(&[0xC0, 0x40]),
+    Err(ConstDecodeError::insufficient_data_with_required(
+      NonZeroUsize::new(3).unwrap(),
+      2
+    ))
+  );
+  assert_eq!(
+    decode_u64_prefix(&[]),
+    Err(ConstDecodeError::insufficient_data(0))
+  );
+  // The prefix of a long `u128` spans several bytes.
+  assert_eq!(
+    decode_u128_prefix(&[0xFF]),
+    Err(ConstDecodeError::insufficient_data(1))
+  );
+}
+
+#[test]
+fn overlong() {
+  // Accepted by `decode`, rejected by `decode_canonical`.
+  let buf = [0x80, 0x01];
+  assert_eq!(decode_u32_prefix(&buf).unwrap().1, 1);
+  assert_eq!(
+    Prefix::<u32>::decode_canonical(&buf),
+    Err(DecodeError::NonCanonical)
+  );
+}
@@ -3,10 +3,11 @@ use core::num::{NonZeroU64, NonZeroUsize};
 use super::{
   ConstDecodeError, ConstEncodeError, DecodeError, EncodeError, Varint,
   utils::{self, zigzag_encode_i64},
+  varint_required_len,
 };
 
 macro_rules! impl_varint {
-  ($($ty:literal), +$(,)?) => {
+  ($decode:ident => $($ty:literal), +$(,)?) => {
     $(
       paste::paste! {
         impl Varint for [< u $ty >] {
@@ -24,7 +25,17 @@ macro_rules! impl_varint {
 
           #[inline]
           fn decode(buf: &[u8]) -> Result<(::core::num::NonZeroUsize, Self), DecodeError> {
-            [< decode_ u $ty _varint >](buf).map_err(Into::into)
+            $decode::[< decode_ u $ty _varint >](buf).map_err(Into::into)
+          }
+
+          #[inline]
+          fn decode_canonical(buf: &[u8]) -> Result<(::core::num::NonZeroUsize, Self), DecodeError> {
+            [< decode_ u $ty _varint_canonical >](buf).map_err(Into::into)
+          }
+
+          #[inline]
+          fn required_len(buf: &[u8]) -> Result<::core::num::NonZeroUsize, DecodeError> {
+            varint_required_len(buf, Self::MAX_ENCODED_LEN)
           }
         }
 
@@ -43,7 +54,20 @@ macro_rules! impl_varint {
 
           #[inline]
           fn decode(buf: &[u8]) -> Result<(::core::num::NonZeroUsize, Self), DecodeError> {
-            [< decode_ i $ty _varint >](buf).map_err(Into::into)
+            match $decode::[< decode_ u $ty _varint >](buf) {
+              Ok((bytes_read, value)) => Ok((bytes_read, utils::[< zigzag_decode_i $ty >](value))),
+              Err(e) => Err(e.into()),
+            }
+          }
+
+          #[inline]
+          fn decode_canonical(buf: &[u8]) -> Result<(::core::num::NonZeroUsize, Self), DecodeError> {
+            [< decode_ i $ty _varint_canonical >](buf).map_err(Into::into)
+          }
+
+          #[inline]
+          fn required_len(buf: &[u8]) -> Result<::core::num::NonZeroUsize, DecodeError> {
+            varint_required_len(buf, Self::MAX_ENCODED_LEN)
           }
         }
       }
@@ -296,12 +320,48 @@ macro_rules! decode {
             Err(e) => Err(e),
           }
         }
+
+        #[doc = "Decodes a `u" $ty "` in LEB128 encoded format from the buffer, rejecting overlong encodings."]
+        ///
+        #[doc = "Unlike [`decode_u" $ty "_varint`], which also accepts zero-padded encodings such as"]
+        /// `[0x80, 0x00]` for `0`, this returns [`ConstDecodeError::NonCanonical`] unless the value
+        /// is encoded in its shortest form, so every value has exactly one accepted encoding.
+        ///
+        /// Returns the bytes read and the decoded value if successful.
+        pub const fn [< decode_ u $ty _varint_canonical >](buf: &[u8]) -> Result<(::core::num::NonZeroUsize, [< u $ty >]), ConstDecodeError> {
+          match [< decode_ u $ty _varint >](buf) {
+            Ok((bytes_read, value)) => {
+              // The shortest encoding never ends with a zero continuation byte.
+              if bytes_read.get() > 1 && buf[bytes_read.get() - 1] == 0 {
+                return Err(ConstDecodeError::NonCanonical);
+              }
+              Ok((bytes_read, value))
+            },
+            Err(e) => Err(e),
+          }
+        }
+
+        #[doc = "Decodes an `i" $ty "` in LEB128 encoded format from the buffer, rejecting overlong encodings."]
+        ///
+        #[doc = "See [`decode_u" $ty "_varint_canonical`] for details."]
+        ///
+        /// Returns the bytes read and the decoded value if successful.
+        pub const fn [< decode_ i $ty _varint_canonical >](buf: &[u8]) -> Result<(::core::num::NonZeroUsize, [< i $ty >]), ConstDecodeError> {
+          match [< decode_ u $ty _varint_canonical >](buf) {
+            Ok((bytes_read, value)) => {
+              let value = utils::[<zigzag_decode_i $ty>](value);
+              Ok((bytes_read, value))
+            },
+            Err(e) => Err(e),
+          }
+        }
       }
     )*
   };
 }
 
-impl_varint!(8, 16, 32, 64, 128,);
+impl_varint!(fast => 64,);
+impl_varint!(self => 8, 16, 32, 128,);
 varint_len!(u8, u16, u32,);
 varint_len!(@zigzag i8, i16, i32,);
 encode!(128, 64, 32, 16, 8);
@@ -410,6 +470,22 @@ impl Varint for f32 {
   {
     decode_f32_varint(buf).map_err(Into::into)
   }
+
+  #[inline]
+  fn decode_canonical(buf: &[u8]) -> Result<(NonZeroUsize, Self), DecodeError>
+  where
+    Self: Sized,
+  {
+    decode_f32_varint_canonical(buf).map_err(Into::into)
+  }
+
+  #[inline]
+  fn required_len(buf: &[u8]) -> Result<NonZeroUsize, DecodeError>
+  where
+    Self: Sized,
+  {
+    varint_required_len(buf, Self::MAX_ENCODED_LEN)
+  }
 }
 
 impl Varint for f64 {
@@ -434,6 +510,22 @@ impl Varint for f64 {
   {
     decode_f64_varint(buf).map_err(Into::into)
   }
+
+  #[inline]
+  fn decode_canonical(buf: &[u8]) -> Result<(NonZeroUsize, Self), DecodeError>
+  where
+    Self: Sized,
+  {
+    decode_f64_varint_canonical(buf).map_err(Into::into)
+  }
+
+  #[inline]
+  fn required_len(buf: &[u8]) -> Result<NonZeroUsize, DecodeError>
+  where
+    Self: Sized,
+  {
+    varint_required_len(buf, Self::MAX_ENCODED_LEN)
+  }
 }
 
 /// Returns the encoded length of the value in LEB128 variable length format. The returned value will be in range of [`f32::ENCODED_LEN_RANGE`].
@@ -470,6 +562,21 @@ pub const fn decode_f32_varint(buf: &[u8]) -> Result<(NonZeroUsize, f32), crate:
   }
 }
 
+/// Decodes an `f32` in LEB128 encoded format from the buffer, rejecting overlong encodings.
+///
+/// See [`decode_u32_varint_canonical`] for details.
+///
+/// Returns the bytes read and the decoded value if successful.
+#[inline]
+pub const fn decode_f32_varint_canonical(
+  buf: &[u8],
+) -> Result<(NonZeroUsize, f32), crate::ConstDecodeError> {
+  match crate::decode_u32_varint_canonical(buf) {
+    Ok((len, bits)) => Ok((len, f32::from_bits(bits))),
+    Err(e) => Err(e),
+  }
+}
+
 /// Returns the encoded length of the value in LEB128 variable length format. The returned value will be in range of [`f64::ENCODED_LEN_RANGE`].
 #[inline]
 pub const fn encoded_f64_varint_len(value: f64) -> NonZeroUsize {
@@ -504,6 +611,21 @@ pub const fn decode_f64_varint(buf: &[u8]) -> Result<(NonZeroUsize, f64), crate:
   }
 }
 
+/// Decodes an `f64` in LEB128 encoded format from the buffer, rejecting overlong encodings.
+///
+/// See [`decode_u64_varint_canonical`] for details.
+///
+/// Returns the bytes read and the decoded value if successful.
+#[inline]
+pub const fn decode_f64_varint_canonical(
+  buf: &[u8],
+) -> Result<(NonZeroUsize, f64), crate::ConstDecodeError> {
+  match crate::decode_u64_varint_canonical(buf) {
+    Ok((len, bits)) => Ok((len, f64::from_bits(bits))),
+    Err(e) => Err(e),
+  }
+}
+
 /// Returns the encoded length of a sequence of `f32` values
 #[inline]
 pub const fn encoded_f32_sequence_len(sequence: &[f32]) -> usize {
@@ -534,6 +656,260 @@ pub const fn encode_f64_sequence_to(
   encode!(@sequence_encode_to_impl buf, sequence, encode_f64_varint_to, encoded_f64_sequence_len)
 }
 
+impl Varint for usize {
+  const MIN_ENCODED_LEN: NonZeroUsize = u64::MIN_ENCODED_LEN;
+
+  const MAX_ENCODED_LEN: NonZeroUsize = u64::MAX_ENCODED_LEN;
+
+  #[inline]
+  fn encoded_len(&self) -> NonZeroUsize {
+    encoded_usize_varint_len(*self)
+  }
+
+  #[inline]
+  fn encode(&self, buf: &mut [u8]) -> Result<NonZeroUsize, EncodeError> {
+    encode_usize_varint_to(*self, buf).map_err(Into::into)
+  }
+
+  #[inline]
+  fn decode(buf: &[u8]) -> Result<(NonZeroUsize, Self), DecodeError>
+  where
+    Self: Sized,
+  {
+    fast::decode_u64_varint(buf)
+      .and_then(|(len, value)| u64_to_usize(len, value))
+      .map_err(Into::into)
+  }
+
+  #[inline]
+  fn decode_canonical(buf: &[u8]) -> Result<(NonZeroUsize, Self), DecodeError>
+  where
+    Self: Sized,
+  {
+    decode_usize_varint_canonical(buf).map_err(Into::into)
+  }
+
+  #[inline]
+  fn required_len(buf: &[u8]) -> Result<NonZeroUsize, DecodeError>
+  where
+    Self: Sized,
+  {
+    varint_required_len(buf, Self::MAX_ENCODED_LEN)
+  }
+}
+
+impl Varint for isize {
+  const MIN_ENCODED_LEN: NonZeroUsize = i64::MIN_ENCODED_LEN;
+
+  const MAX_ENCODED_LEN: NonZeroUsize = i64::MAX_ENCODED_LEN;
+
+  #[inline]
+  fn encoded_len(&self) -> NonZeroUsize {
+    encoded_isize_varint_len(*self)
+  }
+
+  #[inline]
+  fn encode(&self, buf: &mut [u8]) -> Result<NonZeroUsize, EncodeError> {
+    encode_isize_varint_to(*self, buf).map_err(Into::into)
+  }
+
+  #[inline]
+  fn decode(buf: &[u8]) -> Result<(NonZeroUsize, Self), DecodeError>
+  where
+    Self: Sized,
+  {
+    fast::decode_u64_varint(buf)
+      .and_then(|(len, value)| i64_to_isize(len, utils::zigzag_decode_i64(value)))
+      .map_err(Into::into)
+  }
+
+  #[inline]
+  fn decode_canonical(buf: &[u8]) -> Result<(NonZeroUsize, Self), DecodeError>
+  where
+    Self: Sized,
+  {
+    decode_isize_varint_canonical(buf).map_err(Into::into)
+  }
+
+  #[inline]
+  fn required_len(buf: &[u8]) -> Result<NonZeroUsize, DecodeError>
+  where
+    Self: Sized,
+  {
+    varint_required_len(buf, Self::MAX_ENCODED_LEN)
+  }
+}
+
+/// Returns the encoded length of the value in LEB128 variable length format. The returned value will be in range of [`usize::ENCODED_LEN_RANGE`].
+///
+/// `usize` is always encoded as a `u64`, so the encoding is the same on every target.
+#[inline]
+pub const fn encoded_usize_varint_len(value: usize) -> NonZeroUsize {
+  encoded_u64_varint_len(value as u64)
+}
+
+/// Encodes an `usize` value into LEB128 variable length format, and writes it to the buffer.
+///
+/// `usize` is always encoded as a `u64`, so the encoding is the same on every target.
+#[inline]
+pub const fn encode_usize_varint(
+  value: usize,
+) -> crate::utils::Buffer<{ usize::MAX_ENCODED_LEN.get() + 1 }> {
+  encode_u64_varint(value as u64)
+}
+
+/// Encodes an `usize` value into LEB128 variable length format, and writes it to the buffer.
+///
+/// `usize` is always encoded as a `u64`, so the encoding is the same on every target.
+#[inline]
+pub const fn encode_usize_varint_to(
+  value: usize,
+  buf: &mut [u8],
+) -> Result<NonZeroUsize, ConstEncodeError> {
+  encode_u64_varint_to(value as u64, buf)
+}
+
+/// Decodes an `usize` in LEB128 encoded format from the buffer.
+///
+/// The value is decoded as a `u64`, and [`ConstDecodeError::Overflow`] is returned if it
+/// does not fit in the target's pointer width.
+///
+/// Returns the bytes read and the decoded value if successful.
+#[inline]
+pub const fn decode_usize_varint(buf: &[u8]) -> Result<(NonZeroUsize, usize), ConstDecodeError> {
+  match decode_u64_varint(buf) {
+    Ok((len, value)) => u64_to_usize(len, value),
+    Err(e) => Err(e),
+  }
+}
+
+/// Decodes an `usize` in LEB128 encoded format from the buffer, rejecting overlong encodings.
+///
+/// See [`decode_u64_varint_canonical`] and [`decode_usize_varint`] for details.
+///
+/// Returns the bytes read and the decoded value if successful.
+#[inline]
+pub const fn decode_usize_varint_canonical(
+  buf: &[u8],
+) -> Result<(NonZeroUsize, usize), ConstDecodeError> {
+  match decode_u64_varint_canonical(buf) {
+    Ok((len, value)) => u64_to_usize(len, value),
+    Err(e) => Err(e),
+  }
+}
+
+#[inline]
+const fn u64_to_usize(
+  len: NonZeroUsize,
+  value: u64,
+) -> Result<(NonZeroUsize, usize), ConstDecodeError> {
+  if value > usize::MAX as u64 {
+    return Err(ConstDecodeError::Overflow);
+  }
+  Ok((len, value as usize))
+}
+
+/// Returns the encoded length of the value in LEB128 variable length format. The returned value will be in range of [`isize::ENCODED_LEN_RANGE`].
+///
+/// `isize` is always encoded as an `i64`, so the encoding is the same on every target.
+#[inline]
+pub const fn encoded_isize_varint_len(value: isize) -> NonZeroUsize {
+  encoded_i64_varint_len(value as i64)
+}
+
+/// Encodes an `isize` value into LEB128 variable length format, and writes it to the buffer.
+///
+/// `isize` is always encoded as an `i64`, so the encoding is the same on every target.
+#[inline]
+pub const fn encode_isize_varint(
+  value: isize,
+) -> crate::utils::Buffer<{ isize::MAX_ENCODED_LEN.get() + 1 }> {
+  encode_i64_varint(value as i64)
+}
+
+/// Encodes an `isize` value into LEB128 variable length format, and writes it to the buffer.
+///
+/// `isize` is always encoded as an `i64`, so the encoding is the same on every target.
+#[inline]
+pub const fn encode_isize_varint_to(
+  value: isize,
+  buf: &mut [u8],
+) -> Result<NonZeroUsize, ConstEncodeError> {
+  encode_i64_varint_to(value as i64, buf)
+}
+
+/// Decodes an `isize` in LEB128 encoded format from the buffer.
+///
+/// The value is decoded as an `i64`, and [`ConstDecodeError::Overflow`] is returned if it
+/// does not fit in the target's pointer width.
+///
+/// Returns the bytes read and the decoded value if successful.
+#[inline]
+pub const fn decode_isize_varint(buf: &[u8]) -> Result<(NonZeroUsize, isize), ConstDecodeError> {
+  match decode_i64_varint(buf) {
+    Ok((len, value)) => i64_to_isize(len, value),
+    Err(e) => Err(e),
+  }
+}
+
+/// Decodes an `isize` in LEB128 encoded format from the buffer, rejecting overlong encodings.
+///
+/// See [`decode_u64_varint_canonical`] and [`decode_isize_varint`] for details.
+///
+/// Returns the bytes read and the decoded value if successful.
+#[inline]
+pub const fn decode_isize_varint_canonical(
+  buf: &[u8],
+) -> Result<(NonZeroUsize, isize), ConstDecodeError> {
+  match decode_i64_varint_canonical(buf) {
+    Ok((len, value)) => i64_to_isize(len, value),
+    Err(e) => Err(e),
+  }
+}
+
+#[inline]
+const fn i64_to_isize(
+  len: NonZeroUsize,
+  value: i64,
+) -> Result<(NonZeroUsize, isize), ConstDecodeError> {
+  if value < isize::MIN as i64 || value > isize::MAX as i64 {
+    return Err(ConstDecodeError::Overflow);
+  }
+  Ok((len, value as isize))
+}
+
+/// Returns the encoded length of a sequence of `usize` values
+#[inline]
+pub const fn encoded_usize_sequence_len(sequence: &[usize]) -> usize {
+  encode!(@sequence_encoded_len_impl sequence, encoded_usize_varint_len)
+}
+
+/// Encodes a sequence of `usize` to the buffer.
+#[inline]
+pub const fn encode_usize_sequence_to(
+  sequence: &[usize],
+  buf: &mut [u8],
+) -> Result<usize, ConstEncodeError> {
+  encode!(@sequence_encode_to_impl buf, sequence, encode_usize_varint_to, encoded_usize_sequence_len)
+}
+
+/// Returns the encoded length of a sequence of `isize` values
+#[inline]
+pub const fn encoded_isize_sequence_len(sequence: &[isize]) -> usize {
+  encode!(@sequence_encoded_len_impl sequence, encoded_isize_varint_len)
+}
+
+/// Encodes a sequence of `isize` to the buffer.
+#[inline]
+pub const fn encode_isize_sequence_to(
+  sequence: &[isize],
+  buf: &mut [u8],
+) -> Result<usize, ConstEncodeError> {
+  encode!(@sequence_encode_to_impl buf, sequence, encode_isize_varint_to, encoded_isize_sequence_len)
+}
+
+mod fast;
+
 /// LEB128 encoding/decoding for [`half`](https://crates.io/crates/half) types.
 #[cfg(feature = "half_2")]
 mod half;
@@ -0,0 +1,106 @@
+//! Branch-light LEB128 decoding used by the [`Varint`](crate::Varint) implementations of
+//! `u64`, `i64`, `usize` and `isize`.
+//!
+//! The const `decode_*_varint` functions walk the buffer byte by byte. When at least eight
+//! bytes are available, this instead loads them as one little-endian `u64` and, unless the
+//! value fits in the first byte, finds the terminating byte from the continuation bits and
+//! gathers the 7-bit payloads with a few masks and shifts (or a single `pext` when BMI2 is
+//! enabled at compile time). Shorter buffers fall back to the const function, and both
+//! accept and reject exactly the same inputs with the same errors. The narrower integers
+//! stay on the const functions, which measured faster for them in the `varint` bench.
+
+use core::num::NonZeroUsize;
+
+use crate::ConstDecodeError;
+
+/// The continuation bits of eight bytes loaded as a little-endian `u64`.
+const CONTINUATION_BITS: u64 = 0x8080_8080_8080_8080;
+
+/// The payload bits of eight bytes loaded as a little-endian `u64`.
+const PAYLOAD_BITS: u64 = 0x7F7F_7F7F_7F7F_7F7F;
+
+/// Loads the first eight bytes of `buf` as a little-endian `u64`, if there are that many.
+#[inline(always)]
+fn load(buf: &[u8]) -> Option<u64> {
+  buf
+    .first_chunk::<8>()
+    .map(|bytes| u64::from_le_bytes(*bytes))
+}
+
+/// Returns the bytes of `word` up to and including its first byte without a continuation
+/// bit, or all of them if there is none, as a mask, along with their number.
+#[inline(always)]
+fn terminated(word: u64) -> (u64, usize) {
+  let stops = !word & CONTINUATION_BITS;
+  // `stops - 1` clears the lowest stop bit and sets every bit below it.
+  let mask = stops ^ stops.wrapping_sub(1);
+  (mask, stops.trailing_zeros() as usize / 8 + 1)
+}
+
+/// Packs the 7-bit payloads of the eight bytes of `word` into its low 56 bits.
+#[inline(always)]
+fn gather(word: u64) -> u64 {
+  #[cfg(all(target_arch = "x86_64", target_feature = "bmi2"))]
+  {
+    // Safety: BMI2 is enabled at compile time
+    unsafe { core::arch::x86_64::_pext_u64(word, PAYLOAD_BITS) }
+  }
+
+  #[cfg(not(all(target_arch = "x86_64", target_feature = "bmi2")))]
+  {
+    let x = word & PAYLOAD_BITS;
+    let x = (x & 0x007F_007F_007F_007F) | ((x & 0x7F00_7F00_7F00_7F00) >> 1);
+    let x = (x & 0x0000_3FFF_0000_3FFF) | ((x & 0x3FFF_0000_3FFF_0000) >> 2);
+    (x & 0x0000_0000_0FFF_FFFF) | ((x & 0x0FFF_FFFF_0000_0000) >> 4)
+  }
+}
+
+/// Decodes a `u64` in LEB128 encoded format from the buffer, see [`super::decode_u64_varint`].
+#[inline]
+pub(super) fn decode_u64_varint(buf: &[u8]) -> Result<(NonZeroUsize, u64), ConstDecodeError> {
+  let Some(word) = load(buf) else {
+    return super::decode_u64_varint(buf);
+  };
+  // Single byte values are the most common ones, and skip the gather.
+  if word & 0x80 == 0 {
+    return Ok((NonZeroUsize::MIN, word & 0x7F));
+  }
+
+  let (mask, len) = terminated(word);
+  if len <= 8 {
+    // Safety: `len` is at least 1
+    return Ok((
+      unsafe { NonZeroUsize::new_unchecked(len) },
+      gather(word & mask),
+    ));
+  }
+
+  // The first eight bytes all continue, they carry the low 56 bits.
+  let low = gather(word);
+  let Some(&ninth) = buf.get(8) else {
+    return Err(ConstDecodeError::insufficient_data(buf.len()));
+  };
+  if ninth & 0x80 == 0 {
+    // Safety: 9 is non-zero
+    return Ok((
+      unsafe { NonZeroUsize::new_unchecked(9) },
+      low | ((ninth as u64) << 56),
+    ));
+  }
+
+  let Some(&tenth) = buf.get(9) else {
+    return Err(ConstDecodeError::insufficient_data(buf.len()));
+  };
+  // Only the lowest bit of the tenth byte fits in a `u64`, and it must be the last byte.
+  if tenth & 0xFE != 0 {
+    return Err(ConstDecodeError::Overflow);
+  }
+  // Safety: 10 is non-zero
+  Ok((
+    unsafe { NonZeroUsize::new_unchecked(10) },
+    low | (((ninth & 0x7F) as u64) << 56) | ((tenth as u64) << 63),
+  ))
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests;
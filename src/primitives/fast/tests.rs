@@ -0,0 +1,95 @@
+use super::*;
+
+use crate::Varint;
+
+use quickcheck_macros::quickcheck;
+
+macro_rules! fast_matches_const {
+  ($($ty:ident), +$(,)?) => {
+    $(
+      paste::paste! {
+        #[quickcheck]
+        fn [< fast_ $ty _matches_const_on_arbitrary_bytes >](buf: Vec<u8>) -> bool {
+          [< decode_ $ty _varint >](&buf) == super::super::[< decode_ $ty _varint >](&buf)
+        }
+
+        #[quickcheck]
+        fn [< fast_ $ty _decodes_padded_values >](value: $ty, padding: Vec<u8>) -> bool {
+          let mut buf = [0u8; 10];
+          let len = crate::[< encode_ $ty _varint_to >](value, &mut buf).unwrap();
+          let mut buf = buf[..len.get()].to_vec();
+          buf.extend_from_slice(&padding);
+          [< decode_ $ty _varint >](&buf) == Ok((len, value))
+            && <$ty as Varint>::decode(&buf).unwrap() == (len, value)
+        }
+
+        #[test]
+        fn [< fast_ $ty _matches_const_on_every_length >]() {
+          // Overlong, overflowing and unterminated encodings of every length, followed by
+          // enough padding to take the fast path.
+          for len in 1..=12 {
+            for last in [0x00, 0x01, 0x02, 0x0F, 0x10, 0x7F, 0x80, 0xFF] {
+              let mut buf = vec![0xFF; len - 1];
+              buf.push(last);
+              buf.extend_from_slice(&[0u8; 8]);
+              for end in 0..=buf.len() {
+                let buf = &buf[..end];
+                assert_eq!(
+                  [< decode_ $ty _varint >](buf),
+                  super::super::[< decode_ $ty _varint >](buf),
+                  "{buf:02X?}",
+                );
+              }
+            }
+          }
+        }
+      }
+    )*
+  };
+}
+
+fast_matches_const!(u64);
+
+macro_rules! varint_decode_matches_const {
+  ($($ty:ident), +$(,)?) => {
+    $(
+      paste::paste! {
+        #[quickcheck]
+        fn [< varint_decode_ $ty _matches_const >](buf: Vec<u8>) -> bool {
+          <$ty as Varint>::decode(&buf)
+            == crate::[< decode_ $ty _varint >](&buf).map_err(Into::into)
+        }
+      }
+    )*
+  };
+}
+
+varint_decode_matches_const!(u8, u16, u32, u64, i8, i16, i32, i64, usize, isize);
+
+#[test]
+fn gather_packs_payloads() {
+  assert_eq!(gather(0), 0);
+  assert_eq!(gather(u64::MAX), (1 << 56) - 1);
+  assert_eq!(gather(0x0102_0304_0506_0708), {
+    let mut expected = 0;
+    for (i, byte) in [0x08u64, 0x07, 0x06, 0x05, 0x04, 0x03, 0x02, 0x01]
+      .into_iter()
+      .enumerate()
+    {
+      expected |= byte << (7 * i);
+    }
+    expected
+  });
+}
+
+#[test]
+fn decode_u64_max() {
+  let mut buf = [0xFF; 16];
+  buf[9] = 0x01;
+  assert_eq!(
+    decode_u64_varint(&buf),
+    Ok((NonZeroUsize::new(10).unwrap(), u64::MAX))
+  );
+  buf[9] = 0x02;
+  assert_eq!(decode_u64_varint(&buf), Err(ConstDecodeError::Overflow));
+}
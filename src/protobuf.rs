@@ -0,0 +1,646 @@
+use core::num::NonZeroUsize;
+
+use super::{
+  ConstDecodeError, ConstEncodeError, DecodeError, EncodeError, Varint, decode_u32_varint,
+  decode_u64_varint, decode_u64_varint_canonical, encode_i32_varint_to, encode_i64_varint_to,
+  encode_u32_varint, encode_u32_varint_to, encode_u64_varint_to, encoded_i32_varint_len,
+  encoded_i64_varint_len, encoded_u32_varint_len, encoded_u64_varint_len, utils::Buffer,
+};
+
+/// The smallest valid field number.
+pub const MIN_FIELD_NUMBER: u32 = 1;
+
+/// The largest valid field number, `2^29 - 1`.
+pub const MAX_FIELD_NUMBER: u32 = (1 << 29) - 1;
+
+/// The wire type of a protobuf field, stored in the low three bits of its key.
+///
+/// See the [protobuf encoding guide](https://protobuf.dev/programming-guides/encoding/#structure).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[repr(u8)]
+pub enum WireType {
+  /// `int32`, `int64`, `uint32`, `uint64`, `sint32`, `sint64`, `bool` and `enum`.
+  Varint = 0,
+  /// `fixed64`, `sfixed64` and `double`.
+  Fixed64 = 1,
+  /// `string`, `bytes`, embedded messages and packed repeated fields.
+  LengthDelimited = 2,
+  /// Group start (deprecated).
+  StartGroup = 3,
+  /// Group end (deprecated).
+  EndGroup = 4,
+  /// `fixed32`, `sfixed32` and `float`.
+  Fixed32 = 5,
+}
+
+impl WireType {
+  /// Returns the wire type for the low three bits of a key, or `None` for the unused
+  /// values `6` and `7`.
+  #[inline]
+  pub const fn from_u8(value: u8) -> Option<Self> {
+    Some(match value {
+      0 => Self::Varint,
+      1 => Self::Fixed64,
+      2 => Self::LengthDelimited,
+      3 => Self::StartGroup,
+      4 => Self::EndGroup,
+      5 => Self::Fixed32,
+      _ => return None,
+    })
+  }
+
+  /// Returns the numeric value of the wire type.
+  #[inline]
+  pub const fn as_u8(&self) -> u8 {
+    *self as u8
+  }
+}
+
+impl TryFrom<u8> for WireType {
+  type Error = ConstDecodeError;
+
+  #[inline]
+  fn try_from(value: u8) -> Result<Self, Self::Error> {
+    match Self::from_u8(value) {
+      Some(wire_type) => Ok(wire_type),
+      None => Err(ConstDecodeError::other("invalid wire type")),
+    }
+  }
+}
+
+/// Returns the encoded length of the key of a field.
+///
+/// The wire type does not affect the length.
+#[inline]
+pub const fn encoded_key_len(field_number: u32) -> NonZeroUsize {
+  encoded_u32_varint_len(field_number << 3)
+}
+
+/// Encodes the key of a field, `(field_number << 3) | wire_type` as a varint.
+///
+/// Returns an error if `field_number` is not in `MIN_FIELD_NUMBER..=MAX_FIELD_NUMBER`.
+///
+/// ## Example
+///
+/// ```rust
+/// use varing::protobuf::{WireType, decode_key, encode_key};
+///
+/// const KEY: varing::utils::Buffer<6> = match encode_key(1, WireType::Varint) {
+///   Ok(key) => key,
+///   Err(_) => panic!("invalid field number"),
+/// };
+/// assert_eq!(KEY.as_slice(), &[0x08]);
+///
+/// let (read, field_number, wire_type) = decode_key(&KEY).unwrap();
+/// assert_eq!((read.get(), field_number, wire_type), (1, 1, WireType::Varint));
+/// ```
+#[inline]
+pub const fn encode_key(
+  field_number: u32,
+  wire_type: WireType,
+) -> Result<Buffer<{ u32::MAX_ENCODED_LEN.get() + 1 }>, ConstEncodeError> {
+  match key(field_number, wire_type) {
+    Ok(key) => Ok(encode_u32_varint(key)),
+    Err(e) => Err(e),
+  }
+}
+
+/// Encodes the key of a field to the buffer.
+///
+/// Returns an error if `field_number` is not in `MIN_FIELD_NUMBER..=MAX_FIELD_NUMBER`.
+#[inline]
+pub const fn encode_key_to(
+  field_number: u32,
+  wire_type: WireType,
+  buf: &mut [u8],
+) -> Result<NonZeroUsize, ConstEncodeError> {
+  match key(field_number, wire_type) {
+    Ok(key) => encode_u32_varint_to(key, buf),
+    Err(e) => Err(e),
+  }
+}
+
+/// Decodes the key of a field from the buffer.
+///
+/// Returns the bytes read, the field number and the wire type if successful.
+#[inline]
+pub const fn decode_key(buf: &[u8]) -> Result<(NonZeroUsize, u32, WireType), ConstDecodeError> {
+  let (read, key) = match decode_u32_varint(buf) {
+    Ok(res) => res,
+    Err(e) => return Err(e),
+  };
+
+  let field_number = key >> 3;
+  if field_number < MIN_FIELD_NUMBER {
+    return Err(ConstDecodeError::other("invalid field number"));
+  }
+
+  match WireType::from_u8((key & 0x07) as u8) {
+    Some(wire_type) => Ok((read, field_number, wire_type)),
+    None => Err(ConstDecodeError::other("invalid wire type")),
+  }
+}
+
+/// Returns the encoded length of a varint field, e.g. `uint32`, `uint64`, `bool` or `enum`.
+#[inline]
+pub const fn encoded_varint_field_len(field_number: u32, value: u64) -> NonZeroUsize {
+  encoded_key_len(field_number).saturating_add(encoded_u64_varint_len(value).get())
+}
+
+/// Encodes a varint field, e.g. `uint32`, `uint64`, `bool` or `enum`, to the buffer.
+///
+/// Returns the number of bytes written. Nothing is written if the buffer is too small.
+#[inline]
+pub const fn encode_varint_field_to(
+  field_number: u32,
+  value: u64,
+  buf: &mut [u8],
+) -> Result<NonZeroUsize, ConstEncodeError> {
+  let len = encoded_varint_field_len(field_number, value);
+  let key_len = match encode_field_key_to(field_number, WireType::Varint, len, buf) {
+    Ok(key_len) => key_len,
+    Err(e) => return Err(e),
+  };
+  let (_, buf) = buf.split_at_mut(key_len);
+  match encode_u64_varint_to(value, buf) {
+    Ok(_) => Ok(len),
+    Err(e) => Err(e),
+  }
+}
+
+/// Returns the encoded length of a zigzag encoded `sint32` field.
+#[inline]
+pub const fn encoded_sint32_field_len(field_number: u32, value: i32) -> NonZeroUsize {
+  encoded_key_len(field_number).saturating_add(encoded_i32_varint_len(value).get())
+}
+
+/// Encodes a zigzag encoded `sint32` field to the buffer.
+///
+/// Returns the number of bytes written. Nothing is written if the buffer is too small.
+#[inline]
+pub const fn encode_sint32_field_to(
+  field_number: u32,
+  value: i32,
+  buf: &mut [u8],
+) -> Result<NonZeroUsize, ConstEncodeError> {
+  let len = encoded_sint32_field_len(field_number, value);
+  let key_len = match encode_field_key_to(field_number, WireType::Varint, len, buf) {
+    Ok(key_len) => key_len,
+    Err(e) => return Err(e),
+  };
+  let (_, buf) = buf.split_at_mut(key_len);
+  match encode_i32_varint_to(value, buf) {
+    Ok(_) => Ok(len),
+    Err(e) => Err(e),
+  }
+}
+
+/// Returns the encoded length of a zigzag encoded `sint64` field.
+#[inline]
+pub const fn encoded_sint64_field_len(field_number: u32, value: i64) -> NonZeroUsize {
+  encoded_key_len(field_number).saturating_add(encoded_i64_varint_len(value).get())
+}
+
+/// Encodes a zigzag encoded `sint64` field to the buffer.
+///
+/// Returns the number of bytes written. Nothing is written if the buffer is too small.
+#[inline]
+pub const fn encode_sint64_field_to(
+  field_number: u32,
+  value: i64,
+  buf: &mut [u8],
+) -> Result<NonZeroUsize, ConstEncodeError> {
+  let len = encoded_sint64_field_len(field_number, value);
+  let key_len = match encode_field_key_to(field_number, WireType::Varint, len, buf) {
+    Ok(key_len) => key_len,
+    Err(e) => return Err(e),
+  };
+  let (_, buf) = buf.split_at_mut(key_len);
+  match encode_i64_varint_to(value, buf) {
+    Ok(_) => Ok(len),
+    Err(e) => Err(e),
+  }
+}
+
+/// Returns the encoded length of a `fixed32` field.
+#[inline]
+pub const fn encoded_fixed32_field_len(field_number: u32) -> NonZeroUsize {
+  encoded_key_len(field_number).saturating_add(4)
+}
+
+/// Encodes a little-endian `fixed32` field to the buffer.
+///
+/// Use [`u32::from_ne_bytes`] with the bytes of an `i32` or `f32` for `sfixed32` and `float`.
+///
+/// Returns the number of bytes written. Nothing is written if the buffer is too small.
+#[inline]
+pub const fn encode_fixed32_field_to(
+  field_number: u32,
+  value: u32,
+  buf: &mut [u8],
+) -> Result<NonZeroUsize, ConstEncodeError> {
+  let len = encoded_fixed32_field_len(field_number);
+  let key_len = match encode_field_key_to(field_number, WireType::Fixed32, len, buf) {
+    Ok(key_len) => key_len,
+    Err(e) => return Err(e),
+  };
+  let (_, buf) = buf.split_at_mut(key_len);
+  copy(&value.to_le_bytes(), buf);
+  Ok(len)
+}
+
+/// Returns the encoded length of a `fixed64` field.
+#[inline]
+pub const fn encoded_fixed64_field_len(field_number: u32) -> NonZeroUsize {
+  encoded_key_len(field_number).saturating_add(8)
+}
+
+/// Encodes a little-endian `fixed64` field to the buffer.
+///
+/// Use [`u64::from_ne_bytes`] with the bytes of an `i64` or `f64` for `sfixed64` and `double`.
+///
+/// Returns the number of bytes written. Nothing is written if the buffer is too small.
+#[inline]
+pub const fn encode_fixed64_field_to(
+  field_number: u32,
+  value: u64,
+  buf: &mut [u8],
+) -> Result<NonZeroUsize, ConstEncodeError> {
+  let len = encoded_fixed64_field_len(field_number);
+  let key_len = match encode_field_key_to(field_number, WireType::Fixed64, len, buf) {
+    Ok(key_len) => key_len,
+    Err(e) => return Err(e),
+  };
+  let (_, buf) = buf.split_at_mut(key_len);
+  copy(&value.to_le_bytes(), buf);
+  Ok(len)
+}
+
+/// Returns the encoded length of a length-delimited field with a payload of `payload_len` bytes.
+#[inline]
+pub const fn encoded_length_delimited_field_len(
+  field_number: u32,
+  payload_len: usize,
+) -> NonZeroUsize {
+  encoded_key_len(field_number)
+    .saturating_add(encoded_u64_varint_len(payload_len as u64).get())
+    .saturating_add(payload_len)
+}
+
+/// Encodes a length-delimited field, e.g. `string`, `bytes`, an embedded message or a
+/// packed repeated field, to the buffer.
+///
+/// Returns the number of bytes written. Nothing is written if the buffer is too small.
+///
+/// ## Example
+///
+/// ```rust
+/// use varing::protobuf::{encode_length_delimited_field_to, encode_varint_field_to};
+///
+/// // message Test { int32 a = 1; string b = 2; }
+/// let mut buf = [0u8; 16];
+/// let mut len = encode_varint_field_to(1, 150, &mut buf).unwrap().get();
+/// len += encode_length_delimited_field_to(2, b"testing", &mut buf[len..]).unwrap().get();
+/// assert_eq!(&buf[..len], b"\x08\x96\x01\x12\x07testing");
+/// ```
+#[inline]
+pub const fn encode_length_delimited_field_to(
+  field_number: u32,
+  payload: &[u8],
+  buf: &mut [u8],
+) -> Result<NonZeroUsize, ConstEncodeError> {
+  let len = encoded_length_delimited_field_len(field_number, payload.len());
+  let key_len = match encode_field_key_to(field_number, WireType::LengthDelimited, len, buf) {
+    Ok(key_len) => key_len,
+    Err(e) => return Err(e),
+  };
+  let (_, buf) = buf.split_at_mut(key_len);
+  let prefix_len = match encode_u64_varint_to(payload.len() as u64, buf) {
+    Ok(prefix_len) => prefix_len.get(),
+    Err(e) => return Err(e),
+  };
+  let (_, buf) = buf.split_at_mut(prefix_len);
+  copy(payload, buf);
+  Ok(len)
+}
+
+/// Decodes a little-endian `fixed32` value from the buffer.
+///
+/// Returns the bytes read and the decoded value if successful.
+#[inline]
+pub const fn decode_fixed32(buf: &[u8]) -> Result<(NonZeroUsize, u32), ConstDecodeError> {
+  match buf.first_chunk::<4>() {
+    Some(bytes) => Ok((
+      // Safety: 4 is non-zero
+      unsafe { NonZeroUsize::new_unchecked(4) },
+      u32::from_le_bytes(*bytes),
+    )),
+    None => Err(ConstDecodeError::insufficient_data_with_required(
+      // Safety: 4 is non-zero
+      unsafe { NonZeroUsize::new_unchecked(4) },
+      buf.len(),
+    )),
+  }
+}
+
+/// Decodes a little-endian `fixed64` value from the buffer.
+///
+/// Returns the bytes read and the decoded value if successful.
+#[inline]
+pub const fn decode_fixed64(buf: &[u8]) -> Result<(NonZeroUsize, u64), ConstDecodeError> {
+  match buf.first_chunk::<8>() {
+    Some(bytes) => Ok((
+      // Safety: 8 is non-zero
+      unsafe { NonZeroUsize::new_unchecked(8) },
+      u64::from_le_bytes(*bytes),
+    )),
+    None => Err(ConstDecodeError::insufficient_data_with_required(
+      // Safety: 8 is non-zero
+      unsafe { NonZeroUsize::new_unchecked(8) },
+      buf.len(),
+    )),
+  }
+}
+
+/// Decodes the payload of a length-delimited field from the buffer.
+///
+/// Returns the bytes read (length prefix and payload) and the payload, borrowed from
+/// the buffer, if successful.
+#[inline]
+pub const fn decode_length_delimited(
+  buf: &[u8],
+) -> Result<(NonZeroUsize, &[u8]), ConstDecodeError> {
+  let (prefix_len, payload_len) = match decode_u64_varint(buf) {
+    Ok(res) => res,
+    Err(e) => return Err(e),
+  };
+
+  let available = buf.len() - prefix_len.get();
+  if payload_len > available as u64 {
+    return Err(ConstDecodeError::insufficient_data_with_required(
+      prefix_len.saturating_add(payload_len as usize),
+      buf.len(),
+    ));
+  }
+
+  let (_, rest) = buf.split_at(prefix_len.get());
+  let (payload, _) = rest.split_at(payload_len as usize);
+  Ok((prefix_len.saturating_add(payload.len()), payload))
+}
+
+/// Returns the encoded length of an `int32` value.
+///
+/// Negative values are sign-extended to 64 bits, so they always take 10 bytes.
+#[inline]
+pub const fn encoded_int32_varint_len(value: i32) -> NonZeroUsize {
+  encoded_u64_varint_len(value as i64 as u64)
+}
+
+/// Encodes an `int32` value as a two's-complement varint and writes it to the buffer.
+///
+/// Unlike [`encode_i32_varint_to`], which zigzag encodes like `sint32`, negative values
+/// are sign-extended to 64 bits and take 10 bytes.
+#[inline]
+pub const fn encode_int32_varint_to(
+  value: i32,
+  buf: &mut [u8],
+) -> Result<NonZeroUsize, ConstEncodeError> {
+  encode_u64_varint_to(value as i64 as u64, buf)
+}
+
+/// Decodes an `int32` value encoded as a two's-complement varint from the buffer.
+///
+/// Like other protobuf implementations, this accepts any `u64` varint and keeps its low
+/// 32 bits, so both the 10-byte sign-extended form and the 5-byte form of a negative
+/// value decode to the same `i32`.
+///
+/// Returns the bytes read and the decoded value if successful.
+#[inline]
+pub const fn decode_int32_varint(buf: &[u8]) -> Result<(NonZeroUsize, i32), ConstDecodeError> {
+  match decode_u64_varint(buf) {
+    Ok((read, value)) => Ok((read, value as i32)),
+    Err(e) => Err(e),
+  }
+}
+
+/// Decodes an `int32` value encoded as a two's-complement varint from the buffer,
+/// rejecting any encoding other than the one produced by [`encode_int32_varint_to`].
+///
+/// Returns [`ConstDecodeError::NonCanonical`] if the encoding is overlong, or if the
+/// decoded `u64` is not the sign extension of an `i32`.
+#[inline]
+pub const fn decode_int32_varint_canonical(
+  buf: &[u8],
+) -> Result<(NonZeroUsize, i32), ConstDecodeError> {
+  match decode_u64_varint_canonical(buf) {
+    Ok((read, value)) => {
+      if value as i32 as i64 as u64 != value {
+        return Err(ConstDecodeError::non_canonical());
+      }
+      Ok((read, value as i32))
+    }
+    Err(e) => Err(e),
+  }
+}
+
+/// Returns the encoded length of an `int64` value.
+///
+/// Negative values always take 10 bytes.
+#[inline]
+pub const fn encoded_int64_varint_len(value: i64) -> NonZeroUsize {
+  encoded_u64_varint_len(value as u64)
+}
+
+/// Encodes an `int64` value as a two's-complement varint and writes it to the buffer.
+///
+/// Unlike [`encode_i64_varint_to`], which zigzag encodes like `sint64`, negative values
+/// take 10 bytes.
+#[inline]
+pub const fn encode_int64_varint_to(
+  value: i64,
+  buf: &mut [u8],
+) -> Result<NonZeroUsize, ConstEncodeError> {
+  encode_u64_varint_to(value as u64, buf)
+}
+
+/// Decodes an `int64` value encoded as a two's-complement varint from the buffer.
+///
+/// Returns the bytes read and the decoded value if successful.
+#[inline]
+pub const fn decode_int64_varint(buf: &[u8]) -> Result<(NonZeroUsize, i64), ConstDecodeError> {
+  match decode_u64_varint(buf) {
+    Ok((read, value)) => Ok((read, value as i64)),
+    Err(e) => Err(e),
+  }
+}
+
+/// Decodes an `int64` value encoded as a two's-complement varint from the buffer,
+/// rejecting overlong encodings.
+///
+/// Returns the bytes read and the decoded value if successful.
+#[inline]
+pub const fn decode_int64_varint_canonical(
+  buf: &[u8],
+) -> Result<(NonZeroUsize, i64), ConstDecodeError> {
+  match decode_u64_varint_canonical(buf) {
+    Ok((read, value)) => Ok((read, value as i64)),
+    Err(e) => Err(e),
+  }
+}
+
+/// Returns the encoded length of an `int32` field.
+#[inline]
+pub const fn encoded_int32_field_len(field_number: u32, value: i32) -> NonZeroUsize {
+  encoded_key_len(field_number).saturating_add(encoded_int32_varint_len(value).get())
+}
+
+/// Encodes an `int32` field to the buffer. Negative values take 10 bytes.
+///
+/// Returns the number of bytes written. Nothing is written if the buffer is too small.
+#[inline]
+pub const fn encode_int32_field_to(
+  field_number: u32,
+  value: i32,
+  buf: &mut [u8],
+) -> Result<NonZeroUsize, ConstEncodeError> {
+  encode_varint_field_to(field_number, value as i64 as u64, buf)
+}
+
+/// Returns the encoded length of an `int64` field.
+#[inline]
+pub const fn encoded_int64_field_len(field_number: u32, value: i64) -> NonZeroUsize {
+  encoded_key_len(field_number).saturating_add(encoded_int64_varint_len(value).get())
+}
+
+/// Encodes an `int64` field to the buffer. Negative values take 10 bytes.
+///
+/// Returns the number of bytes written. Nothing is written if the buffer is too small.
+#[inline]
+pub const fn encode_int64_field_to(
+  field_number: u32,
+  value: i64,
+  buf: &mut [u8],
+) -> Result<NonZeroUsize, ConstEncodeError> {
+  encode_varint_field_to(field_number, value as u64, buf)
+}
+
+macro_rules! impl_protobuf_int {
+  ($($name:ident($ty:ident) => $proto:literal), +$(,)?) => {
+    $(
+      paste::paste! {
+        #[doc = "An `" $ty "` encoded like a protobuf `" $proto "`."]
+        ///
+        /// The [`Varint`] implementation of the bare integer zigzag encodes it, which matches
+        #[doc = "protobuf `s" $proto "`. This wrapper instead encodes it as a two's-complement"]
+        /// varint, so negative values are sign-extended to 64 bits and take 10 bytes.
+        ///
+        /// ## Example
+        ///
+        /// ```rust
+        #[doc = "use varing::{Varint, protobuf::" $name "};"]
+        ///
+        #[doc = "let value = " $name "(-1);"]
+        /// let mut buf = [0u8; 10];
+        /// assert_eq!(value.encode(&mut buf).unwrap().get(), 10);
+        /// assert_eq!(buf, [0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01]);
+        #[doc = "assert_eq!(" $name "::decode(&buf).unwrap().1, value);"]
+        /// ```
+        #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        #[repr(transparent)]
+        pub struct $name(pub $ty);
+
+        impl From<$ty> for $name {
+          #[inline]
+          fn from(value: $ty) -> Self {
+            Self(value)
+          }
+        }
+
+        impl From<$name> for $ty {
+          #[inline]
+          fn from(value: $name) -> Self {
+            value.0
+          }
+        }
+
+        impl Varint for $name {
+          const MIN_ENCODED_LEN: NonZeroUsize = u64::MIN_ENCODED_LEN;
+          const MAX_ENCODED_LEN: NonZeroUsize = u64::MAX_ENCODED_LEN;
+
+          #[inline]
+          fn encoded_len(&self) -> NonZeroUsize {
+            [< encoded_ $proto _varint_len >](self.0)
+          }
+
+          #[inline]
+          fn encode(&self, buf: &mut [u8]) -> Result<NonZeroUsize, EncodeError> {
+            [< encode_ $proto _varint_to >](self.0, buf).map_err(Into::into)
+          }
+
+          #[inline]
+          fn decode(buf: &[u8]) -> Result<(NonZeroUsize, Self), DecodeError>
+          where
+            Self: Sized,
+          {
+            [< decode_ $proto _varint >](buf)
+              .map(|(read, value)| (read, Self(value)))
+              .map_err(Into::into)
+          }
+
+          #[inline]
+          fn decode_canonical(buf: &[u8]) -> Result<(NonZeroUsize, Self), DecodeError>
+          where
+            Self: Sized,
+          {
+            [< decode_ $proto _varint_canonical >](buf)
+              .map(|(read, value)| (read, Self(value)))
+              .map_err(Into::into)
+          }
+        }
+      }
+    )*
+  };
+}
+
+impl_protobuf_int!(ProtobufInt32(i32) => "int32", ProtobufInt64(i64) => "int64");
+
+#[inline]
+const fn key(field_number: u32, wire_type: WireType) -> Result<u32, ConstEncodeError> {
+  if field_number < MIN_FIELD_NUMBER || field_number > MAX_FIELD_NUMBER {
+    return Err(ConstEncodeError::other("invalid field number"));
+  }
+  Ok((field_number << 3) | wire_type as u32)
+}
+
+/// Checks that the whole field of `len` bytes fits, then writes its key.
+///
+/// Returns the length of the key.
+#[inline]
+const fn encode_field_key_to(
+  field_number: u32,
+  wire_type: WireType,
+  len: NonZeroUsize,
+  buf: &mut [u8],
+) -> Result<usize, ConstEncodeError> {
+  if buf.len() < len.get() {
+    return Err(ConstEncodeError::insufficient_space(len, buf.len()));
+  }
+
+  match encode_key_to(field_number, wire_type, buf) {
+    Ok(key_len) => Ok(key_len.get()),
+    Err(e) => Err(e),
+  }
+}
+
+/// `<[u8]>::copy_from_slice` is not `const` on our MSRV. `dst` must be at least as long as `src`.
+#[inline]
+const fn copy(src: &[u8], dst: &mut [u8]) {
+  let mut i = 0;
+  while i < src.len() {
+    dst[i] = src[i];
+    i += 1;
+  }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests;
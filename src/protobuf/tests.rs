@@ -0,0 +1,275 @@
+use super::*;
+
+use std::{vec, vec::Vec};
+
+use prost::encoding;
+use quickcheck_macros::quickcheck;
+
+fn field_number(n: u32) -> u32 {
+  n % MAX_FIELD_NUMBER + 1
+}
+
+fn prost_wire_type(wire_type: WireType) -> encoding::WireType {
+  encoding::WireType::try_from(wire_type.as_u8() as u64).unwrap()
+}
+
+macro_rules! prost_equivalent {
+  ($($name:ident($ty:ty) => $prost:ident), +$(,)?) => {
+    $(
+      paste::paste! {
+        #[quickcheck]
+        fn [< $name _field_matches_prost >](field: u32, value: $ty) -> bool {
+          let field = field_number(field);
+          let mut expected = Vec::new();
+          encoding::$prost::encode(field, &value, &mut expected);
+
+          let mut buf = vec![0; [< encoded_ $name _field_len >](field, value).get()];
+          let written = [< encode_ $name _field_to >](field, value, &mut buf).unwrap();
+          written.get() == buf.len() && buf == expected
+        }
+      }
+    )*
+  };
+}
+
+prost_equivalent!(
+  varint(u64) => uint64,
+  sint32(i32) => sint32,
+  sint64(i64) => sint64,
+);
+
+#[quickcheck]
+fn key_matches_prost(field: u32, wire_type: u8) -> bool {
+  let field = field_number(field);
+  let wire_type = WireType::from_u8(wire_type % 6).unwrap();
+
+  let mut expected = Vec::new();
+  encoding::encode_key(field, prost_wire_type(wire_type), &mut expected);
+
+  let key = encode_key(field, wire_type).unwrap();
+  let (read, decoded_field, decoded_wire_type) = decode_key(&key).unwrap();
+  key.as_slice() == expected
+    && encoded_key_len(field).get() == expected.len()
+    && read.get() == expected.len()
+    && decoded_field == field
+    && decoded_wire_type == wire_type
+}
+
+#[quickcheck]
+fn fixed_fields_match_prost(field: u32, a: u32, b: u64) -> bool {
+  let field = field_number(field);
+  let mut expected = Vec::new();
+  encoding::fixed32::encode(field, &a, &mut expected);
+  encoding::fixed64::encode(field, &b, &mut expected);
+
+  let mut buf =
+    vec![0; encoded_fixed32_field_len(field).get() + encoded_fixed64_field_len(field).get()];
+  let len = encode_fixed32_field_to(field, a, &mut buf).unwrap().get();
+  let len = len
+    + encode_fixed64_field_to(field, b, &mut buf[len..])
+      .unwrap()
+      .get();
+  if len != buf.len() || buf != expected {
+    return false;
+  }
+
+  let key_len = encoded_key_len(field).get();
+  let (read, decoded_a) = decode_fixed32(&buf[key_len..]).unwrap();
+  let (_, decoded_b) = decode_fixed64(&buf[key_len * 2 + read.get()..]).unwrap();
+  decoded_a == a && decoded_b == b
+}
+
+#[quickcheck]
+fn length_delimited_field_matches_prost(field: u32, payload: Vec<u8>) -> bool {
+  let field = field_number(field);
+  let mut expected = Vec::new();
+  encoding::bytes::encode(field, &payload, &mut expected);
+
+  let mut buf = vec![0; encoded_length_delimited_field_len(field, payload.len()).get()];
+  let written = encode_length_delimited_field_to(field, &payload, &mut buf).unwrap();
+  if written.get() != buf.len() || buf != expected {
+    return false;
+  }
+
+  let key_len = encoded_key_len(field).get();
+  let (read, decoded) = decode_length_delimited(&buf[key_len..]).unwrap();
+  read.get() == buf.len() - key_len && decoded == payload.as_slice()
+}
+
+#[test]
+fn known_vectors() {
+  let mut buf = [0u8; 16];
+
+  let len = encode_varint_field_to(1, 150, &mut buf).unwrap().get();
+  assert_eq!(&buf[..len], &[0x08, 0x96, 0x01]);
+
+  let len = encode_sint32_field_to(1, -1, &mut buf).unwrap().get();
+  assert_eq!(&buf[..len], &[0x08, 0x01]);
+
+  let len = encode_fixed32_field_to(2, 1, &mut buf).unwrap().get();
+  assert_eq!(&buf[..len], &[0x15, 0x01, 0x00, 0x00, 0x00]);
+
+  let len = encode_key_to(MAX_FIELD_NUMBER, WireType::Fixed32, &mut buf)
+    .unwrap()
+    .get();
+  assert_eq!(&buf[..len], &[0xFD, 0xFF, 0xFF, 0xFF, 0x0F]);
+}
+
+#[test]
+fn invalid_field_number() {
+  for field in [0, MAX_FIELD_NUMBER + 1, u32::MAX] {
+    assert_eq!(
+      encode_key(field, WireType::Varint),
+      Err(ConstEncodeError::other("invalid field number"))
+    );
+    assert!(encode_varint_field_to(field, 1, &mut [0; 16]).is_err());
+  }
+
+  assert_eq!(
+    decode_key(&[0x00]),
+    Err(ConstDecodeError::other("invalid field number"))
+  );
+}
+
+#[test]
+fn invalid_wire_type() {
+  assert_eq!(WireType::from_u8(6), None);
+  assert!(WireType::try_from(7).is_err());
+  assert_eq!(
+    decode_key(&[0x0E]),
+    Err(ConstDecodeError::other("invalid wire type"))
+  );
+  assert_eq!(
+    decode_key(&[0x0F]),
+    Err(ConstDecodeError::other("invalid wire type"))
+  );
+}
+
+#[test]
+fn insufficient_space_writes_nothing() {
+  let mut buf = [0xFFu8; 8];
+  assert_eq!(
+    encode_length_delimited_field_to(1, b"testing", &mut buf),
+    Err(ConstEncodeError::insufficient_space(
+      NonZeroUsize::new(9).unwrap(),
+      8
+    ))
+  );
+  assert!(encode_fixed64_field_to(1, 0, &mut buf).is_err());
+  assert_eq!(buf, [0xFF; 8]);
+}
+
+#[test]
+fn insufficient_data() {
+  assert_eq!(
+    decode_fixed32(&[0; 3]),
+    Err(ConstDecodeError::insufficient_data_with_required(
+      NonZeroUsize::new(4).unwrap(),
+      3
+    ))
+  );
+  assert_eq!(
+    decode_fixed64(&[0; 7]),
+    Err(ConstDecodeError::insufficient_data_with_required(
+      NonZeroUsize::new(8).unwrap(),
+      7
+    ))
+  );
+  assert_eq!(
+    decode_length_delimited(&[0x05, b'a', b'b']),
+    Err(ConstDecodeError::insufficient_data_with_required(
+      NonZeroUsize::new(6).unwrap(),
+      3
+    ))
+  );
+}
+
+macro_rules! protobuf_int_matches_prost {
+  ($($name:ident($ty:ty) => $proto:ident), +$(,)?) => {
+    $(
+      paste::paste! {
+        #[quickcheck]
+        fn [< $proto _field_matches_prost >](field: u32, value: $ty) -> bool {
+          let field = field_number(field);
+          let mut expected = Vec::new();
+          encoding::$proto::encode(field, &value, &mut expected);
+
+          let mut buf = vec![0; [< encoded_ $proto _field_len >](field, value).get()];
+          let written = [< encode_ $proto _field_to >](field, value, &mut buf).unwrap();
+          written.get() == buf.len() && buf == expected
+        }
+
+        #[quickcheck]
+        fn [< $proto _varint_matches_prost >](value: $ty) -> bool {
+          let value = $name(value);
+          let mut expected = Vec::new();
+          encoding::encode_varint(value.0 as u64, &mut expected);
+
+          let mut buf = vec![0; value.encoded_len().get()];
+          value.encode(&mut buf).unwrap();
+          buf == expected
+            && $name::decode(&buf).unwrap() == (value.encoded_len(), value)
+            && $name::decode_canonical(&buf).unwrap() == (value.encoded_len(), value)
+        }
+      }
+    )*
+  };
+}
+
+protobuf_int_matches_prost!(ProtobufInt32(i32) => int32, ProtobufInt64(i64) => int64);
+
+#[test]
+fn negative_int32_takes_ten_bytes() {
+  let mut buf = [0u8; 10];
+  assert_eq!(ProtobufInt32(-1).encoded_len().get(), 10);
+  assert_eq!(ProtobufInt32(-1).encode(&mut buf).unwrap().get(), 10);
+  assert_eq!(
+    buf,
+    [0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01]
+  );
+  assert_eq!(
+    ProtobufInt32::decode(&buf).unwrap(),
+    (NonZeroUsize::new(10).unwrap(), ProtobufInt32(-1))
+  );
+
+  let (read, value) =
+    ProtobufInt32::decode(&[0x80, 0x80, 0x80, 0x80, 0xF8, 0xFF, 0xFF, 0xFF, 0xFF, 0x01]).unwrap();
+  assert_eq!((read.get(), value.0), (10, i32::MIN));
+
+  // Zigzag encoding of the bare integer is unaffected.
+  assert_eq!((-1i32).encoded_len().get(), 1);
+}
+
+#[test]
+fn int32_accepts_truncated_negative() {
+  // Some encoders write negative `int32` values with only 5 bytes.
+  let buf = [0xFF, 0xFF, 0xFF, 0xFF, 0x0F];
+  assert_eq!(
+    ProtobufInt32::decode(&buf).unwrap(),
+    (NonZeroUsize::new(5).unwrap(), ProtobufInt32(-1))
+  );
+  assert_eq!(
+    ProtobufInt32::decode_canonical(&buf),
+    Err(DecodeError::NonCanonical)
+  );
+  assert_eq!(
+    ProtobufInt64::decode(&buf).unwrap().1,
+    ProtobufInt64(u32::MAX as i64)
+  );
+}
+
+#[test]
+fn protobuf_int_rejects_overlong() {
+  assert_eq!(
+    ProtobufInt32::decode_canonical(&[0x81, 0x00]),
+    Err(DecodeError::NonCanonical)
+  );
+  assert_eq!(
+    ProtobufInt64::decode_canonical(&[0x81, 0x00]),
+    Err(DecodeError::NonCanonical)
+  );
+  assert_eq!(
+    ProtobufInt64::decode(&[0x81, 0x00]).unwrap().1,
+    ProtobufInt64(1)
+  );
+}
@@ -0,0 +1,167 @@
+use core::{marker::PhantomData, num::NonZeroUsize};
+
+use std::vec::Vec;
+
+use super::{DecodeError, EncodeError, Varint};
+
+/// Encoded values up to this length are staged on the stack, longer ones on the heap.
+const INLINE_SCRATCH_LEN: usize = 32;
+
+/// A scratch buffer for staging a single encoded value.
+pub(crate) enum Scratch {
+  Inline([u8; INLINE_SCRATCH_LEN], usize),
+  Heap(Vec<u8>),
+}
+
+impl Scratch {
+  #[inline]
+  pub(crate) fn new(len: usize) -> Self {
+    if len <= INLINE_SCRATCH_LEN {
+      Self::Inline([0; INLINE_SCRATCH_LEN], len)
+    } else {
+      Self::Heap(std::vec![0; len])
+    }
+  }
+
+  /// Creates a scratch buffer holding the encoded bytes of `value`.
+  #[inline]
+  pub(crate) fn encode<V: Varint + ?Sized>(value: &V) -> Result<Self, EncodeError> {
+    let mut scratch = Self::new(value.encoded_len().get());
+    let written = value.encode(&mut scratch)?;
+    scratch.truncate(written.get());
+    Ok(scratch)
+  }
+
+  /// Resizes the buffer, moving it to the heap once it no longer fits inline.
+  fn resize(&mut self, new_len: usize) {
+    match self {
+      Self::Inline(_, len) if new_len <= INLINE_SCRATCH_LEN => *len = new_len,
+      Self::Inline(buf, len) => {
+        let mut heap = Vec::with_capacity(new_len);
+        heap.extend_from_slice(&buf[..*len]);
+        heap.resize(new_len, 0);
+        *self = Self::Heap(heap);
+      }
+      Self::Heap(buf) => buf.resize(new_len, 0),
+    }
+  }
+
+  #[inline]
+  fn truncate(&mut self, new_len: usize) {
+    match self {
+      Self::Inline(_, len) => *len = new_len.min(*len),
+      Self::Heap(buf) => buf.truncate(new_len),
+    }
+  }
+}
+
+impl core::ops::Deref for Scratch {
+  type Target = [u8];
+
+  #[inline]
+  fn deref(&self) -> &[u8] {
+    match self {
+      Self::Inline(buf, len) => &buf[..*len],
+      Self::Heap(buf) => buf,
+    }
+  }
+}
+
+impl core::ops::DerefMut for Scratch {
+  #[inline]
+  fn deref_mut(&mut self) -> &mut [u8] {
+    match self {
+      Self::Inline(buf, len) => &mut buf[..*len],
+      Self::Heap(buf) => buf,
+    }
+  }
+}
+
+/// A partially read value grows its buffer by at most this many bytes, or by the bytes
+/// read so far if more, at a time. The allocation follows the bytes actually read instead
+/// of a claimed length, while still only growing a logarithmic number of times.
+const MIN_READ_CHUNK_LEN: usize = 8 * 1024;
+
+/// The bytes of a single value read from a non-contiguous source.
+///
+/// The buffer never spans more bytes than [`Varint::required_len`] reported as needed, so
+/// filling it never reads past the end of the value. `required_len` is only called again
+/// once its previous result was reached, and the value is decoded once.
+pub(crate) struct PartialRead<V> {
+  buf: Scratch,
+  filled: usize,
+  required: usize,
+  _m: PhantomData<fn() -> V>,
+}
+
+impl<V> PartialRead<V> {
+  /// Returns the number of bytes read so far.
+  #[inline]
+  pub(crate) fn filled(&self) -> usize {
+    self.filled
+  }
+
+  /// Returns the buffer the next bytes of the value must be read into, it is never empty.
+  #[inline]
+  pub(crate) fn unfilled(&mut self) -> &mut [u8] {
+    &mut self.buf[self.filled..]
+  }
+
+  /// Grows the buffer towards the required length.
+  #[inline]
+  fn grow(&mut self) {
+    let more = (self.required - self.filled).min(self.filled.max(MIN_READ_CHUNK_LEN));
+    self.buf.resize(self.filled + more);
+  }
+}
+
+impl<V: Varint> PartialRead<V> {
+  #[inline]
+  pub(crate) fn new() -> Self {
+    let mut partial = Self {
+      buf: Scratch::new(0),
+      filled: 0,
+      required: V::MIN_ENCODED_LEN.get(),
+      _m: PhantomData,
+    };
+    partial.grow();
+    partial
+  }
+
+  /// Records that `n` bytes were read into [`unfilled`](Self::unfilled).
+  ///
+  /// Returns `None` if more bytes are required.
+  pub(crate) fn advance(&mut self, n: usize) -> Option<Result<(NonZeroUsize, V), DecodeError>> {
+    self.filled += n;
+    if self.filled < self.buf.len() {
+      return None;
+    }
+
+    if self.filled < self.required {
+      self.grow();
+      return None;
+    }
+
+    let required = match V::required_len(&self.buf) {
+      Ok(required) => required,
+      Err(e) => return Some(Err(e)),
+    };
+    if required.get() > self.filled {
+      if required > V::MAX_ENCODED_LEN {
+        return Some(Err(DecodeError::Overflow));
+      }
+
+      self.required = required.get();
+      self.grow();
+      return None;
+    }
+
+    Some(match V::decode(&self.buf) {
+      // A decoder must consume every byte it was handed here, as `required_len`
+      // reported each of them as needed.
+      Ok((read, value)) if read.get() == self.filled && required == read => Ok((read, value)),
+      Ok(_) => Err(crate::invalid_consumed_length()),
+      Err(e) => Err(e),
+    })
+  }
+}
@@ -0,0 +1,269 @@
+//! The compact format of [`to_slice`](crate::serde::to_slice),
+//! [`to_vec`](crate::serde::to_vec) and [`from_slice`](crate::serde::from_slice) is not
+//! self-describing, like postcard or bincode, with every number going through the
+//! encodings of this crate:
+//!
+//! | Data type | Encoding |
+//! |-----------|----------|
+//! | `bool` | one byte, `0` or `1` |
+//! | `u8`, `i8` | one byte, as is |
+//! | `u16` to `u128` | LEB128 varint |
+//! | `i16` to `i128` | zigzag varint |
+//! | `f32`, `f64` | the varint of their bits, like their [`Varint`](crate::Varint) impls |
+//! | `char` | [`encode_char_to`](crate::encode_char_to) |
+//! | `str`, bytes | `u64` varint length, then the bytes |
+//! | `Option` | `0`, or `1` then the value |
+//! | unit, unit struct | nothing |
+//! | newtype struct | the inner value |
+//! | sequence, map | `u64` varint length, then the elements or entries |
+//! | tuple, tuple struct, struct | the fields one after another |
+//! | enum variant | `u32` varint variant index, then the fields |
+//!
+//! [`core::time::Duration`] is encoded with
+//! [`encode_duration_to`](crate::encode_duration_to). serde sees it as a struct named
+//! `Duration` with a `u64` `secs` and a `u32` `nanos` field, so other structs with that
+//! name, those field names and a `u64` `secs` share its encoding. Their `nanos` must then
+//! be a `u32` below one second, or serializing them fails. Structs named `Duration` whose
+//! `secs` is not a `u64`, or with other fields, are encoded like any other struct. `f16`
+//! with the `half` feature is a newtype struct of its bits, so it ends up with the
+//! encoding of its [`Varint`](crate::Varint) impl as well.
+
+pub use de::{Deserializer, from_slice};
+pub use error::Error;
+#[cfg(any(feature = "std", feature = "alloc"))]
+pub use ser::to_vec;
+pub use ser::{Serializer, to_slice};
+
+mod de;
+mod error;
+mod ser;
+
+/// The name serde gives the struct of [`core::time::Duration`].
+const DURATION: &str = "Duration";
+/// The fields serde gives the struct of [`core::time::Duration`].
+const DURATION_FIELDS: &[&str] = &["secs", "nanos"];
+
+/// Serializes a [`Varint`](crate::Varint) field as its varint encoding, for use with
+/// `#[serde(with = "varing::serde::as_varint")]`.
+///
+/// Binary formats (where [`Serializer::is_human_readable`](serde_1::Serializer::is_human_readable)
+/// is `false`) get the bytes written by [`Varint::encode`](crate::Varint::encode), as a
+/// tuple of `u8`, which formats like bincode and postcard write as is, without a length.
+/// Human-readable formats get the value's own `Serialize` and `Deserialize`
+/// implementations, so a number stays a plain number in JSON.
+///
+/// ## Example
+///
+/// ```rust
+/// # use serde_1 as serde;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// # #[serde(crate = "serde_1")]
+/// struct Event {
+///   #[serde(with = "varing::serde::as_varint")]
+///   id: u64,
+///   #[serde(with = "varing::serde::as_varint")]
+///   offset: i32,
+/// }
+///
+/// let event = Event { id: 300, offset: -1 };
+/// let json = serde_json::to_string(&event).unwrap();
+/// assert_eq!(json, r#"{"id":300,"offset":-1}"#);
+/// assert_eq!(serde_json::from_str::<Event>(&json).unwrap(), event);
+/// ```
+pub mod as_varint {
+  use core::{fmt, marker::PhantomData};
+
+  use serde_1::{
+    Deserialize, Deserializer, Serialize, Serializer,
+    de::{Error as _, SeqAccess, Visitor},
+    ser::{Error as _, SerializeTuple},
+  };
+
+  use crate::{DecodeError, Varint};
+
+  use super::Staged;
+
+  /// Serializes `value` as its varint encoding, or with its own `Serialize` implementation
+  /// for human-readable formats.
+  pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    T: Varint + Serialize + ?Sized,
+    S: Serializer,
+  {
+    if serializer.is_human_readable() {
+      return value.serialize(serializer);
+    }
+
+    let mut buf = Staged::with_len(value.encoded_len().get()).map_err(S::Error::custom)?;
+    let written = value.encode(buf.as_mut()).map_err(S::Error::custom)?;
+    let encoded = &buf.as_ref()[..written.get()];
+    let mut tuple = serializer.serialize_tuple(encoded.len())?;
+    for byte in encoded {
+      tuple.serialize_element(byte)?;
+    }
+    tuple.end()
+  }
+
+  /// Deserializes a value serialized by [`serialize`].
+  pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+  where
+    T: Varint + Deserialize<'de>,
+    D: Deserializer<'de>,
+  {
+    if deserializer.is_human_readable() {
+      return T::deserialize(deserializer);
+    }
+
+    deserializer.deserialize_tuple(T::MAX_ENCODED_LEN.get(), VarintVisitor(PhantomData))
+  }
+
+  struct VarintVisitor<T>(PhantomData<T>);
+
+  impl<'de, T: Varint> Visitor<'de> for VarintVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+      formatter.write_str("a varint encoded value")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<T, A::Error>
+    where
+      A: SeqAccess<'de>,
+    {
+      // The bytes are requested one at a time, up to the length `required_len` reports,
+      // so no byte past the value is consumed and the value is decoded once.
+      let mut buf = Staged::new();
+      let mut required = T::MIN_ENCODED_LEN;
+      loop {
+        while buf.len() < required.get() {
+          let Some(byte) = seq.next_element::<u8>()? else {
+            return Err(A::Error::custom(DecodeError::insufficient_data(buf.len())));
+          };
+          buf.push(byte).map_err(A::Error::custom)?;
+        }
+
+        required = T::required_len(buf.as_ref()).map_err(A::Error::custom)?;
+        if required.get() <= buf.len() {
+          break;
+        }
+        if required > T::MAX_ENCODED_LEN {
+          return Err(A::Error::custom(DecodeError::Overflow));
+        }
+      }
+
+      match T::decode(buf.as_ref()) {
+        Ok((read, value)) if read == required && read.get() == buf.len() => Ok(value),
+        Ok(_) => Err(A::Error::custom(crate::invalid_consumed_length())),
+        Err(e) => Err(A::Error::custom(e)),
+      }
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<T, E>
+    where
+      E: serde_1::de::Error,
+    {
+      match T::decode(v) {
+        Ok((read, value)) if read.get() == v.len() => Ok(value),
+        Ok(_) => Err(E::invalid_length(v.len(), &self)),
+        Err(e) => Err(E::custom(e)),
+      }
+    }
+  }
+}
+
+/// Encoded values up to this length are staged on the stack.
+const INLINE_LEN: usize = 32;
+
+/// A buffer for a single encoded value, on the stack if it is short enough, on the heap
+/// otherwise if `alloc` is enabled.
+enum Staged {
+  Inline([u8; INLINE_LEN], usize),
+  #[cfg(any(feature = "std", feature = "alloc"))]
+  Heap(std::vec::Vec<u8>),
+}
+
+impl Staged {
+  #[inline]
+  const fn new() -> Self {
+    Self::Inline([0; INLINE_LEN], 0)
+  }
+
+  #[inline]
+  fn with_len(len: usize) -> Result<Self, &'static str> {
+    if len <= INLINE_LEN {
+      return Ok(Self::Inline([0; INLINE_LEN], len));
+    }
+
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    {
+      Ok(Self::Heap(std::vec![0; len]))
+    }
+
+    #[cfg(not(any(feature = "std", feature = "alloc")))]
+    {
+      Err(TOO_LONG)
+    }
+  }
+
+  #[inline]
+  fn len(&self) -> usize {
+    self.as_ref().len()
+  }
+
+  #[inline]
+  fn push(&mut self, byte: u8) -> Result<(), &'static str> {
+    match self {
+      Self::Inline(buf, len) if *len < INLINE_LEN => {
+        buf[*len] = byte;
+        *len += 1;
+        Ok(())
+      }
+      #[cfg(any(feature = "std", feature = "alloc"))]
+      Self::Inline(buf, _) => {
+        let mut heap = std::vec::Vec::with_capacity(INLINE_LEN * 2);
+        heap.extend_from_slice(buf);
+        heap.push(byte);
+        *self = Self::Heap(heap);
+        Ok(())
+      }
+      #[cfg(not(any(feature = "std", feature = "alloc")))]
+      Self::Inline(..) => Err(TOO_LONG),
+      #[cfg(any(feature = "std", feature = "alloc"))]
+      Self::Heap(buf) => {
+        buf.push(byte);
+        Ok(())
+      }
+    }
+  }
+}
+
+/// The error of a value longer than [`INLINE_LEN`] without `alloc`.
+#[cfg(not(any(feature = "std", feature = "alloc")))]
+const TOO_LONG: &str = "varint encoded values longer than 32 bytes require the `alloc` feature";
+
+impl AsRef<[u8]> for Staged {
+  #[inline]
+  fn as_ref(&self) -> &[u8] {
+    match self {
+      Self::Inline(buf, len) => &buf[..*len],
+      #[cfg(any(feature = "std", feature = "alloc"))]
+      Self::Heap(buf) => buf,
+    }
+  }
+}
+
+impl AsMut<[u8]> for Staged {
+  #[inline]
+  fn as_mut(&mut self) -> &mut [u8] {
+    match self {
+      Self::Inline(buf, len) => &mut buf[..*len],
+      #[cfg(any(feature = "std", feature = "alloc"))]
+      Self::Heap(buf) => buf,
+    }
+  }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests;
@@ -0,0 +1,487 @@
+use core::{num::NonZeroUsize, time::Duration};
+
+use serde_1::de::{self, DeserializeSeed, Error as _, IntoDeserializer, Visitor};
+
+use crate::{DecodeError, Varint, derive::decode_field};
+
+use super::{DURATION, DURATION_FIELDS, Error};
+
+/// Deserializes a `T` from the start of `buf` and returns the number of bytes read and
+/// the value.
+///
+/// Strings and byte slices can borrow from `buf`.
+///
+/// ## Example
+///
+/// ```rust
+/// let buf = [0xAC, 0x02, 0x01, 2, b'h', b'i', 0xFF];
+/// let (read, value) = varing::serde::from_slice::<(u64, i32, &str)>(&buf).unwrap();
+/// assert_eq!(read, 6);
+/// assert_eq!(value, (300, -1, "hi"));
+/// ```
+pub fn from_slice<'de, T: de::Deserialize<'de>>(buf: &'de [u8]) -> Result<(usize, T), Error> {
+  let mut deserializer = Deserializer::new(buf);
+  let value = T::deserialize(&mut deserializer)?;
+  Ok((deserializer.position(), value))
+}
+
+/// The deserializer of the compact format.
+///
+/// The format is not self-describing, so `deserialize_any` and `deserialize_ignored_any`
+/// are not supported.
+pub struct Deserializer<'de> {
+  buf: &'de [u8],
+  pos: usize,
+}
+
+impl<'de> Deserializer<'de> {
+  /// Creates a deserializer reading from the start of `buf`.
+  #[inline]
+  pub const fn new(buf: &'de [u8]) -> Self {
+    Self { buf, pos: 0 }
+  }
+
+  /// Returns the number of bytes read so far.
+  #[inline]
+  pub const fn position(&self) -> usize {
+    self.pos
+  }
+
+  fn read_bytes(&mut self, len: usize) -> Result<&'de [u8], Error> {
+    let available = self.buf.len();
+    match self.pos.checked_add(len) {
+      Some(end) if end <= available => {
+        let bytes = &self.buf[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+      }
+      Some(end) => Err(match NonZeroUsize::new(end) {
+        Some(required) => DecodeError::insufficient_data_with_required(required, available),
+        None => DecodeError::insufficient_data(available),
+      })?,
+      None => Err(DecodeError::insufficient_data(available).into()),
+    }
+  }
+
+  fn read_byte(&mut self) -> Result<u8, Error> {
+    self.read_bytes(1).map(|bytes| bytes[0])
+  }
+
+  fn read_varint<V: Varint>(&mut self) -> Result<V, Error> {
+    let (pos, value) = decode_field(self.buf, self.pos)?;
+    self.pos = pos;
+    Ok(value)
+  }
+
+  fn read_len(&mut self) -> Result<usize, Error> {
+    usize::try_from(self.read_varint::<u64>()?).map_err(|_| DecodeError::Overflow.into())
+  }
+
+  fn read_flag(&mut self, what: &'static str) -> Result<bool, Error> {
+    match self.read_byte()? {
+      0 => Ok(false),
+      1 => Ok(true),
+      _ => Err(DecodeError::other(what).into()),
+    }
+  }
+}
+
+impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
+  type Error = Error;
+
+  #[inline]
+  fn is_human_readable(&self) -> bool {
+    false
+  }
+
+  fn deserialize_any<V: Visitor<'de>>(self, _: V) -> Result<V::Value, Error> {
+    Err(Error::custom(
+      "the format is not self-describing, deserialize_any is not supported",
+    ))
+  }
+
+  fn deserialize_ignored_any<V: Visitor<'de>>(self, _: V) -> Result<V::Value, Error> {
+    Err(Error::custom(
+      "the format is not self-describing, deserialize_ignored_any is not supported",
+    ))
+  }
+
+  #[inline]
+  fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+    visitor.visit_bool(self.read_flag("invalid bool")?)
+  }
+
+  #[inline]
+  fn deserialize_i8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+    visitor.visit_i8(self.read_byte()? as i8)
+  }
+
+  #[inline]
+  fn deserialize_i16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+    visitor.visit_i16(self.read_varint()?)
+  }
+
+  #[inline]
+  fn deserialize_i32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+    visitor.visit_i32(self.read_varint()?)
+  }
+
+  #[inline]
+  fn deserialize_i64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+    visitor.visit_i64(self.read_varint()?)
+  }
+
+  #[inline]
+  fn deserialize_i128<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+    visitor.visit_i128(self.read_varint()?)
+  }
+
+  #[inline]
+  fn deserialize_u8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+    visitor.visit_u8(self.read_byte()?)
+  }
+
+  #[inline]
+  fn deserialize_u16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+    visitor.visit_u16(self.read_varint()?)
+  }
+
+  #[inline]
+  fn deserialize_u32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+    visitor.visit_u32(self.read_varint()?)
+  }
+
+  #[inline]
+  fn deserialize_u64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+    visitor.visit_u64(self.read_varint()?)
+  }
+
+  #[inline]
+  fn deserialize_u128<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+    visitor.visit_u128(self.read_varint()?)
+  }
+
+  #[inline]
+  fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+    visitor.visit_f32(self.read_varint()?)
+  }
+
+  #[inline]
+  fn deserialize_f64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+    visitor.visit_f64(self.read_varint()?)
+  }
+
+  #[inline]
+  fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+    visitor.visit_char(self.read_varint()?)
+  }
+
+  #[inline]
+  fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+    let len = self.read_len()?;
+    let bytes = self.read_bytes(len)?;
+    let s = core::str::from_utf8(bytes).map_err(|_| DecodeError::other("invalid UTF-8 string"))?;
+    visitor.visit_borrowed_str(s)
+  }
+
+  #[inline]
+  fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+    self.deserialize_str(visitor)
+  }
+
+  #[inline]
+  fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+    let len = self.read_len()?;
+    visitor.visit_borrowed_bytes(self.read_bytes(len)?)
+  }
+
+  #[inline]
+  fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+    self.deserialize_bytes(visitor)
+  }
+
+  #[inline]
+  fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+    if self.read_flag("invalid Option presence flag")? {
+      visitor.visit_some(self)
+    } else {
+      visitor.visit_none()
+    }
+  }
+
+  #[inline]
+  fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+    visitor.visit_unit()
+  }
+
+  #[inline]
+  fn deserialize_unit_struct<V: Visitor<'de>>(
+    self,
+    _: &'static str,
+    visitor: V,
+  ) -> Result<V::Value, Error> {
+    visitor.visit_unit()
+  }
+
+  #[inline]
+  fn deserialize_newtype_struct<V: Visitor<'de>>(
+    self,
+    _: &'static str,
+    visitor: V,
+  ) -> Result<V::Value, Error> {
+    visitor.visit_newtype_struct(self)
+  }
+
+  #[inline]
+  fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+    let len = self.read_len()?;
+    visitor.visit_seq(Access { de: self, len })
+  }
+
+  #[inline]
+  fn deserialize_tuple<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value, Error> {
+    visitor.visit_seq(Access { de: self, len })
+  }
+
+  #[inline]
+  fn deserialize_tuple_struct<V: Visitor<'de>>(
+    self,
+    _: &'static str,
+    len: usize,
+    visitor: V,
+  ) -> Result<V::Value, Error> {
+    self.deserialize_tuple(len, visitor)
+  }
+
+  #[inline]
+  fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+    let len = self.read_len()?;
+    visitor.visit_map(Access { de: self, len })
+  }
+
+  #[inline]
+  fn deserialize_struct<V: Visitor<'de>>(
+    self,
+    name: &'static str,
+    fields: &'static [&'static str],
+    visitor: V,
+  ) -> Result<V::Value, Error> {
+    if name != DURATION || fields != DURATION_FIELDS {
+      return self.deserialize_tuple(fields.len(), visitor);
+    }
+
+    visitor.visit_seq(DurationAccess {
+      de: self,
+      len: DURATION_FIELDS.len(),
+      nanos: None,
+    })
+  }
+
+  #[inline]
+  fn deserialize_enum<V: Visitor<'de>>(
+    self,
+    _: &'static str,
+    _: &'static [&'static str],
+    visitor: V,
+  ) -> Result<V::Value, Error> {
+    visitor.visit_enum(self)
+  }
+
+  #[inline]
+  fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+    visitor.visit_u32(self.read_varint()?)
+  }
+}
+
+/// Hands out the `secs` and `nanos` of a struct named `Duration`.
+///
+/// If `secs` is read as a `u64`, the serializer wrote the whole struct with the
+/// [`Duration`] varint encoding, and `nanos` comes from it as well.
+struct DurationAccess<'a, 'de> {
+  de: &'a mut Deserializer<'de>,
+  len: usize,
+  nanos: Option<u32>,
+}
+
+impl<'de> de::SeqAccess<'de> for DurationAccess<'_, 'de> {
+  type Error = Error;
+
+  fn next_element_seed<T: DeserializeSeed<'de>>(
+    &mut self,
+    seed: T,
+  ) -> Result<Option<T::Value>, Error> {
+    let value = match (self.len, self.nanos) {
+      (0, _) => return Ok(None),
+      (1, Some(nanos)) => seed.deserialize(IntoDeserializer::<Error>::into_deserializer(nanos)),
+      (1, None) => seed.deserialize(&mut *self.de),
+      _ => seed.deserialize(DurationSecs {
+        de: &mut *self.de,
+        nanos: &mut self.nanos,
+      }),
+    }?;
+    self.len -= 1;
+    Ok(Some(value))
+  }
+
+  #[inline]
+  fn size_hint(&self) -> Option<usize> {
+    Some(self.len)
+  }
+}
+
+/// Reads the `secs` of a struct named `Duration`: a `u64` is the [`Duration`] varint,
+/// whose nanoseconds are kept for `nanos`, and anything else is read as usual.
+struct DurationSecs<'a, 'de> {
+  de: &'a mut Deserializer<'de>,
+  nanos: &'a mut Option<u32>,
+}
+
+macro_rules! forward {
+  ($($method:ident($($arg:ident: $ty:ty),*)),+$(,)?) => {
+    $(
+      #[inline]
+      fn $method<V: Visitor<'de>>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value, Error> {
+        de::Deserializer::$method(self.de, $($arg,)* visitor)
+      }
+    )*
+  };
+}
+
+impl<'de> de::Deserializer<'de> for DurationSecs<'_, 'de> {
+  type Error = Error;
+
+  #[inline]
+  fn is_human_readable(&self) -> bool {
+    false
+  }
+
+  #[inline]
+  fn deserialize_u64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+    let duration = self.de.read_varint::<Duration>()?;
+    *self.nanos = Some(duration.subsec_nanos());
+    visitor.visit_u64(duration.as_secs())
+  }
+
+  forward!(
+    deserialize_any(),
+    deserialize_ignored_any(),
+    deserialize_bool(),
+    deserialize_i8(),
+    deserialize_i16(),
+    deserialize_i32(),
+    deserialize_i64(),
+    deserialize_i128(),
+    deserialize_u8(),
+    deserialize_u16(),
+    deserialize_u32(),
+    deserialize_u128(),
+    deserialize_f32(),
+    deserialize_f64(),
+    deserialize_char(),
+    deserialize_str(),
+    deserialize_string(),
+    deserialize_bytes(),
+    deserialize_byte_buf(),
+    deserialize_option(),
+    deserialize_unit(),
+    deserialize_unit_struct(name: &'static str),
+    deserialize_newtype_struct(name: &'static str),
+    deserialize_seq(),
+    deserialize_tuple(len: usize),
+    deserialize_tuple_struct(name: &'static str, len: usize),
+    deserialize_map(),
+    deserialize_struct(name: &'static str, fields: &'static [&'static str]),
+    deserialize_enum(name: &'static str, variants: &'static [&'static str]),
+    deserialize_identifier(),
+  );
+}
+
+/// Hands out the elements of a sequence, tuple or map with a known length.
+struct Access<'a, 'de> {
+  de: &'a mut Deserializer<'de>,
+  len: usize,
+}
+
+impl<'de> de::SeqAccess<'de> for Access<'_, 'de> {
+  type Error = Error;
+
+  #[inline]
+  fn next_element_seed<T: DeserializeSeed<'de>>(
+    &mut self,
+    seed: T,
+  ) -> Result<Option<T::Value>, Error> {
+    if self.len == 0 {
+      return Ok(None);
+    }
+    self.len -= 1;
+    seed.deserialize(&mut *self.de).map(Some)
+  }
+
+  #[inline]
+  fn size_hint(&self) -> Option<usize> {
+    Some(self.len)
+  }
+}
+
+impl<'de> de::MapAccess<'de> for Access<'_, 'de> {
+  type Error = Error;
+
+  #[inline]
+  fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, Error> {
+    if self.len == 0 {
+      return Ok(None);
+    }
+    self.len -= 1;
+    seed.deserialize(&mut *self.de).map(Some)
+  }
+
+  #[inline]
+  fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+    seed.deserialize(&mut *self.de)
+  }
+
+  #[inline]
+  fn size_hint(&self) -> Option<usize> {
+    Some(self.len)
+  }
+}
+
+impl<'de> de::EnumAccess<'de> for &mut Deserializer<'de> {
+  type Error = Error;
+  type Variant = Self;
+
+  #[inline]
+  fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self), Error> {
+    let index = self.read_varint::<u32>()?;
+    let value = seed.deserialize(IntoDeserializer::<Error>::into_deserializer(index))?;
+    Ok((value, self))
+  }
+}
+
+impl<'de> de::VariantAccess<'de> for &mut Deserializer<'de> {
+  type Error = Error;
+
+  #[inline]
+  fn unit_variant(self) -> Result<(), Error> {
+    Ok(())
+  }
+
+  #[inline]
+  fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Error> {
+    seed.deserialize(self)
+  }
+
+  #[inline]
+  fn tuple_variant<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value, Error> {
+    de::Deserializer::deserialize_tuple(self, len, visitor)
+  }
+
+  #[inline]
+  fn struct_variant<V: Visitor<'de>>(
+    self,
+    fields: &'static [&'static str],
+    visitor: V,
+  ) -> Result<V::Value, Error> {
+    de::Deserializer::deserialize_tuple(self, fields.len(), visitor)
+  }
+}
@@ -0,0 +1,43 @@
+use core::fmt::Display;
+
+use crate::{DecodeError, EncodeError};
+
+/// An error of the compact serde format.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[non_exhaustive]
+pub enum Error {
+  /// Serializing failed.
+  #[error(transparent)]
+  Encode(#[from] EncodeError),
+  /// Deserializing failed.
+  #[error(transparent)]
+  Decode(#[from] DecodeError),
+}
+
+impl serde_1::ser::Error for Error {
+  #[cfg(any(feature = "std", feature = "alloc"))]
+  fn custom<T: Display>(msg: T) -> Self {
+    use std::string::ToString;
+
+    Self::Encode(EncodeError::other(msg.to_string()))
+  }
+
+  #[cfg(not(any(feature = "std", feature = "alloc")))]
+  fn custom<T: Display>(_: T) -> Self {
+    Self::Encode(EncodeError::other("custom serialization error"))
+  }
+}
+
+impl serde_1::de::Error for Error {
+  #[cfg(any(feature = "std", feature = "alloc"))]
+  fn custom<T: Display>(msg: T) -> Self {
+    use std::string::ToString;
+
+    Self::Decode(DecodeError::other(msg.to_string()))
+  }
+
+  #[cfg(not(any(feature = "std", feature = "alloc")))]
+  fn custom<T: Display>(_: T) -> Self {
+    Self::Decode(DecodeError::other("custom deserialization error"))
+  }
+}
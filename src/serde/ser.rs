@@ -0,0 +1,651 @@
+use core::{num::NonZeroUsize, time::Duration};
+
+use serde_1::{
+  Serialize,
+  ser::{self, Error as _, Impossible},
+};
+
+use crate::{EncodeError, Varint};
+
+use super::{DURATION, DURATION_FIELDS, Error};
+
+/// Serializes `value` into `buf` and returns the number of bytes written.
+///
+/// ## Example
+///
+/// ```rust
+/// let mut buf = [0u8; 8];
+/// let written = varing::serde::to_slice(&(300u64, -1i32, "hi"), &mut buf).unwrap();
+/// assert_eq!(&buf[..written], [0xAC, 0x02, 0x01, 2, b'h', b'i']);
+/// ```
+pub fn to_slice<T: Serialize + ?Sized>(value: &T, buf: &mut [u8]) -> Result<usize, Error> {
+  let mut serializer = Serializer::new(buf);
+  value.serialize(&mut serializer)?;
+  Ok(serializer.position())
+}
+
+/// Serializes `value` into a new `Vec`.
+///
+/// ## Example
+///
+/// ```rust
+/// let buf = varing::serde::to_vec(&(300u64, -1i32, "hi")).unwrap();
+/// assert_eq!(buf, [0xAC, 0x02, 0x01, 2, b'h', b'i']);
+/// ```
+#[cfg(any(feature = "std", feature = "alloc"))]
+#[cfg_attr(docsrs, doc(cfg(any(feature = "std", feature = "alloc"))))]
+pub fn to_vec<T: Serialize + ?Sized>(value: &T) -> Result<std::vec::Vec<u8>, Error> {
+  let mut buf = std::vec::Vec::new();
+  let mut serializer = Serializer::from_vec(&mut buf);
+  value.serialize(&mut serializer)?;
+  Ok(buf)
+}
+
+enum Output<'a> {
+  Slice(&'a mut [u8]),
+  #[cfg(any(feature = "std", feature = "alloc"))]
+  Vec(&'a mut std::vec::Vec<u8>),
+}
+
+/// The serializer of the compact format.
+///
+/// See the [module documentation](crate::serde) for the layout of each data type.
+pub struct Serializer<'a> {
+  out: Output<'a>,
+  pos: usize,
+}
+
+impl<'a> Serializer<'a> {
+  /// Creates a serializer writing to the start of `buf`.
+  #[inline]
+  pub const fn new(buf: &'a mut [u8]) -> Self {
+    Self {
+      out: Output::Slice(buf),
+      pos: 0,
+    }
+  }
+
+  /// Creates a serializer appending to `buf`.
+  #[cfg(any(feature = "std", feature = "alloc"))]
+  #[cfg_attr(docsrs, doc(cfg(any(feature = "std", feature = "alloc"))))]
+  #[inline]
+  pub const fn from_vec(buf: &'a mut std::vec::Vec<u8>) -> Self {
+    Self {
+      out: Output::Vec(buf),
+      pos: 0,
+    }
+  }
+
+  /// Returns the number of bytes written so far.
+  #[inline]
+  pub const fn position(&self) -> usize {
+    self.pos
+  }
+
+  fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), Error> {
+    let Some(len) = NonZeroUsize::new(bytes.len()) else {
+      return Ok(());
+    };
+
+    match &mut self.out {
+      Output::Slice(buf) => {
+        let remaining = &mut buf[self.pos..];
+        if remaining.len() < len.get() {
+          return Err(self.insufficient_space(len));
+        }
+        remaining[..len.get()].copy_from_slice(bytes);
+      }
+      #[cfg(any(feature = "std", feature = "alloc"))]
+      Output::Vec(buf) => buf.extend_from_slice(bytes),
+    }
+    self.pos += len.get();
+    Ok(())
+  }
+
+  fn write_varint<V: Varint + ?Sized>(&mut self, value: &V) -> Result<(), Error> {
+    let written = match &mut self.out {
+      Output::Slice(buf) => match value.encode(&mut buf[self.pos..]) {
+        Ok(written) => written,
+        Err(EncodeError::InsufficientSpace(e)) => {
+          return Err(self.insufficient_space(e.requested()));
+        }
+        Err(e) => return Err(e.into()),
+      },
+      #[cfg(any(feature = "std", feature = "alloc"))]
+      Output::Vec(buf) => {
+        let start = buf.len();
+        buf.resize(start + value.encoded_len().get(), 0);
+        let written = value.encode(&mut buf[start..])?;
+        buf.truncate(start + written.get());
+        written
+      }
+    };
+    self.pos += written.get();
+    Ok(())
+  }
+
+  fn write_len(&mut self, len: usize) -> Result<(), Error> {
+    self.write_varint(&(len as u64))
+  }
+
+  /// Reports a shortfall relative to the whole buffer.
+  fn insufficient_space(&self, requested: NonZeroUsize) -> Error {
+    let available = match &self.out {
+      Output::Slice(buf) => buf.len(),
+      #[cfg(any(feature = "std", feature = "alloc"))]
+      Output::Vec(buf) => buf.len(),
+    };
+    EncodeError::insufficient_space(requested.saturating_add(self.pos), available).into()
+  }
+}
+
+impl<'s, 'a> ser::Serializer for &'s mut Serializer<'a> {
+  type Ok = ();
+  type Error = Error;
+  type SerializeSeq = Self;
+  type SerializeTuple = Self;
+  type SerializeTupleStruct = Self;
+  type SerializeTupleVariant = Self;
+  type SerializeMap = Self;
+  type SerializeStruct = StructSerializer<'s, 'a>;
+  type SerializeStructVariant = Self;
+
+  #[inline]
+  fn is_human_readable(&self) -> bool {
+    false
+  }
+
+  #[inline]
+  fn serialize_bool(self, v: bool) -> Result<(), Error> {
+    self.write_bytes(&[v as u8])
+  }
+
+  #[inline]
+  fn serialize_i8(self, v: i8) -> Result<(), Error> {
+    self.write_bytes(&[v as u8])
+  }
+
+  #[inline]
+  fn serialize_i16(self, v: i16) -> Result<(), Error> {
+    self.write_varint(&v)
+  }
+
+  #[inline]
+  fn serialize_i32(self, v: i32) -> Result<(), Error> {
+    self.write_varint(&v)
+  }
+
+  #[inline]
+  fn serialize_i64(self, v: i64) -> Result<(), Error> {
+    self.write_varint(&v)
+  }
+
+  #[inline]
+  fn serialize_i128(self, v: i128) -> Result<(), Error> {
+    self.write_varint(&v)
+  }
+
+  #[inline]
+  fn serialize_u8(self, v: u8) -> Result<(), Error> {
+    self.write_bytes(&[v])
+  }
+
+  #[inline]
+  fn serialize_u16(self, v: u16) -> Result<(), Error> {
+    self.write_varint(&v)
+  }
+
+  #[inline]
+  fn serialize_u32(self, v: u32) -> Result<(), Error> {
+    self.write_varint(&v)
+  }
+
+  #[inline]
+  fn serialize_u64(self, v: u64) -> Result<(), Error> {
+    self.write_varint(&v)
+  }
+
+  #[inline]
+  fn serialize_u128(self, v: u128) -> Result<(), Error> {
+    self.write_varint(&v)
+  }
+
+  #[inline]
+  fn serialize_f32(self, v: f32) -> Result<(), Error> {
+    self.write_varint(&v)
+  }
+
+  #[inline]
+  fn serialize_f64(self, v: f64) -> Result<(), Error> {
+    self.write_varint(&v)
+  }
+
+  #[inline]
+  fn serialize_char(self, v: char) -> Result<(), Error> {
+    self.write_varint(&v)
+  }
+
+  #[inline]
+  fn serialize_str(self, v: &str) -> Result<(), Error> {
+    self.serialize_bytes(v.as_bytes())
+  }
+
+  #[inline]
+  fn serialize_bytes(self, v: &[u8]) -> Result<(), Error> {
+    self.write_len(v.len())?;
+    self.write_bytes(v)
+  }
+
+  #[inline]
+  fn serialize_none(self) -> Result<(), Error> {
+    self.write_bytes(&[0])
+  }
+
+  #[inline]
+  fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<(), Error> {
+    self.write_bytes(&[1])?;
+    value.serialize(self)
+  }
+
+  #[inline]
+  fn serialize_unit(self) -> Result<(), Error> {
+    Ok(())
+  }
+
+  #[inline]
+  fn serialize_unit_struct(self, _: &'static str) -> Result<(), Error> {
+    Ok(())
+  }
+
+  #[inline]
+  fn serialize_unit_variant(
+    self,
+    _: &'static str,
+    index: u32,
+    _: &'static str,
+  ) -> Result<(), Error> {
+    self.write_varint(&index)
+  }
+
+  #[inline]
+  fn serialize_newtype_struct<T: Serialize + ?Sized>(
+    self,
+    _: &'static str,
+    value: &T,
+  ) -> Result<(), Error> {
+    value.serialize(self)
+  }
+
+  #[inline]
+  fn serialize_newtype_variant<T: Serialize + ?Sized>(
+    self,
+    _: &'static str,
+    index: u32,
+    _: &'static str,
+    value: &T,
+  ) -> Result<(), Error> {
+    self.write_varint(&index)?;
+    value.serialize(self)
+  }
+
+  #[inline]
+  fn serialize_seq(self, len: Option<usize>) -> Result<Self, Error> {
+    let len = len.ok_or_else(|| Error::custom("sequences must have a known length"))?;
+    self.write_len(len)?;
+    Ok(self)
+  }
+
+  #[inline]
+  fn serialize_tuple(self, _: usize) -> Result<Self, Error> {
+    Ok(self)
+  }
+
+  #[inline]
+  fn serialize_tuple_struct(self, _: &'static str, _: usize) -> Result<Self, Error> {
+    Ok(self)
+  }
+
+  #[inline]
+  fn serialize_tuple_variant(
+    self,
+    _: &'static str,
+    index: u32,
+    _: &'static str,
+    _: usize,
+  ) -> Result<Self, Error> {
+    self.write_varint(&index)?;
+    Ok(self)
+  }
+
+  #[inline]
+  fn serialize_map(self, len: Option<usize>) -> Result<Self, Error> {
+    let len = len.ok_or_else(|| Error::custom("maps must have a known length"))?;
+    self.write_len(len)?;
+    Ok(self)
+  }
+
+  #[inline]
+  fn serialize_struct(
+    self,
+    name: &'static str,
+    len: usize,
+  ) -> Result<Self::SerializeStruct, Error> {
+    let duration = name == DURATION && len == DURATION_FIELDS.len();
+    Ok(StructSerializer {
+      ser: self,
+      duration: duration.then_some(DurationFields::Secs),
+    })
+  }
+
+  #[inline]
+  fn serialize_struct_variant(
+    self,
+    _: &'static str,
+    index: u32,
+    _: &'static str,
+    _: usize,
+  ) -> Result<Self, Error> {
+    self.write_varint(&index)?;
+    Ok(self)
+  }
+
+  /// Without `alloc` the value is formatted twice, once to count its length.
+  #[cfg(not(any(feature = "std", feature = "alloc")))]
+  fn collect_str<T: core::fmt::Display + ?Sized>(self, value: &T) -> Result<(), Error> {
+    use core::fmt::Write;
+
+    struct Counter(usize);
+
+    impl Write for Counter {
+      fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        self.0 += s.len();
+        Ok(())
+      }
+    }
+
+    struct Writer<'s, 'a>(&'s mut Serializer<'a>, Option<Error>);
+
+    impl Write for Writer<'_, '_> {
+      fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        self.0.write_bytes(s.as_bytes()).map_err(|e| {
+          self.1 = Some(e);
+          core::fmt::Error
+        })
+      }
+    }
+
+    let mut counter = Counter(0);
+    write!(counter, "{value}").map_err(Error::custom)?;
+    self.write_len(counter.0)?;
+
+    let start = self.pos;
+    let mut writer = Writer(self, None);
+    if write!(writer, "{value}").is_err() {
+      return Err(
+        writer
+          .1
+          .unwrap_or_else(|| Error::custom("formatting failed")),
+      );
+    }
+    if self.pos - start != counter.0 {
+      return Err(Error::custom(
+        "Display wrote a different length the second time",
+      ));
+    }
+    Ok(())
+  }
+}
+
+macro_rules! compound {
+  ($($trait:ident::$method:ident),+$(,)?) => {
+    $(
+      impl ser::$trait for &mut Serializer<'_> {
+        type Ok = ();
+        type Error = Error;
+
+        #[inline]
+        fn $method<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+          value.serialize(&mut **self)
+        }
+
+        #[inline]
+        fn end(self) -> Result<(), Error> {
+          Ok(())
+        }
+      }
+    )*
+  };
+}
+
+compound!(
+  SerializeSeq::serialize_element,
+  SerializeTuple::serialize_element,
+  SerializeTupleStruct::serialize_field,
+  SerializeTupleVariant::serialize_field,
+);
+
+impl ser::SerializeMap for &mut Serializer<'_> {
+  type Ok = ();
+  type Error = Error;
+
+  #[inline]
+  fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Error> {
+    key.serialize(&mut **self)
+  }
+
+  #[inline]
+  fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+    value.serialize(&mut **self)
+  }
+
+  #[inline]
+  fn end(self) -> Result<(), Error> {
+    Ok(())
+  }
+}
+
+impl ser::SerializeStructVariant for &mut Serializer<'_> {
+  type Ok = ();
+  type Error = Error;
+
+  #[inline]
+  fn serialize_field<T: Serialize + ?Sized>(
+    &mut self,
+    _: &'static str,
+    value: &T,
+  ) -> Result<(), Error> {
+    value.serialize(&mut **self)
+  }
+
+  #[inline]
+  fn end(self) -> Result<(), Error> {
+    Ok(())
+  }
+}
+
+/// Where a struct named `Duration` is in its fields.
+enum DurationFields {
+  Secs,
+  Nanos(u64),
+  Done(Duration),
+}
+
+/// Serializes the fields of a struct one after another, except for a `Duration` whose
+/// `secs` is a `u64`, which is written with the [`Duration`] varint encoding once its
+/// `nanos` is known.
+#[doc(hidden)]
+pub struct StructSerializer<'s, 'a> {
+  ser: &'s mut Serializer<'a>,
+  duration: Option<DurationFields>,
+}
+
+impl ser::SerializeStruct for StructSerializer<'_, '_> {
+  type Ok = ();
+  type Error = Error;
+
+  fn serialize_field<T: Serialize + ?Sized>(
+    &mut self,
+    key: &'static str,
+    value: &T,
+  ) -> Result<(), Error> {
+    self.duration = match self.duration.take() {
+      Some(DurationFields::Secs) if key == DURATION_FIELDS[0] => match value.serialize(Probe) {
+        Ok(Scalar::U64(secs)) => Some(DurationFields::Nanos(secs)),
+        _ => return value.serialize(&mut *self.ser),
+      },
+      // The deserializer has already read the whole `Duration` for the `u64` `secs`.
+      Some(DurationFields::Nanos(secs)) if key == DURATION_FIELDS[1] => {
+        match value.serialize(Probe) {
+          Ok(Scalar::U32(nanos)) if nanos < 1_000_000_000 => {
+            Some(DurationFields::Done(Duration::new(secs, nanos)))
+          }
+          _ => {
+            return Err(Error::custom(
+              "the nanos of a Duration struct must be a u32 below one second",
+            ));
+          }
+        }
+      }
+      // Not a `Duration` after all, write what was held back as is.
+      Some(DurationFields::Nanos(secs)) => {
+        self.ser.write_varint(&secs)?;
+        return value.serialize(&mut *self.ser);
+      }
+      Some(DurationFields::Done(duration)) => {
+        self.ser.write_varint(&duration.as_secs())?;
+        self.ser.write_varint(&duration.subsec_nanos())?;
+        return value.serialize(&mut *self.ser);
+      }
+      Some(DurationFields::Secs) | None => return value.serialize(&mut *self.ser),
+    };
+    Ok(())
+  }
+
+  fn end(self) -> Result<(), Error> {
+    match self.duration {
+      Some(DurationFields::Done(duration)) => self.ser.write_varint(&duration),
+      Some(DurationFields::Nanos(secs)) => self.ser.write_varint(&secs),
+      Some(DurationFields::Secs) | None => Ok(()),
+    }
+  }
+}
+
+/// What a `Duration` field serializes as.
+enum Scalar {
+  U64(u64),
+  U32(u32),
+  Other,
+}
+
+/// Finds out whether a field serializes as a single `u64` or `u32`, without writing it.
+struct Probe;
+
+macro_rules! other {
+  ($($method:ident($($ty:ty),*)),+$(,)?) => {
+    $(
+      #[inline]
+      fn $method(self, $(_: $ty),*) -> Result<Scalar, Error> {
+        Ok(Scalar::Other)
+      }
+    )*
+  };
+}
+
+macro_rules! not_scalar {
+  ($($method:ident($($ty:ty),*) -> $compound:ident),+$(,)?) => {
+    $(
+      #[inline]
+      fn $method(self, $(_: $ty),*) -> Result<Self::$compound, Error> {
+        Err(EncodeError::other("not a scalar").into())
+      }
+    )*
+  };
+}
+
+impl ser::Serializer for Probe {
+  type Ok = Scalar;
+  type Error = Error;
+  type SerializeSeq = Impossible<Scalar, Error>;
+  type SerializeTuple = Impossible<Scalar, Error>;
+  type SerializeTupleStruct = Impossible<Scalar, Error>;
+  type SerializeTupleVariant = Impossible<Scalar, Error>;
+  type SerializeMap = Impossible<Scalar, Error>;
+  type SerializeStruct = Impossible<Scalar, Error>;
+  type SerializeStructVariant = Impossible<Scalar, Error>;
+
+  #[inline]
+  fn is_human_readable(&self) -> bool {
+    false
+  }
+
+  #[inline]
+  fn serialize_u64(self, v: u64) -> Result<Scalar, Error> {
+    Ok(Scalar::U64(v))
+  }
+
+  #[inline]
+  fn serialize_u32(self, v: u32) -> Result<Scalar, Error> {
+    Ok(Scalar::U32(v))
+  }
+
+  other!(
+    serialize_bool(bool),
+    serialize_i8(i8),
+    serialize_i16(i16),
+    serialize_i32(i32),
+    serialize_i64(i64),
+    serialize_i128(i128),
+    serialize_u8(u8),
+    serialize_u16(u16),
+    serialize_u128(u128),
+    serialize_f32(f32),
+    serialize_f64(f64),
+    serialize_char(char),
+    serialize_str(&str),
+    serialize_bytes(&[u8]),
+    serialize_none(),
+    serialize_unit(),
+    serialize_unit_struct(&'static str),
+    serialize_unit_variant(&'static str, u32, &'static str),
+  );
+
+  #[inline]
+  fn serialize_some<T: Serialize + ?Sized>(self, _: &T) -> Result<Scalar, Error> {
+    Ok(Scalar::Other)
+  }
+
+  #[inline]
+  fn serialize_newtype_struct<T: Serialize + ?Sized>(
+    self,
+    _: &'static str,
+    _: &T,
+  ) -> Result<Scalar, Error> {
+    Ok(Scalar::Other)
+  }
+
+  #[inline]
+  fn serialize_newtype_variant<T: Serialize + ?Sized>(
+    self,
+    _: &'static str,
+    _: u32,
+    _: &'static str,
+    _: &T,
+  ) -> Result<Scalar, Error> {
+    Ok(Scalar::Other)
+  }
+
+  #[inline]
+  fn collect_str<T: core::fmt::Display + ?Sized>(self, _: &T) -> Result<Scalar, Error> {
+    Ok(Scalar::Other)
+  }
+
+  not_scalar!(
+    serialize_seq(Option<usize>) -> SerializeSeq,
+    serialize_tuple(usize) -> SerializeTuple,
+    serialize_tuple_struct(&'static str, usize) -> SerializeTupleStruct,
+    serialize_tuple_variant(&'static str, u32, &'static str, usize) -> SerializeTupleVariant,
+    serialize_map(Option<usize>) -> SerializeMap,
+    serialize_struct(&'static str, usize) -> SerializeStruct,
+    serialize_struct_variant(&'static str, u32, &'static str, usize) -> SerializeStructVariant,
+  );
+}
@@ -0,0 +1,558 @@
+use super::as_varint;
+
+use crate::Varint;
+
+use core::fmt::Debug;
+use std::{string::String, vec, vec::Vec};
+
+use quickcheck_macros::quickcheck;
+use serde_1::{
+  Deserialize, Deserializer, Serialize, Serializer,
+  de::{
+    Visitor,
+    value::{BytesDeserializer, Error, SeqDeserializer},
+  },
+  forward_to_deserialize_any,
+  ser::{self, Impossible},
+};
+
+/// A binary serializer that supports just enough for `as_varint`: tuples of `u8`.
+struct Bytes(Vec<u8>);
+
+macro_rules! unsupported {
+  ($($method:ident($($ty:ty),*) -> $ok:ty),+$(,)?) => {
+    $(
+      fn $method(self, $(_: $ty),*) -> Result<$ok, Error> {
+        Err(ser::Error::custom(concat!(stringify!($method), " is not supported")))
+      }
+    )+
+  };
+}
+
+impl Serializer for &mut Bytes {
+  type Ok = ();
+  type Error = Error;
+  type SerializeSeq = Impossible<(), Error>;
+  type SerializeTuple = Self;
+  type SerializeTupleStruct = Impossible<(), Error>;
+  type SerializeTupleVariant = Impossible<(), Error>;
+  type SerializeMap = Impossible<(), Error>;
+  type SerializeStruct = Impossible<(), Error>;
+  type SerializeStructVariant = Impossible<(), Error>;
+
+  fn is_human_readable(&self) -> bool {
+    false
+  }
+
+  fn serialize_u8(self, v: u8) -> Result<(), Error> {
+    self.0.push(v);
+    Ok(())
+  }
+
+  fn serialize_tuple(self, _: usize) -> Result<Self, Error> {
+    Ok(self)
+  }
+
+  fn serialize_some<T: Serialize + ?Sized>(self, _: &T) -> Result<(), Error> {
+    Err(ser::Error::custom("serialize_some is not supported"))
+  }
+
+  fn serialize_newtype_struct<T: Serialize + ?Sized>(
+    self,
+    _: &'static str,
+    _: &T,
+  ) -> Result<(), Error> {
+    Err(ser::Error::custom(
+      "serialize_newtype_struct is not supported",
+    ))
+  }
+
+  fn serialize_newtype_variant<T: Serialize + ?Sized>(
+    self,
+    _: &'static str,
+    _: u32,
+    _: &'static str,
+    _: &T,
+  ) -> Result<(), Error> {
+    Err(ser::Error::custom(
+      "serialize_newtype_variant is not supported",
+    ))
+  }
+
+  unsupported!(
+    serialize_bool(bool) -> (),
+    serialize_i8(i8) -> (),
+    serialize_i16(i16) -> (),
+    serialize_i32(i32) -> (),
+    serialize_i64(i64) -> (),
+    serialize_u16(u16) -> (),
+    serialize_u32(u32) -> (),
+    serialize_u64(u64) -> (),
+    serialize_f32(f32) -> (),
+    serialize_f64(f64) -> (),
+    serialize_char(char) -> (),
+    serialize_str(&str) -> (),
+    serialize_bytes(&[u8]) -> (),
+    serialize_none() -> (),
+    serialize_unit() -> (),
+    serialize_unit_struct(&'static str) -> (),
+    serialize_unit_variant(&'static str, u32, &'static str) -> (),
+    serialize_seq(Option<usize>) -> Self::SerializeSeq,
+    serialize_tuple_struct(&'static str, usize) -> Self::SerializeTupleStruct,
+    serialize_tuple_variant(&'static str, u32, &'static str, usize) -> Self::SerializeTupleVariant,
+    serialize_map(Option<usize>) -> Self::SerializeMap,
+    serialize_struct(&'static str, usize) -> Self::SerializeStruct,
+    serialize_struct_variant(&'static str, u32, &'static str, usize) -> Self::SerializeStructVariant,
+  );
+}
+
+impl ser::SerializeTuple for &mut Bytes {
+  type Ok = ();
+  type Error = Error;
+
+  fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+    value.serialize(&mut **self)
+  }
+
+  fn end(self) -> Result<(), Error> {
+    Ok(())
+  }
+}
+
+/// Makes a value deserializer binary.
+struct Binary<D>(D);
+
+impl<'de, D: Deserializer<'de, Error = Error>> Deserializer<'de> for Binary<D> {
+  type Error = Error;
+
+  fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+    self.0.deserialize_any(visitor)
+  }
+
+  fn is_human_readable(&self) -> bool {
+    false
+  }
+
+  forward_to_deserialize_any! {
+    bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+    bytes byte_buf option unit unit_struct newtype_struct seq tuple
+    tuple_struct map struct enum identifier ignored_any
+  }
+}
+
+fn to_bytes<T: Varint + Serialize + ?Sized>(value: &T) -> Result<Vec<u8>, Error> {
+  let mut bytes = Bytes(Vec::new());
+  as_varint::serialize(value, &mut bytes)?;
+  Ok(bytes.0)
+}
+
+fn from_bytes<T: Varint + for<'de> Deserialize<'de>>(bytes: &[u8]) -> Result<T, Error> {
+  as_varint::deserialize(Binary(SeqDeserializer::new(bytes.iter().copied())))
+}
+
+fn binary_roundtrip<T>(value: T) -> bool
+where
+  T: Varint + Serialize + for<'de> Deserialize<'de> + PartialEq + Debug,
+{
+  let mut buf = vec![0; value.encoded_len().get()];
+  value.encode(&mut buf).unwrap();
+
+  let bytes = to_bytes(&value).unwrap();
+  bytes == buf
+    && from_bytes::<T>(&bytes).unwrap() == value
+    && as_varint::deserialize::<T, _>(Binary(BytesDeserializer::<Error>::new(&bytes))).unwrap()
+      == value
+    && from_bytes::<T>(&bytes[..bytes.len() - 1]).is_err()
+}
+
+macro_rules! serde_roundtrip {
+  ($($name:ident: $ty:ty), +$(,)?) => {
+    $(
+      paste::paste! {
+        #[quickcheck]
+        fn [< binary_roundtrip_ $name >](value: $ty) -> bool {
+          binary_roundtrip(value)
+        }
+      }
+    )*
+  };
+}
+
+serde_roundtrip!(
+  u8: u8,
+  u64: u64,
+  i64: i64,
+  u128: u128,
+  i128: i128,
+  char: char,
+  option_u32: Option<u32>,
+  tuple_u16_i64: (u16, i64),
+  vec_u32: Vec<u32>,
+  string: String,
+);
+
+#[test]
+fn binary_is_the_varint_encoding() {
+  assert_eq!(to_bytes(&300u64).unwrap(), [0xAC, 0x02]);
+  assert_eq!(to_bytes(&-1i32).unwrap(), [0x01]);
+  assert_eq!(from_bytes::<u64>(&[0xAC, 0x02]).unwrap(), 300);
+}
+
+#[test]
+fn binary_rejects_invalid_encodings() {
+  assert!(from_bytes::<u8>(&[0xFF, 0xFF]).is_err());
+  assert!(from_bytes::<u64>(&[]).is_err());
+  assert!(from_bytes::<char>(&[0xFF, 0xFF, 0xFF, 0xFF, 0x0F]).is_err());
+  // Trailing bytes are left to the format, `SeqDeserializer` rejects them.
+  assert!(from_bytes::<u64>(&[0x01, 0x02]).is_err());
+}
+
+std::thread_local! {
+  static DECODES: core::cell::Cell<usize> = const { core::cell::Cell::new(0) };
+}
+
+/// A `u32` which counts the calls to its `decode`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(crate = "serde_1")]
+struct Counted(u32);
+
+impl Varint for Counted {
+  const MIN_ENCODED_LEN: core::num::NonZeroUsize = u32::MIN_ENCODED_LEN;
+  const MAX_ENCODED_LEN: core::num::NonZeroUsize = u32::MAX_ENCODED_LEN;
+
+  fn encoded_len(&self) -> core::num::NonZeroUsize {
+    self.0.encoded_len()
+  }
+
+  fn encode(&self, buf: &mut [u8]) -> Result<core::num::NonZeroUsize, crate::EncodeError> {
+    self.0.encode(buf)
+  }
+
+  fn decode(buf: &[u8]) -> Result<(core::num::NonZeroUsize, Self), crate::DecodeError> {
+    DECODES.with(|decodes| decodes.set(decodes.get() + 1));
+    u32::decode(buf).map(|(read, value)| (read, Self(value)))
+  }
+
+  fn required_len(buf: &[u8]) -> Result<core::num::NonZeroUsize, crate::DecodeError> {
+    u32::required_len(buf)
+  }
+}
+
+#[test]
+fn binary_decodes_each_element_once() {
+  let value: Vec<Counted> = (0..10_000).map(|i| Counted(i * 1000)).collect();
+  let bytes = to_bytes(&value).unwrap();
+
+  DECODES.with(|decodes| decodes.set(0));
+  assert_eq!(from_bytes::<Vec<Counted>>(&bytes).unwrap(), value);
+  assert_eq!(DECODES.with(|decodes| decodes.get()), value.len());
+}
+
+#[test]
+fn binary_overflow() {
+  // Stops at `MAX_ENCODED_LEN` instead of buffering the whole input.
+  let err = from_bytes::<u32>(&[0x80; 1024]).unwrap_err();
+  assert_eq!(err.to_string(), crate::DecodeError::Overflow.to_string());
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(crate = "serde_1")]
+struct Event {
+  #[serde(with = "as_varint")]
+  id: u64,
+  #[serde(with = "as_varint")]
+  offset: i32,
+  #[serde(with = "as_varint")]
+  tags: Vec<u16>,
+}
+
+#[test]
+fn human_readable_is_the_plain_value() {
+  let event = Event {
+    id: 300,
+    offset: -1,
+    tags: vec![1, 2],
+  };
+  let json = serde_json::to_string(&event).unwrap();
+  assert_eq!(json, r#"{"id":300,"offset":-1,"tags":[1,2]}"#);
+  assert_eq!(serde_json::from_str::<Event>(&json).unwrap(), event);
+}
+
+fn format_roundtrip<T>(value: T) -> bool
+where
+  T: Serialize + for<'de> Deserialize<'de> + PartialEq + Debug,
+{
+  let buf = super::to_vec(&value).unwrap();
+  let mut slice = vec![0; buf.len()];
+  let written = super::to_slice(&value, &mut slice).unwrap();
+
+  written == buf.len()
+    && slice == buf
+    && super::from_slice::<T>(&buf).unwrap() == (buf.len(), value)
+    && (buf.is_empty()
+      || super::from_slice::<T>(&buf[..buf.len() - 1]).is_err()
+        && super::to_slice(&value_of::<T>(&buf), &mut slice[..buf.len() - 1]).is_err())
+}
+
+fn value_of<T: for<'de> Deserialize<'de>>(buf: &[u8]) -> T {
+  super::from_slice(buf).unwrap().1
+}
+
+macro_rules! format_roundtrip {
+  ($($name:ident: $ty:ty), +$(,)?) => {
+    $(
+      paste::paste! {
+        #[quickcheck]
+        fn [< format_roundtrip_ $name >](value: $ty) -> bool {
+          format_roundtrip(value)
+        }
+      }
+    )*
+  };
+}
+
+format_roundtrip!(
+  bool: bool,
+  u8: u8,
+  i8: i8,
+  u16: u16,
+  i32: i32,
+  u64: u64,
+  i64: i64,
+  u128: u128,
+  i128: i128,
+  f32: u32,
+  char: char,
+  string: String,
+  option_u32: Option<u32>,
+  tuple_u16_i64_string: (u16, i64, String),
+  vec_i32: Vec<i32>,
+  vec_option_string: Vec<Option<String>>,
+  btree_map_u64_string: std::collections::BTreeMap<u64, String>,
+  duration: core::time::Duration,
+);
+
+#[quickcheck]
+fn format_roundtrip_floats(a: f32, b: f64) -> bool {
+  let buf = super::to_vec(&(a, b)).unwrap();
+  let (_, (da, db)) = super::from_slice::<(f32, f64)>(&buf).unwrap();
+  da.to_bits() == a.to_bits() && db.to_bits() == b.to_bits()
+}
+
+#[cfg(feature = "half_2")]
+#[quickcheck]
+fn format_roundtrip_f16(bits: u16) -> bool {
+  let value = half_2::f16::from_bits(bits);
+  let mut expected = [0u8; 3];
+  let len = value.encode(&mut expected).unwrap().get();
+
+  let buf = super::to_vec(&value).unwrap();
+  buf == expected[..len] && value_of::<half_2::f16>(&buf).to_bits() == bits
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(crate = "serde_1")]
+enum Message {
+  Ping,
+  Data(u64),
+  Move(i32, i32),
+  Rename { from: String, to: String },
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(crate = "serde_1")]
+struct Envelope {
+  id: u64,
+  timeout: core::time::Duration,
+  messages: Vec<Message>,
+  unit: (),
+  tag: Option<char>,
+}
+
+#[test]
+fn format_structs_and_enums() {
+  let envelope = Envelope {
+    id: 300,
+    timeout: core::time::Duration::new(5, 1),
+    messages: vec![
+      Message::Ping,
+      Message::Data(u64::MAX),
+      Message::Move(-1, 1),
+      Message::Rename {
+        from: "a".into(),
+        to: "bc".into(),
+      },
+    ],
+    unit: (),
+    tag: Some('λ'),
+  };
+
+  let buf = super::to_vec(&envelope).unwrap();
+  assert_eq!(
+    super::from_slice::<Envelope>(&buf).unwrap(),
+    (buf.len(), envelope)
+  );
+  assert!(super::from_slice::<Envelope>(&buf[..buf.len() - 1]).is_err());
+}
+
+#[test]
+fn format_layout() {
+  let mut buf = [0u8; 32];
+
+  let written = super::to_slice(&(300u64, -1i32, 0xFFu8, true), &mut buf).unwrap();
+  assert_eq!(&buf[..written], [0xAC, 0x02, 0x01, 0xFF, 1]);
+
+  let written = super::to_slice(&Message::Move(-1, 1), &mut buf).unwrap();
+  assert_eq!(&buf[..written], [2, 0x01, 0x02]);
+
+  let written = super::to_slice(&[1u16, 300][..], &mut buf).unwrap();
+  assert_eq!(&buf[..written], [2, 1, 0xAC, 0x02]);
+
+  let written = super::to_slice(&Some("hi"), &mut buf).unwrap();
+  assert_eq!(&buf[..written], [1, 2, b'h', b'i']);
+
+  // `Duration` gets its varint encoding rather than two fields.
+  let duration = core::time::Duration::new(1, 2);
+  let written = super::to_slice(&duration, &mut buf).unwrap();
+  let mut expected = [0u8; 32];
+  let len = crate::encode_duration_to(&duration, &mut expected).unwrap();
+  assert_eq!(&buf[..written], &expected[..len.get()]);
+
+  let written = super::to_slice(&u128::MAX, &mut buf).unwrap();
+  assert_eq!(written, u128::MAX_ENCODED_LEN.get());
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(crate = "serde_1")]
+struct Duration {
+  secs: u8,
+  millis: u16,
+}
+
+#[test]
+fn format_other_duration_structs_are_plain() {
+  let value = Duration {
+    secs: 0xFF,
+    millis: 300,
+  };
+  let buf = super::to_vec(&value).unwrap();
+  assert_eq!(buf, [0xFF, 0xAC, 0x02]);
+  assert_eq!(super::from_slice::<Duration>(&buf).unwrap(), (3, value));
+}
+
+#[test]
+fn format_duration_named_structs_are_plain() {
+  /// Has the name and fields of `core::time::Duration`, but not its ranges.
+  #[derive(Debug, PartialEq, Serialize, Deserialize)]
+  #[serde(crate = "serde_1")]
+  struct Duration {
+    secs: i64,
+    nanos: u32,
+  }
+
+  for (value, expected) in [
+    (Duration { secs: -1, nanos: 5 }, &[0x01, 0x05][..]),
+    (
+      Duration {
+        secs: 1,
+        nanos: 2_000_000_000,
+      },
+      &[0x02, 0x80, 0xA8, 0xD6, 0xB9, 0x07][..],
+    ),
+  ] {
+    let buf = super::to_vec(&value).unwrap();
+    assert_eq!(buf, expected);
+    assert_eq!(
+      super::from_slice::<Duration>(&buf).unwrap(),
+      (buf.len(), value)
+    );
+  }
+}
+
+#[test]
+fn format_duration_shaped_structs_share_its_encoding() {
+  /// Has the name, fields and field types of `core::time::Duration`.
+  #[derive(Debug, PartialEq, Serialize, Deserialize)]
+  #[serde(crate = "serde_1")]
+  struct Duration {
+    secs: u64,
+    nanos: u32,
+  }
+
+  let value = Duration {
+    secs: u64::MAX,
+    nanos: 999_999_999,
+  };
+  let buf = super::to_vec(&value).unwrap();
+  let duration = core::time::Duration::new(value.secs, value.nanos);
+  assert_eq!(buf, super::to_vec(&duration).unwrap());
+  assert_eq!(
+    super::from_slice::<Duration>(&buf).unwrap(),
+    (buf.len(), value)
+  );
+  assert_eq!(
+    super::from_slice::<core::time::Duration>(&buf).unwrap(),
+    (buf.len(), duration)
+  );
+
+  // A whole second or more in `nanos` has no `Duration` encoding.
+  let value = Duration {
+    secs: 1,
+    nanos: 1_000_000_000,
+  };
+  assert!(super::to_vec(&value).is_err());
+}
+
+#[test]
+fn format_borrows() {
+  let buf = super::to_vec(&("varing", &b"\x00\x01"[..])).unwrap();
+  let (read, (s, bytes)) = super::from_slice::<(&str, &[u8])>(&buf).unwrap();
+  assert_eq!(read, buf.len());
+  assert_eq!(s, "varing");
+  assert_eq!(bytes, [0, 1]);
+}
+
+#[test]
+fn format_errors() {
+  use super::Error as FormatError;
+  use crate::{DecodeError, EncodeError};
+
+  let mut buf = [0u8; 2];
+  assert!(matches!(
+    super::to_slice(&(1u8, 300u16), &mut buf),
+    Err(FormatError::Encode(EncodeError::InsufficientSpace(e))) if e.requested().get() == 3 && e.available() == 2
+  ));
+
+  assert!(matches!(
+    super::from_slice::<bool>(&[2]),
+    Err(FormatError::Decode(DecodeError::Other(_)))
+  ));
+  assert!(matches!(
+    super::from_slice::<Option<u8>>(&[2, 0]),
+    Err(FormatError::Decode(DecodeError::Other(_)))
+  ));
+  assert!(matches!(
+    super::from_slice::<String>(&[2, 0xC3, 0x28]),
+    Err(FormatError::Decode(DecodeError::Other(_)))
+  ));
+  assert!(matches!(
+    super::from_slice::<String>(&[5, b'a']),
+    Err(FormatError::Decode(DecodeError::InsufficientData(_)))
+  ));
+  assert!(super::from_slice::<Message>(&[4]).is_err());
+  assert!(super::from_slice::<core::time::Duration>(&[0xFF; 20]).is_err());
+  // Not self-describing.
+  assert!(super::from_slice::<serde_json::Value>(&[0]).is_err());
+  // Sequences need their length up front.
+  struct Unsized;
+  impl Serialize for Unsized {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+      serializer.collect_seq(core::iter::from_fn(|| None::<u8>).filter(|_| true))
+    }
+  }
+  assert!(super::to_vec(&Unsized).is_err());
+  assert_eq!(
+    super::to_vec(&format_args!("{}-{}", 1, "a")).unwrap(),
+    [3, b'1', b'-', b'a']
+  );
+}
@@ -0,0 +1,164 @@
+use core::num::NonZeroUsize;
+
+use super::{ConstDecodeError, ConstEncodeError, DecodeError, EncodeError, Varint};
+
+macro_rules! sleb128 {
+  ($($bits:literal), +$(,)?) => {
+    $(
+      paste::paste! {
+        #[doc = "Returns the encoded length of an `i" $bits "` value in signed LEB128 format."]
+        #[doc = "The returned value will be in range of [`Sleb128::<i" $bits ">::ENCODED_LEN_RANGE`]."]
+        #[inline]
+        pub const fn [< encoded_i $bits _sleb128_len >](value: [< i $bits >]) -> NonZeroUsize {
+          // The significant bits, plus the sign bit.
+          let bits = if value < 0 {
+            [< i $bits >]::BITS - value.leading_ones() + 1
+          } else {
+            [< i $bits >]::BITS - value.leading_zeros() + 1
+          };
+          // Safety: `bits` is at least 1, so the length is at least 1
+          unsafe { NonZeroUsize::new_unchecked(bits.div_ceil(7) as usize) }
+        }
+
+        #[doc = "Encodes an `i" $bits "` value in signed LEB128 format, and writes it to the buffer."]
+        ///
+        /// Unlike the zigzag encoding used by the [`Varint`] implementation of the bare integer,
+        /// this is the sign-extension based format used by DWARF and WebAssembly.
+        ///
+        /// Returns the number of bytes written. Nothing is written if the buffer is too small.
+        #[inline]
+        pub const fn [< encode_i $bits _sleb128_to >](mut value: [< i $bits >], buf: &mut [u8]) -> Result<NonZeroUsize, ConstEncodeError> {
+          let len = [< encoded_i $bits _sleb128_len >](value);
+          if buf.len() < len.get() {
+            return Err(ConstEncodeError::insufficient_space(len, buf.len()));
+          }
+
+          let last = len.get() - 1;
+          let mut i = 0;
+          while i < last {
+            buf[i] = (value as u8) | 0x80;
+            value >>= 7;
+            i += 1;
+          }
+          buf[last] = (value as u8) & 0x7F;
+          Ok(len)
+        }
+
+        #[doc = "Decodes an `i" $bits "` in signed LEB128 format from the buffer."]
+        ///
+        #[doc = "Returns [`ConstDecodeError::Overflow`] if the encoding is longer than [`Sleb128::<i" $bits ">::MAX_ENCODED_LEN`],"]
+        #[doc = "or if the value does not fit in an `i" $bits "`, i.e. the unused bits of the last byte are not"]
+        /// a sign extension of the value. Overlong encodings that fit are accepted.
+        ///
+        /// Returns the bytes read and the decoded value if successful.
+        pub const fn [< decode_i $bits _sleb128 >](buf: &[u8]) -> Result<(NonZeroUsize, [< i $bits >]), ConstDecodeError> {
+          const MAX_ENCODED_LEN: usize = Sleb128::<[< i $bits >]>::MAX_ENCODED_LEN.get();
+          // The number of value bits carried by the last byte of a maximum length encoding.
+          const LAST_BITS: u32 = [< i $bits >]::BITS - 7 * (MAX_ENCODED_LEN as u32 - 1);
+          // The bits of that last byte that must all equal its sign bit.
+          const SIGN_MASK: u8 = 0x7F & !((1 << (LAST_BITS - 1)) - 1);
+
+          let mut result: [< u $bits >] = 0;
+          let mut shift = 0;
+          let mut index = 0;
+
+          loop {
+            if index == MAX_ENCODED_LEN {
+              return Err(ConstDecodeError::Overflow);
+            }
+
+            if index >= buf.len() {
+              return Err(ConstDecodeError::insufficient_data(buf.len()));
+            }
+
+            let next = buf[index];
+            if index == MAX_ENCODED_LEN - 1 {
+              let sign = next & SIGN_MASK;
+              if next & 0x80 != 0 || (sign != 0 && sign != SIGN_MASK) {
+                return Err(ConstDecodeError::Overflow);
+              }
+            }
+
+            result |= ((next & 0x7F) as [< u $bits >]) << shift;
+            shift += 7;
+            if next & 0x80 == 0 {
+              if shift < [< u $bits >]::BITS && next & 0x40 != 0 {
+                result |= [< u $bits >]::MAX << shift;
+              }
+              break;
+            }
+            index += 1;
+          }
+
+          // Safety: +1 guaranteed to be non-zero
+          Ok((unsafe { NonZeroUsize::new_unchecked(index + 1) }, result as [< i $bits >]))
+        }
+
+        impl Varint for Sleb128<[< i $bits >]> {
+          const MIN_ENCODED_LEN: NonZeroUsize = [< encoded_i $bits _sleb128_len >](0);
+          const MAX_ENCODED_LEN: NonZeroUsize = [< encoded_i $bits _sleb128_len >]([< i $bits >]::MIN);
+
+          #[inline]
+          fn encoded_len(&self) -> NonZeroUsize {
+            [< encoded_i $bits _sleb128_len >](self.0)
+          }
+
+          #[inline]
+          fn encode(&self, buf: &mut [u8]) -> Result<NonZeroUsize, EncodeError> {
+            [< encode_i $bits _sleb128_to >](self.0, buf).map_err(Into::into)
+          }
+
+          #[inline]
+          fn decode(buf: &[u8]) -> Result<(NonZeroUsize, Self), DecodeError>
+          where
+            Self: Sized,
+          {
+            [< decode_i $bits _sleb128 >](buf)
+              .map(|(read, value)| (read, Self(value)))
+              .map_err(Into::into)
+          }
+        }
+
+        impl From<[< i $bits >]> for Sleb128<[< i $bits >]> {
+          #[inline]
+          fn from(value: [< i $bits >]) -> Self {
+            Self(value)
+          }
+        }
+
+        impl From<Sleb128<[< i $bits >]>> for [< i $bits >] {
+          #[inline]
+          fn from(value: Sleb128<[< i $bits >]>) -> Self {
+            value.0
+          }
+        }
+      }
+    )*
+  };
+}
+
+sleb128!(8, 16, 32, 64, 128);
+
+/// A signed integer encoded in signed LEB128 format.
+///
+/// The [`Varint`] implementations of the bare signed integers zigzag encode them, like
+/// protobuf `sint64`. This wrapper instead uses the sign-extension based signed LEB128
+/// format of DWARF and WebAssembly, where the sign bit of the last byte is extended to the
+/// full width of the value.
+///
+/// ## Example
+///
+/// ```rust
+/// use varing::{Varint, sleb128::Sleb128};
+///
+/// let mut buf = [0u8; 3];
+/// assert_eq!(Sleb128(-123456i32).encode(&mut buf).unwrap().get(), 3);
+/// assert_eq!(buf, [0xC0, 0xBB, 0x78]);
+/// assert_eq!(Sleb128::<i32>::decode(&buf).unwrap().1, Sleb128(-123456));
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[repr(transparent)]
+pub struct Sleb128<T>(pub T);
+
+#[cfg(all(test, feature = "std"))]
+mod tests;
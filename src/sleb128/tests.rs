@@ -0,0 +1,174 @@
+use super::*;
+
+use std::vec::Vec;
+
+use quickcheck_macros::quickcheck;
+
+fn leb128_signed(value: i64) -> Vec<u8> {
+  let mut buf = Vec::new();
+  leb128::write::signed(&mut buf, value).unwrap();
+  buf
+}
+
+macro_rules! sleb128_roundtrip {
+  ($($bits:literal), +$(,)?) => {
+    $(
+      paste::paste! {
+        #[quickcheck]
+        fn [< sleb128_i $bits _roundtrip >](value: [< i $bits >]) -> bool {
+          let mut buf = [0u8; Sleb128::<[< i $bits >]>::MAX_ENCODED_LEN.get()];
+          let len = [< encode_i $bits _sleb128_to >](value, &mut buf).unwrap();
+          len == [< encoded_i $bits _sleb128_len >](value)
+            && Sleb128::<[< i $bits >]>::ENCODED_LEN_RANGE.contains(&len)
+            && [< decode_i $bits _sleb128 >](&buf[..len.get()]) == Ok((len, value))
+            && Sleb128::<[< i $bits >]>::decode_canonical(&buf) == Ok((len, Sleb128(value)))
+        }
+
+        #[quickcheck]
+        fn [< sleb128_i $bits _matches_leb128 >](value: [< i $bits >]) -> bool {
+          let value = value as i64;
+          let expected = leb128_signed(value);
+          let mut buf = [0u8; Sleb128::<[< i $bits >]>::MAX_ENCODED_LEN.get()];
+          let len = [< encode_i $bits _sleb128_to >](value as [< i $bits >], &mut buf).unwrap().get();
+          buf[..len] == expected[..]
+        }
+
+        #[quickcheck]
+        fn [< sleb128_i $bits _decode_rejects_out_of_range >](value: i64) -> bool {
+          let encoded = leb128_signed(value);
+          let decoded = [< decode_i $bits _sleb128 >](&encoded);
+          match [< i $bits >]::try_from(value) {
+            Ok(value) => decoded == Ok((NonZeroUsize::new(encoded.len()).unwrap(), value)),
+            Err(_) => decoded == Err(ConstDecodeError::Overflow),
+          }
+        }
+      }
+    )*
+  };
+}
+
+sleb128_roundtrip!(8, 16, 32, 64);
+
+#[quickcheck]
+fn sleb128_i128_roundtrip(value: i128) -> bool {
+  let mut buf = [0u8; Sleb128::<i128>::MAX_ENCODED_LEN.get()];
+  let len = encode_i128_sleb128_to(value, &mut buf).unwrap();
+  len == encoded_i128_sleb128_len(value) && decode_i128_sleb128(&buf) == Ok((len, value))
+}
+
+#[quickcheck]
+fn sleb128_i128_matches_leb128(value: i64) -> bool {
+  let mut buf = [0u8; Sleb128::<i128>::MAX_ENCODED_LEN.get()];
+  let len = encode_i128_sleb128_to(value as i128, &mut buf)
+    .unwrap()
+    .get();
+  buf[..len] == leb128_signed(value)[..]
+}
+
+#[test]
+fn known_vectors() {
+  // From the DWARF specification.
+  for (value, expected) in [
+    (2i64, &[0x02][..]),
+    (-2, &[0x7E]),
+    (127, &[0xFF, 0x00]),
+    (-127, &[0x81, 0x7F]),
+    (128, &[0x80, 0x01]),
+    (-128, &[0x80, 0x7F]),
+    (129, &[0x81, 0x01]),
+    (-129, &[0xFF, 0x7E]),
+    (63, &[0x3F]),
+    (-64, &[0x40]),
+    (64, &[0xC0, 0x00]),
+  ] {
+    let mut buf = [0u8; 10];
+    let len = encode_i64_sleb128_to(value, &mut buf).unwrap().get();
+    assert_eq!(&buf[..len], expected, "{value}");
+    assert_eq!(decode_i64_sleb128(expected).unwrap().1, value);
+  }
+}
+
+#[test]
+fn max_encoded_len() {
+  assert_eq!(Sleb128::<i8>::MAX_ENCODED_LEN.get(), 2);
+  assert_eq!(Sleb128::<i16>::MAX_ENCODED_LEN.get(), 3);
+  assert_eq!(Sleb128::<i32>::MAX_ENCODED_LEN.get(), 5);
+  assert_eq!(Sleb128::<i64>::MAX_ENCODED_LEN.get(), 10);
+  assert_eq!(Sleb128::<i128>::MAX_ENCODED_LEN.get(), 19);
+  assert_eq!(Sleb128::<i64>::MIN_ENCODED_LEN.get(), 1);
+}
+
+#[test]
+fn overflow() {
+  // Too many bytes.
+  assert_eq!(
+    decode_i8_sleb128(&[0x80, 0x80, 0x00]),
+    Err(ConstDecodeError::Overflow)
+  );
+  assert_eq!(
+    decode_i64_sleb128(&[0xFF; 11]),
+    Err(ConstDecodeError::Overflow)
+  );
+  // The last byte does not sign-extend the value.
+  assert_eq!(
+    decode_i8_sleb128(&[0x80, 0x01]),
+    Err(ConstDecodeError::Overflow)
+  );
+  assert_eq!(
+    decode_i8_sleb128(&[0xFF, 0x7E]),
+    Err(ConstDecodeError::Overflow)
+  );
+  assert_eq!(
+    decode_i32_sleb128(&[0xFF, 0xFF, 0xFF, 0xFF, 0x17]),
+    Err(ConstDecodeError::Overflow)
+  );
+  // Maximum length encodings of in-range values.
+  assert_eq!(decode_i8_sleb128(&[0xFF, 0x7F]).unwrap().1, -1);
+  assert_eq!(decode_i8_sleb128(&[0x80, 0x7F]).unwrap().1, i8::MIN);
+  assert_eq!(decode_i8_sleb128(&[0xFF, 0x00]).unwrap().1, i8::MAX);
+  assert_eq!(
+    decode_i32_sleb128(&[0xFF, 0xFF, 0xFF, 0xFF, 0x07])
+      .unwrap()
+      .1,
+    i32::MAX
+  );
+  assert_eq!(
+    decode_i32_sleb128(&[0x80, 0x80, 0x80, 0x80, 0x78])
+      .unwrap()
+      .1,
+    i32::MIN
+  );
+}
+
+#[test]
+fn overlong() {
+  // Accepted by `decode`, rejected by `decode_canonical`.
+  let buf = [0xFF, 0x7F];
+  assert_eq!(decode_i64_sleb128(&buf).unwrap().1, -1);
+  assert_eq!(
+    Sleb128::<i64>::decode_canonical(&buf),
+    Err(DecodeError::NonCanonical)
+  );
+}
+
+#[test]
+fn insufficient() {
+  assert_eq!(
+    decode_i32_sleb128(&[0x80, 0x80]),
+    Err(ConstDecodeError::insufficient_data(2))
+  );
+  assert_eq!(
+    decode_i32_sleb128(&[]),
+    Err(ConstDecodeError::insufficient_data(0))
+  );
+
+  let mut buf = [0xAAu8; 2];
+  assert_eq!(
+    encode_i32_sleb128_to(-123456, &mut buf),
+    Err(ConstEncodeError::insufficient_space(
+      NonZeroUsize::new(3).unwrap(),
+      2
+    ))
+  );
+  assert_eq!(buf, [0xAA; 2]);
+}
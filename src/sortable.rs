@@ -0,0 +1,418 @@
+use core::num::NonZeroUsize;
+
+use super::{ConstDecodeError, ConstEncodeError, DecodeError, EncodeError, Varint};
+
+/// The largest `u64` encoded in a single byte.
+const U1_MAX: u64 = 240;
+/// The largest `u64` encoded in two bytes.
+const U2_MAX: u64 = 2287;
+/// The largest `u64` encoded in three bytes.
+const U3_MAX: u64 = 67823;
+
+/// The largest non-negative value encoded in a single byte by the signed format.
+const I1_MAX: u64 = 0x77;
+/// The first byte of a single byte non-negative value in the signed format.
+const I1_TAG: u8 = 0x80;
+/// The first byte of a non-negative value followed by `k` bytes is `I_TAG_BASE + k`.
+const I_TAG_BASE: u8 = 0xF7;
+
+/// Returns the encoded length of an `u64` value in order-preserving format.
+/// The returned value will be in range of [`Sortable::<u64>::ENCODED_LEN_RANGE`].
+#[inline]
+pub const fn encoded_u64_sortable_len(value: u64) -> NonZeroUsize {
+  let len = if value <= U1_MAX {
+    1
+  } else if value <= U2_MAX {
+    2
+  } else if value <= U3_MAX {
+    3
+  } else {
+    1 + be_len(value)
+  };
+  // Safety: `len` is at least 1
+  unsafe { NonZeroUsize::new_unchecked(len) }
+}
+
+/// Encodes an `u64` value in order-preserving format, and writes it to the buffer.
+///
+/// The format is the varint of SQLite4: the first byte either holds the value or tells how
+/// many bytes follow, and comparing encodings with `memcmp` orders them like the values.
+///
+/// | first byte `A0` | value                                      |
+/// | --------------- | ------------------------------------------ |
+/// | `0..=240`       | `A0`                                       |
+/// | `241..=248`     | `240 + 256 * (A0 - 241) + A1`              |
+/// | `249`           | `2288 + 256 * A1 + A2`                     |
+/// | `250..=255`     | the next `A0 - 247` bytes, big-endian      |
+///
+/// Returns the number of bytes written. Nothing is written if the buffer is too small.
+#[inline]
+pub const fn encode_u64_sortable_to(
+  value: u64,
+  buf: &mut [u8],
+) -> Result<NonZeroUsize, ConstEncodeError> {
+  let len = encoded_u64_sortable_len(value);
+  if buf.len() < len.get() {
+    return Err(ConstEncodeError::insufficient_space(len, buf.len()));
+  }
+
+  match len.get() {
+    1 => buf[0] = value as u8,
+    2 => {
+      let value = value - U1_MAX;
+      buf[0] = (value >> 8) as u8 + 241;
+      buf[1] = value as u8;
+    }
+    3 => {
+      let value = value - (U2_MAX + 1);
+      buf[0] = 249;
+      buf[1] = (value >> 8) as u8;
+      buf[2] = value as u8;
+    }
+    len => {
+      buf[0] = (len + 246) as u8;
+      write_be(value, len - 1, buf, 1);
+    }
+  }
+  Ok(len)
+}
+
+/// Decodes an `u64` in order-preserving format from the buffer.
+///
+/// The total length is known from the first byte, so if the buffer is too short the
+/// error carries it as [`InsufficientData::required`](crate::InsufficientData::required).
+/// Encodings longer than needed for their value are accepted, but do not sort correctly.
+///
+/// Returns the bytes read and the decoded value if successful.
+pub const fn decode_u64_sortable(buf: &[u8]) -> Result<(NonZeroUsize, u64), ConstDecodeError> {
+  if buf.is_empty() {
+    return Err(ConstDecodeError::insufficient_data(0));
+  }
+
+  let a0 = buf[0];
+  let len = match a0 {
+    0..=240 => 1,
+    241..=248 => 2,
+    249 => 3,
+    _ => a0 as usize - 246,
+  };
+  // Safety: `len` is at least 1
+  let len = unsafe { NonZeroUsize::new_unchecked(len) };
+  if buf.len() < len.get() {
+    return Err(ConstDecodeError::insufficient_data_with_required(
+      len,
+      buf.len(),
+    ));
+  }
+
+  let value = match a0 {
+    0..=240 => a0 as u64,
+    241..=248 => U1_MAX + 256 * (a0 as u64 - 241) + buf[1] as u64,
+    249 => U2_MAX + 1 + 256 * buf[1] as u64 + buf[2] as u64,
+    _ => read_be(buf, 1, len.get() - 1),
+  };
+  Ok((len, value))
+}
+
+/// Returns the encoded length of an `i64` value in order-preserving format.
+/// The returned value will be in range of [`Sortable::<i64>::ENCODED_LEN_RANGE`].
+#[inline]
+pub const fn encoded_i64_sortable_len(value: i64) -> NonZeroUsize {
+  let magnitude = if value < 0 { !value } else { value } as u64;
+  let len = if magnitude <= I1_MAX {
+    1
+  } else {
+    let mut k = 1;
+    while k < 8 && magnitude >= signed_base(k + 1) {
+      k += 1;
+    }
+    1 + k
+  };
+  // Safety: `len` is at least 1
+  unsafe { NonZeroUsize::new_unchecked(len) }
+}
+
+/// Encodes an `i64` value in order-preserving format, and writes it to the buffer.
+///
+/// Non-negative values start with a byte of `0x80` or more: `0x80..=0xF7` holds values
+/// up to `119`, and `0xF7 + k` is followed by `k` big-endian bytes holding the offset of the
+/// value from the smallest value with that length. A negative value `n` is encoded like
+/// `!n`, with every byte inverted, so its first byte is below `0x80` and comparing
+/// encodings with `memcmp` orders them like the values.
+///
+/// Returns the number of bytes written. Nothing is written if the buffer is too small.
+#[inline]
+pub const fn encode_i64_sortable_to(
+  value: i64,
+  buf: &mut [u8],
+) -> Result<NonZeroUsize, ConstEncodeError> {
+  let len = encoded_i64_sortable_len(value);
+  if buf.len() < len.get() {
+    return Err(ConstEncodeError::insufficient_space(len, buf.len()));
+  }
+
+  let negative = value < 0;
+  let magnitude = if negative { !value } else { value } as u64;
+  if len.get() == 1 {
+    buf[0] = I1_TAG + magnitude as u8;
+  } else {
+    let k = len.get() - 1;
+    buf[0] = I_TAG_BASE + k as u8;
+    write_be(magnitude - signed_base(k), k, buf, 1);
+  }
+
+  if negative {
+    let mut i = 0;
+    while i < len.get() {
+      buf[i] = !buf[i];
+      i += 1;
+    }
+  }
+  Ok(len)
+}
+
+/// Decodes an `i64` in order-preserving format from the buffer.
+///
+/// Returns the bytes read and the decoded value if successful.
+pub const fn decode_i64_sortable(buf: &[u8]) -> Result<(NonZeroUsize, i64), ConstDecodeError> {
+  if buf.is_empty() {
+    return Err(ConstDecodeError::insufficient_data(0));
+  }
+
+  let negative = buf[0] < I1_TAG;
+  // The byte mask that undoes the inversion of negative values.
+  let flip = if negative { 0xFF } else { 0 };
+  let a0 = buf[0] ^ flip;
+
+  let k = if a0 <= I_TAG_BASE {
+    0
+  } else {
+    (a0 - I_TAG_BASE) as usize
+  };
+  // Safety: +1 guaranteed to be non-zero
+  let len = unsafe { NonZeroUsize::new_unchecked(k + 1) };
+  if buf.len() < len.get() {
+    return Err(ConstDecodeError::insufficient_data_with_required(
+      len,
+      buf.len(),
+    ));
+  }
+
+  let magnitude = if k == 0 {
+    (a0 - I1_TAG) as u64
+  } else {
+    let mut offset = 0u64;
+    let mut i = 1;
+    while i <= k {
+      offset = (offset << 8) | (buf[i] ^ flip) as u64;
+      i += 1;
+    }
+    match signed_base(k).checked_add(offset) {
+      Some(magnitude) if magnitude <= i64::MAX as u64 => magnitude,
+      _ => return Err(ConstDecodeError::Overflow),
+    }
+  };
+
+  let magnitude = magnitude as i64;
+  Ok((len, if negative { !magnitude } else { magnitude }))
+}
+
+macro_rules! sortable {
+  ($(($u:ident, $i:ident)), +$(,)?) => {
+    $(
+      paste::paste! {
+        #[doc = "Returns the encoded length of an `" $u "` value in order-preserving format."]
+        #[doc = "The returned value will be in range of [`Sortable::<" $u ">::ENCODED_LEN_RANGE`]."]
+        #[inline]
+        pub const fn [< encoded_ $u _sortable_len >](value: $u) -> NonZeroUsize {
+          encoded_u64_sortable_len(value as u64)
+        }
+
+        #[doc = "Encodes an `" $u "` value in order-preserving format, and writes it to the buffer."]
+        ///
+        /// The encoding is the one of [`encode_u64_sortable_to`] for the same value.
+        #[inline]
+        pub const fn [< encode_ $u _sortable_to >](value: $u, buf: &mut [u8]) -> Result<NonZeroUsize, ConstEncodeError> {
+          encode_u64_sortable_to(value as u64, buf)
+        }
+
+        #[doc = "Decodes an `" $u "` in order-preserving format from the buffer."]
+        ///
+        /// Returns the bytes read and the decoded value if successful.
+        pub const fn [< decode_ $u _sortable >](buf: &[u8]) -> Result<(NonZeroUsize, $u), ConstDecodeError> {
+          match decode_u64_sortable(buf) {
+            Ok((read, value)) => {
+              if value > $u::MAX as u64 {
+                return Err(ConstDecodeError::Overflow);
+              }
+              Ok((read, value as $u))
+            }
+            Err(e) => Err(e),
+          }
+        }
+
+        #[doc = "Returns the encoded length of an `" $i "` value in order-preserving format."]
+        #[doc = "The returned value will be in range of [`Sortable::<" $i ">::ENCODED_LEN_RANGE`]."]
+        #[inline]
+        pub const fn [< encoded_ $i _sortable_len >](value: $i) -> NonZeroUsize {
+          encoded_i64_sortable_len(value as i64)
+        }
+
+        #[doc = "Encodes an `" $i "` value in order-preserving format, and writes it to the buffer."]
+        ///
+        /// The encoding is the one of [`encode_i64_sortable_to`] for the same value.
+        #[inline]
+        pub const fn [< encode_ $i _sortable_to >](value: $i, buf: &mut [u8]) -> Result<NonZeroUsize, ConstEncodeError> {
+          encode_i64_sortable_to(value as i64, buf)
+        }
+
+        #[doc = "Decodes an `" $i "` in order-preserving format from the buffer."]
+        ///
+        /// Returns the bytes read and the decoded value if successful.
+        pub const fn [< decode_ $i _sortable >](buf: &[u8]) -> Result<(NonZeroUsize, $i), ConstDecodeError> {
+          match decode_i64_sortable(buf) {
+            Ok((read, value)) => {
+              if value < $i::MIN as i64 || value > $i::MAX as i64 {
+                return Err(ConstDecodeError::Overflow);
+              }
+              Ok((read, value as $i))
+            }
+            Err(e) => Err(e),
+          }
+        }
+      }
+    )*
+  };
+}
+
+sortable!((u8, i8), (u16, i16), (u32, i32), (usize, isize));
+
+macro_rules! impl_sortable_varint {
+  ($($ty:ident), +$(,)?) => {
+    $(
+      paste::paste! {
+        impl Varint for Sortable<$ty> {
+          const MIN_ENCODED_LEN: NonZeroUsize = NonZeroUsize::MIN;
+          const MAX_ENCODED_LEN: NonZeroUsize = {
+            let min = [< encoded_ $ty _sortable_len >]($ty::MIN);
+            let max = [< encoded_ $ty _sortable_len >]($ty::MAX);
+            if min.get() > max.get() { min } else { max }
+          };
+
+          #[inline]
+          fn encoded_len(&self) -> NonZeroUsize {
+            [< encoded_ $ty _sortable_len >](self.0)
+          }
+
+          #[inline]
+          fn encode(&self, buf: &mut [u8]) -> Result<NonZeroUsize, EncodeError> {
+            [< encode_ $ty _sortable_to >](self.0, buf).map_err(Into::into)
+          }
+
+          #[inline]
+          fn decode(buf: &[u8]) -> Result<(NonZeroUsize, Self), DecodeError>
+          where
+            Self: Sized,
+          {
+            [< decode_ $ty _sortable >](buf)
+              .map(|(read, value)| (read, Self(value)))
+              .map_err(Into::into)
+          }
+        }
+
+        impl From<$ty> for Sortable<$ty> {
+          #[inline]
+          fn from(value: $ty) -> Self {
+            Self(value)
+          }
+        }
+
+        impl From<Sortable<$ty>> for $ty {
+          #[inline]
+          fn from(value: Sortable<$ty>) -> Self {
+            value.0
+          }
+        }
+      }
+    )*
+  };
+}
+
+impl_sortable_varint!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+/// An integer encoded in an order-preserving format, where comparing encodings byte by
+/// byte (`memcmp`) gives the same order as comparing the values.
+///
+/// This makes encoded values usable as keys of ordered byte stores such as LSM trees,
+/// which LEB128 is not: `128` encodes to `[0x80, 0x01]`, which sorts before the `[0x02]`
+/// of `2`. Unsigned integers use the varint format of SQLite4, see
+/// [`encode_u64_sortable_to`], and signed integers a sign-aware variant of it, see
+/// [`encode_i64_sortable_to`]. Both formats encode a value the same way whatever its
+/// width, so `usize` and `isize` are encoded like `u64` and `i64` on every target, and take
+/// at most 9 bytes. Their length tags only go up to eight bytes of payload, so `u128` and
+/// `i128` have no order-preserving encoding.
+///
+/// ## Example
+///
+/// ```rust
+/// use varing::{Varint, sortable::Sortable};
+///
+/// let mut a = [0u8; 9];
+/// let mut b = [0u8; 9];
+/// let a_len = Sortable(2u32).encode(&mut a).unwrap().get();
+/// let b_len = Sortable(300u32).encode(&mut b).unwrap().get();
+/// assert!(a[..a_len] < b[..b_len]);
+///
+/// let a_len = Sortable(-300i32).encode(&mut a).unwrap().get();
+/// let b_len = Sortable(-2i32).encode(&mut b).unwrap().get();
+/// assert!(a[..a_len] < b[..b_len]);
+/// assert_eq!(Sortable::<i32>::decode(&a).unwrap().1, Sortable(-300));
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[repr(transparent)]
+pub struct Sortable<T>(pub T);
+
+/// Returns the number of bytes needed to hold `value` in big-endian order, at least 3.
+#[inline]
+const fn be_len(value: u64) -> usize {
+  let len = (u64::BITS - value.leading_zeros()).div_ceil(8) as usize;
+  if len < 3 { 3 } else { len }
+}
+
+/// Writes the low `len` bytes of `value` in big-endian order to `buf[offset..]`.
+#[inline]
+const fn write_be(value: u64, len: usize, buf: &mut [u8], offset: usize) {
+  let mut i = 0;
+  while i < len {
+    buf[offset + i] = (value >> (8 * (len - 1 - i))) as u8;
+    i += 1;
+  }
+}
+
+/// Reads `len` bytes in big-endian order from `buf[offset..]`.
+#[inline]
+const fn read_be(buf: &[u8], offset: usize, len: usize) -> u64 {
+  let mut value = 0;
+  let mut i = 0;
+  while i < len {
+    value = (value << 8) | buf[offset + i] as u64;
+    i += 1;
+  }
+  value
+}
+
+/// Returns the smallest non-negative value encoded with `k` bytes after the first one by
+/// the signed format, for `k` in `1..=8`.
+#[inline]
+const fn signed_base(k: usize) -> u64 {
+  let mut base = I1_MAX + 1;
+  let mut i = 1;
+  while i < k {
+    base += 1 << (8 * i);
+    i += 1;
+  }
+  base
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests;
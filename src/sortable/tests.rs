@@ -0,0 +1,238 @@
+use super::*;
+
+use core::cmp::Ordering;
+
+use quickcheck_macros::quickcheck;
+
+/// Narrows `value` to a random bit width, so small encodings are exercised as often as
+/// long ones.
+macro_rules! narrow {
+  ($value:ident, $shift:ident, $ty:ident) => {
+    $value >> ($shift as u32 % $ty::BITS)
+  };
+}
+
+fn encode<T>(value: T) -> ([u8; 9], usize)
+where
+  Sortable<T>: Varint,
+{
+  let mut buf = [0u8; 9];
+  let len = Sortable(value).encode(&mut buf).unwrap().get();
+  (buf, len)
+}
+
+fn cmp_encoded<T>(a: T, b: T) -> Ordering
+where
+  Sortable<T>: Varint,
+{
+  let (a, a_len) = encode(a);
+  let (b, b_len) = encode(b);
+  a[..a_len].cmp(&b[..b_len])
+}
+
+/// Values around the length boundaries of both formats.
+const BOUNDARIES: [i128; 26] = [
+  0,
+  1,
+  119,
+  120,
+  240,
+  241,
+  375,
+  376,
+  2287,
+  2288,
+  65911,
+  65912,
+  67823,
+  67824,
+  (1 << 24) - 1,
+  1 << 24,
+  (1 << 32) - 1,
+  1 << 32,
+  (1 << 56) - 1,
+  1 << 56,
+  -1,
+  -120,
+  -121,
+  -376,
+  -377,
+  -65913,
+];
+
+macro_rules! sortable_properties {
+  ($($ty:ident), +$(,)?) => {
+    $(
+      paste::paste! {
+        #[quickcheck]
+        fn [< sortable_ $ty _roundtrip >](value: $ty, shift: u8) -> bool {
+          let value = narrow!(value, shift, $ty);
+          let mut buf = [0u8; Sortable::<$ty>::MAX_ENCODED_LEN.get()];
+          let len = [< encode_ $ty _sortable_to >](value, &mut buf).unwrap();
+          len == [< encoded_ $ty _sortable_len >](value)
+            && Sortable::<$ty>::ENCODED_LEN_RANGE.contains(&len)
+            && [< decode_ $ty _sortable >](&buf[..len.get()]) == Ok((len, value))
+            && Sortable::<$ty>::decode_canonical(&buf) == Ok((len, Sortable(value)))
+            && (0..len.get()).all(|available| [< decode_ $ty _sortable >](&buf[..available]).is_err())
+        }
+
+        #[quickcheck]
+        fn [< sortable_ $ty _preserves_order >](a: $ty, a_shift: u8, b: $ty, b_shift: u8) -> bool {
+          let a = narrow!(a, a_shift, $ty);
+          let b = narrow!(b, b_shift, $ty);
+          cmp_encoded(a, b) == a.cmp(&b)
+        }
+
+        #[test]
+        fn [< sortable_ $ty _preserves_order_at_boundaries >]() {
+          let mut values = BOUNDARIES
+            .iter()
+            .filter_map(|v| $ty::try_from(*v).ok())
+            .chain([$ty::MIN, $ty::MIN + 1, $ty::MAX - 1, $ty::MAX])
+            .collect::<std::vec::Vec<_>>();
+          values.sort();
+          for pair in values.windows(2) {
+            assert_eq!(cmp_encoded(pair[0], pair[1]), pair[0].cmp(&pair[1]), "{pair:?}");
+          }
+        }
+      }
+    )*
+  };
+}
+
+sortable_properties!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+#[test]
+fn sqlite4_vectors() {
+  for (value, expected) in [
+    (0u64, &[0x00][..]),
+    (240, &[0xF0]),
+    (241, &[0xF1, 0x01]),
+    (2287, &[0xF8, 0xFF]),
+    (2288, &[0xF9, 0x00, 0x00]),
+    (67823, &[0xF9, 0xFF, 0xFF]),
+    (67824, &[0xFA, 0x01, 0x08, 0xF0]),
+    ((1 << 24) - 1, &[0xFA, 0xFF, 0xFF, 0xFF]),
+    (1 << 24, &[0xFB, 0x01, 0x00, 0x00, 0x00]),
+    (u64::MAX, &[0xFF; 9]),
+  ] {
+    let (buf, len) = encode(value);
+    assert_eq!(&buf[..len], expected, "{value}");
+    assert_eq!(decode_u64_sortable(expected).unwrap().1, value);
+  }
+}
+
+#[test]
+fn signed_vectors() {
+  for (value, expected) in [
+    (0i64, &[0x80][..]),
+    (119, &[0xF7]),
+    (120, &[0xF8, 0x00]),
+    (375, &[0xF8, 0xFF]),
+    (376, &[0xF9, 0x00, 0x00]),
+    (-1, &[0x7F]),
+    (-120, &[0x08]),
+    (-121, &[0x07, 0xFF]),
+    (
+      i64::MAX,
+      &[0xFF, 0x7E, 0xFE, 0xFE, 0xFE, 0xFE, 0xFE, 0xFE, 0x87],
+    ),
+    (
+      i64::MIN,
+      &[0x00, 0x81, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x78],
+    ),
+  ] {
+    let (buf, len) = encode(value);
+    assert_eq!(&buf[..len], expected, "{value}");
+    assert_eq!(decode_i64_sortable(expected).unwrap().1, value);
+  }
+}
+
+#[test]
+fn max_encoded_len() {
+  assert_eq!(Sortable::<u8>::MAX_ENCODED_LEN.get(), 2);
+  assert_eq!(Sortable::<u32>::MAX_ENCODED_LEN.get(), 5);
+  assert_eq!(Sortable::<u64>::MAX_ENCODED_LEN.get(), 9);
+  assert_eq!(Sortable::<i8>::MAX_ENCODED_LEN.get(), 2);
+  assert_eq!(Sortable::<i64>::MAX_ENCODED_LEN.get(), 9);
+  assert_eq!(
+    Sortable::<usize>::MAX_ENCODED_LEN,
+    encoded_u64_sortable_len(usize::MAX as u64)
+  );
+  assert_eq!(
+    Sortable::<isize>::MAX_ENCODED_LEN,
+    encoded_i64_sortable_len(isize::MIN as i64)
+  );
+}
+
+#[test]
+fn pointer_sized_like_64_bit() {
+  for value in [0usize, 240, 2288, 1 << 24, usize::MAX] {
+    assert_eq!(encode(value), encode(value as u64));
+  }
+  for value in [0isize, -121, 376, isize::MIN, isize::MAX] {
+    assert_eq!(encode(value), encode(value as i64));
+  }
+}
+
+#[test]
+fn overflow() {
+  assert_eq!(
+    decode_u8_sortable(&[0xF1, 0x10]),
+    Err(ConstDecodeError::Overflow)
+  );
+  assert_eq!(decode_u8_sortable(&[0xF1, 0x0F]).unwrap().1, u8::MAX);
+  assert_eq!(
+    decode_i8_sortable(&[0xF8, 0x08]),
+    Err(ConstDecodeError::Overflow)
+  );
+  assert_eq!(
+    decode_i8_sortable(&[0x07, 0xF7]),
+    Err(ConstDecodeError::Overflow)
+  );
+  assert_eq!(
+    decode_i64_sortable(&[0xFF; 9]),
+    Err(ConstDecodeError::Overflow)
+  );
+  assert_eq!(
+    decode_i64_sortable(&[0x00; 9]),
+    Err(ConstDecodeError::Overflow)
+  );
+}
+
+#[test]
+fn overlong() {
+  // A value that fits in one byte, encoded with a 3-byte big-endian payload.
+  let buf = [0xFA, 0x00, 0x00, 0x05];
+  assert_eq!(decode_u64_sortable(&buf).unwrap().1, 5);
+  assert_eq!(
+    Sortable::<u64>::decode_canonical(&buf),
+    Err(DecodeError::NonCanonical)
+  );
+}
+
+#[test]
+fn insufficient_data_reports_required() {
+  assert_eq!(
+    decode_u64_sortable(&[0xFB, 0x01]),
+    Err(ConstDecodeError::insufficient_data_with_required(
+      NonZeroUsize::new(5).unwrap(),
+      2
+    ))
+  );
+  assert_eq!(
+    decode_i64_sortable(&[0x07]),
+    Err(ConstDecodeError::insufficient_data_with_required(
+      NonZeroUsize::new(2).unwrap(),
+      1
+    ))
+  );
+  assert_eq!(
+    decode_i64_sortable(&[]),
+    Err(ConstDecodeError::insufficient_data(0))
+  );
+
+  let mut buf = [0xAAu8; 2];
+  assert!(encode_u64_sortable_to(2288, &mut buf).is_err());
+  assert_eq!(buf, [0xAA; 2]);
+}
@@ -0,0 +1,338 @@
+use core::num::NonZeroUsize;
+
+use super::{DecodeError, EncodeError};
+
+/// The number of values described by a control byte.
+const GROUP_SIZE: usize = 4;
+
+/// The data length of the four values described by each control byte.
+const LENGTHS: [u8; 256] = {
+  let mut table = [0; 256];
+  let mut control = 0;
+  while control < 256 {
+    let mut len = 0;
+    let mut i = 0;
+    while i < GROUP_SIZE {
+      len += ((control >> (2 * i)) & 0b11) as u8 + 1;
+      i += 1;
+    }
+    table[control] = len;
+    control += 1;
+  }
+  table
+};
+
+/// The `pshufb` masks spreading the data bytes of the four values described by each
+/// control byte to four little-endian `u32` lanes.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+static SHUFFLES: [[u8; 16]; 256] = {
+  let mut table = [[0; 16]; 256];
+  let mut control = 0;
+  while control < 256 {
+    let mut offset = 0;
+    let mut i = 0;
+    while i < GROUP_SIZE {
+      let len = ((control >> (2 * i)) & 0b11) as u8 + 1;
+      let mut b = 0;
+      while b < 4 {
+        // A set high bit zeroes the lane byte.
+        table[control][4 * i + b as usize] = if b < len { offset + b } else { 0x80 };
+        b += 1;
+      }
+      offset += len;
+      i += 1;
+    }
+    control += 1;
+  }
+  table
+};
+
+/// Returns the number of data bytes of `value`, in `1..=4`.
+#[inline]
+const fn value_len(value: u32) -> usize {
+  match value {
+    0..=0xFF => 1,
+    0x100..=0xFFFF => 2,
+    0x1_0000..=0xFF_FFFF => 3,
+    _ => 4,
+  }
+}
+
+/// Returns the encoded length of a sequence of `u32` values in Stream VByte format.
+#[inline]
+pub const fn encoded_u32_stream_vbyte_len(sequence: &[u32]) -> usize {
+  let mut total = sequence.len().div_ceil(GROUP_SIZE);
+  let mut i = 0;
+  while i < sequence.len() {
+    total += value_len(sequence[i]);
+    i += 1;
+  }
+  total
+}
+
+/// Encodes a sequence of `u32` values in Stream VByte format, and writes it to the buffer.
+///
+/// The encoding is a control stream followed by a data stream. Each control byte holds the
+/// byte length minus one of four values in two bits, starting from the least significant
+/// bits, and the data stream holds every value in little-endian order using only that many
+/// bytes. The tag bits of the values missing from the last control byte are zero. Keeping
+/// the lengths apart from the data lets [`decode_u32_stream_vbyte`] decode four values at a
+/// time with a single SIMD shuffle.
+///
+/// The number of values is not part of the encoding, it has to be known to decode the
+/// sequence, e.g. by storing it in front of the sequence.
+///
+/// Returns the number of bytes written. Nothing is written if the buffer is too small.
+///
+/// ## Example
+///
+/// ```rust
+/// use varing::stream_vbyte::{decode_u32_stream_vbyte, encode_u32_stream_vbyte};
+///
+/// let values = [1, 300, 70_000, u32::MAX, 7];
+/// let mut buf = [0u8; 16];
+/// let written = encode_u32_stream_vbyte(&values, &mut buf).unwrap();
+/// assert_eq!(written, 13);
+/// assert_eq!(buf[..2], [0b11_10_01_00, 0b00]);
+///
+/// let mut decoded = [0u32; 5];
+/// let read = decode_u32_stream_vbyte(&buf, &mut decoded).unwrap();
+/// assert_eq!(read, written);
+/// assert_eq!(decoded, values);
+/// ```
+pub fn encode_u32_stream_vbyte(sequence: &[u32], buf: &mut [u8]) -> Result<usize, EncodeError> {
+  let encoded_len = encoded_u32_stream_vbyte_len(sequence);
+  if buf.len() < encoded_len {
+    // Safety: the buffer is shorter than `encoded_len`, so it is non-zero
+    return Err(EncodeError::insufficient_space(
+      unsafe { NonZeroUsize::new_unchecked(encoded_len) },
+      buf.len(),
+    ));
+  }
+
+  let (control, data) = buf[..encoded_len].split_at_mut(sequence.len().div_ceil(GROUP_SIZE));
+  let mut offset = 0;
+  for (control, group) in control.iter_mut().zip(sequence.chunks(GROUP_SIZE)) {
+    *control = 0;
+    for (i, value) in group.iter().enumerate() {
+      let len = value_len(*value);
+      *control |= ((len - 1) as u8) << (2 * i);
+      data[offset..offset + len].copy_from_slice(&value.to_le_bytes()[..len]);
+      offset += len;
+    }
+  }
+  Ok(encoded_len)
+}
+
+/// Decodes a sequence of `u32` values in Stream VByte format from the buffer, filling `out`.
+///
+/// Exactly `out.len()` values are decoded, see [`encode_u32_stream_vbyte`] for the format.
+/// On `x86` and `x86_64`, groups of values are decoded with AVX2 or SSSE3 instructions
+/// when the CPU supports them. With the `std` feature, support is detected at runtime,
+/// otherwise only the target features enabled at compile time are used.
+///
+/// Returns the number of bytes read if successful. If the buffer is too short once the
+/// control stream has been read, the error carries the total encoded length as
+/// [`InsufficientData::required`](crate::InsufficientData::required).
+pub fn decode_u32_stream_vbyte(buf: &[u8], out: &mut [u32]) -> Result<usize, DecodeError> {
+  let control_len = out.len().div_ceil(GROUP_SIZE);
+  if buf.len() < control_len {
+    return Err(DecodeError::insufficient_data(buf.len()));
+  }
+
+  let (control, data) = buf.split_at(control_len);
+  let data_len = data_len(control, out.len())?;
+  let total = control_len + data_len;
+  if buf.len() < total {
+    // Safety: the buffer is shorter than `total`, so it is non-zero
+    return Err(DecodeError::insufficient_data_with_required(
+      unsafe { NonZeroUsize::new_unchecked(total) },
+      buf.len(),
+    ));
+  }
+
+  // The data stream is only bounded by the buffer, so trailing bytes let the SIMD paths,
+  // which load 16 bytes at a time, handle the last groups too.
+  #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+  let (groups, offset) = x86::decode(control, data, out);
+  #[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+  let (groups, offset) = (0, 0);
+
+  decode_scalar(control, data, out, groups, offset);
+  Ok(total)
+}
+
+/// Returns the data length described by the control stream of `count` values.
+#[inline]
+fn data_len(control: &[u8], count: usize) -> Result<usize, DecodeError> {
+  let full = count / GROUP_SIZE;
+  let mut len = control[..full]
+    .iter()
+    .map(|control| LENGTHS[*control as usize] as usize)
+    .sum::<usize>();
+
+  let tail = count % GROUP_SIZE;
+  if tail != 0 {
+    let control = control[full];
+    if control >> (2 * tail) != 0 {
+      return Err(DecodeError::other(
+        "stream vbyte control byte describes missing values",
+      ));
+    }
+    // The missing values would each take one byte.
+    len += LENGTHS[control as usize] as usize - (GROUP_SIZE - tail);
+  }
+  Ok(len)
+}
+
+/// Decodes the values from group `groups` and data offset `offset` on, one at a time.
+///
+/// The data stream must hold every remaining value.
+#[inline]
+fn decode_scalar(control: &[u8], data: &[u8], out: &mut [u32], groups: usize, mut offset: usize) {
+  for (idx, value) in out.iter_mut().enumerate().skip(groups * GROUP_SIZE) {
+    let len = ((control[idx / GROUP_SIZE] >> (2 * (idx % GROUP_SIZE))) & 0b11) as usize + 1;
+    let mut bytes = [0; 4];
+    bytes[..len].copy_from_slice(&data[offset..offset + len]);
+    *value = u32::from_le_bytes(bytes);
+    offset += len;
+  }
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod x86 {
+  #[cfg(target_arch = "x86")]
+  use core::arch::x86::*;
+  #[cfg(target_arch = "x86_64")]
+  use core::arch::x86_64::*;
+
+  use super::{GROUP_SIZE, LENGTHS, SHUFFLES};
+
+  /// Decodes as many full groups as possible with SIMD instructions, if supported.
+  ///
+  /// Returns the number of groups decoded and the data offset after them.
+  #[inline]
+  pub(super) fn decode(control: &[u8], data: &[u8], out: &mut [u32]) -> (usize, usize) {
+    let groups = out.len() / GROUP_SIZE;
+    if has_avx2() {
+      // Safety: AVX2 is supported
+      let (decoded, offset) = unsafe { decode_avx2(control, data, out, groups) };
+      // AVX2 decodes pairs of groups, finish with SSSE3, which AVX2 implies.
+      // Safety: SSSE3 is supported
+      let (rest, rest_offset) = unsafe {
+        decode_ssse3(
+          &control[decoded..],
+          &data[offset..],
+          &mut out[decoded * GROUP_SIZE..],
+          groups - decoded,
+        )
+      };
+      (decoded + rest, offset + rest_offset)
+    } else if has_ssse3() {
+      // Safety: SSSE3 is supported
+      unsafe { decode_ssse3(control, data, out, groups) }
+    } else {
+      (0, 0)
+    }
+  }
+
+  #[inline]
+  fn has_avx2() -> bool {
+    #[cfg(feature = "std")]
+    {
+      std::is_x86_feature_detected!("avx2")
+    }
+    #[cfg(not(feature = "std"))]
+    {
+      cfg!(target_feature = "avx2")
+    }
+  }
+
+  #[inline]
+  fn has_ssse3() -> bool {
+    #[cfg(feature = "std")]
+    {
+      std::is_x86_feature_detected!("ssse3")
+    }
+    #[cfg(not(feature = "std"))]
+    {
+      cfg!(target_feature = "ssse3")
+    }
+  }
+
+  /// Decodes up to `groups` full groups, four values per `pshufb`, while at least 16 data
+  /// bytes are left to load.
+  ///
+  /// # Safety
+  ///
+  /// The CPU must support SSSE3, and `control` and `out` must hold `groups` full groups.
+  #[target_feature(enable = "ssse3")]
+  pub(super) unsafe fn decode_ssse3(
+    control: &[u8],
+    data: &[u8],
+    out: &mut [u32],
+    groups: usize,
+  ) -> (usize, usize) {
+    debug_assert!(control.len() >= groups && out.len() >= groups * GROUP_SIZE);
+
+    let mut group = 0;
+    let mut offset = 0;
+    while group < groups && offset + 16 <= data.len() {
+      let c = control[group] as usize;
+      // Safety: `offset + 16 <= data.len()`, and `out` holds the whole group.
+      unsafe {
+        let bytes = _mm_loadu_si128(data.as_ptr().add(offset).cast());
+        let mask = _mm_loadu_si128(SHUFFLES[c].as_ptr().cast());
+        let values = _mm_shuffle_epi8(bytes, mask);
+        _mm_storeu_si128(out.as_mut_ptr().add(group * GROUP_SIZE).cast(), values);
+      }
+      offset += LENGTHS[c] as usize;
+      group += 1;
+    }
+    (group, offset)
+  }
+
+  /// Decodes up to `groups` full groups, two groups per `vpshufb`, while at least 32 data
+  /// bytes are left to load.
+  ///
+  /// # Safety
+  ///
+  /// The CPU must support AVX2, and `control` and `out` must hold `groups` full groups.
+  #[target_feature(enable = "avx2")]
+  pub(super) unsafe fn decode_avx2(
+    control: &[u8],
+    data: &[u8],
+    out: &mut [u32],
+    groups: usize,
+  ) -> (usize, usize) {
+    debug_assert!(control.len() >= groups && out.len() >= groups * GROUP_SIZE);
+
+    let mut group = 0;
+    let mut offset = 0;
+    while group + 2 <= groups && offset + 32 <= data.len() {
+      let lo = control[group] as usize;
+      let hi = control[group + 1] as usize;
+      let hi_offset = offset + LENGTHS[lo] as usize;
+      // Safety: both loads end within `offset + 32 <= data.len()`, as a group takes at most
+      // 16 bytes, and `out` holds both groups.
+      unsafe {
+        let bytes = _mm256_inserti128_si256::<1>(
+          _mm256_castsi128_si256(_mm_loadu_si128(data.as_ptr().add(offset).cast())),
+          _mm_loadu_si128(data.as_ptr().add(hi_offset).cast()),
+        );
+        let mask = _mm256_inserti128_si256::<1>(
+          _mm256_castsi128_si256(_mm_loadu_si128(SHUFFLES[lo].as_ptr().cast())),
+          _mm_loadu_si128(SHUFFLES[hi].as_ptr().cast()),
+        );
+        let values = _mm256_shuffle_epi8(bytes, mask);
+        _mm256_storeu_si256(out.as_mut_ptr().add(group * GROUP_SIZE).cast(), values);
+      }
+      offset = hi_offset + LENGTHS[hi] as usize;
+      group += 2;
+    }
+    (group, offset)
+  }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests;
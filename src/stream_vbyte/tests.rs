@@ -0,0 +1,149 @@
+use super::*;
+
+use std::{vec, vec::Vec};
+
+use quickcheck_macros::quickcheck;
+
+/// Narrows every value to a random byte length, so all four lengths are exercised.
+fn narrow(values: Vec<(u32, u8)>) -> Vec<u32> {
+  values
+    .into_iter()
+    .map(|(value, shift)| value >> (8 * (shift % 4)))
+    .collect()
+}
+
+fn encode(values: &[u32]) -> Vec<u8> {
+  let mut buf = vec![0; encoded_u32_stream_vbyte_len(values)];
+  let written = encode_u32_stream_vbyte(values, &mut buf).unwrap();
+  assert_eq!(written, buf.len());
+  buf
+}
+
+/// Decodes with the scalar path only.
+fn decode_reference(buf: &[u8], count: usize) -> Vec<u32> {
+  let mut out = vec![0; count];
+  let (control, data) = buf.split_at(count.div_ceil(GROUP_SIZE));
+  decode_scalar(control, data, &mut out, 0, 0);
+  out
+}
+
+#[quickcheck]
+fn stream_vbyte_roundtrip(values: Vec<(u32, u8)>, trailing: Vec<u8>) -> bool {
+  let values = narrow(values);
+  let mut buf = encode(&values);
+  let len = buf.len();
+  // Trailing bytes let the SIMD paths handle the last groups too.
+  buf.extend_from_slice(&trailing);
+
+  let mut decoded = vec![0; values.len()];
+  decode_u32_stream_vbyte(&buf, &mut decoded) == Ok(len)
+    && decoded == values
+    && decode_reference(&buf, values.len()) == values
+}
+
+#[quickcheck]
+fn stream_vbyte_long_roundtrip(values: Vec<(u32, u8)>) -> bool {
+  // Long enough for the AVX2 and SSSE3 loops to run many times.
+  let values = narrow(values.repeat(8));
+  let buf = encode(&values);
+  let mut decoded = vec![0; values.len()];
+  decode_u32_stream_vbyte(&buf, &mut decoded) == Ok(buf.len()) && decoded == values
+}
+
+#[quickcheck]
+fn stream_vbyte_insufficient_data(values: Vec<(u32, u8)>) -> bool {
+  let values = narrow(values);
+  let buf = encode(&values);
+  let mut decoded = vec![0; values.len()];
+  (0..buf.len()).all(|available| {
+    matches!(
+      decode_u32_stream_vbyte(&buf[..available], &mut decoded),
+      Err(DecodeError::InsufficientData(_))
+    ) && encode_u32_stream_vbyte(&values, &mut vec![0; available]).is_err()
+  })
+}
+
+#[test]
+fn known_vectors() {
+  let buf = encode(&[0xAA, 0xBBCC, 0xDDEEFF, 0x11223344, 0x01]);
+  assert_eq!(
+    buf,
+    [
+      0b11_10_01_00,
+      0b00,
+      0xAA,
+      0xCC,
+      0xBB,
+      0xFF,
+      0xEE,
+      0xDD,
+      0x44,
+      0x33,
+      0x22,
+      0x11,
+      0x01
+    ]
+  );
+  assert_eq!(encode(&[]), [0u8; 0]);
+  assert_eq!(encoded_u32_stream_vbyte_len(&[0; 5]), 7);
+}
+
+#[test]
+fn insufficient_data_reports_required() {
+  let buf = encode(&[0xFFFF; 8]);
+  assert_eq!(buf.len(), 18);
+
+  let mut decoded = [0u32; 8];
+  assert_eq!(
+    decode_u32_stream_vbyte(&buf[..1], &mut decoded),
+    Err(DecodeError::insufficient_data(1))
+  );
+  assert_eq!(
+    decode_u32_stream_vbyte(&buf[..10], &mut decoded),
+    Err(DecodeError::insufficient_data_with_required(
+      NonZeroUsize::new(18).unwrap(),
+      10
+    ))
+  );
+}
+
+#[test]
+fn missing_values_in_control() {
+  let mut decoded = [0u32; 1];
+  assert_eq!(
+    decode_u32_stream_vbyte(&[0b01_00, 0x01, 0x02, 0x03], &mut decoded),
+    Err(DecodeError::other(
+      "stream vbyte control byte describes missing values"
+    ))
+  );
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[quickcheck]
+fn simd_paths_match_scalar(values: Vec<(u32, u8)>) -> bool {
+  let values = narrow(values.repeat(8));
+  let buf = encode(&values);
+  let expected = decode_reference(&buf, values.len());
+  let (control, data) = buf.split_at(values.len().div_ceil(GROUP_SIZE));
+  let groups = values.len() / GROUP_SIZE;
+
+  let check = |(decoded, offset): (usize, usize), mut out: Vec<u32>| {
+    decode_scalar(control, data, &mut out, decoded, offset);
+    out == expected
+  };
+
+  let mut ok = true;
+  if std::is_x86_feature_detected!("ssse3") {
+    let mut out = vec![0; values.len()];
+    // Safety: SSSE3 is supported
+    let progress = unsafe { x86::decode_ssse3(control, data, &mut out, groups) };
+    ok &= check(progress, out);
+  }
+  if std::is_x86_feature_detected!("avx2") {
+    let mut out = vec![0; values.len()];
+    // Safety: AVX2 is supported
+    let progress = unsafe { x86::decode_avx2(control, data, &mut out, groups) };
+    ok &= check(progress, out);
+  }
+  ok
+}
@@ -0,0 +1,110 @@
+use core::{num::NonZeroUsize, time::Duration};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::{ConstDecodeError, ConstEncodeError, DecodeError, EncodeError, Varint, time_utils};
+
+/// Splits a `SystemTime` into whole seconds and subsecond nanoseconds since
+/// [`UNIX_EPOCH`], both negative for times before it.
+///
+/// Returns `None` if the seconds do not fit in an `i64`.
+#[inline]
+fn to_secs_and_subsec_nanos(time: &SystemTime) -> Option<(i64, i32)> {
+  match time.duration_since(UNIX_EPOCH) {
+    Ok(since) => Some((
+      i64::try_from(since.as_secs()).ok()?,
+      since.subsec_nanos() as i32,
+    )),
+    Err(e) => {
+      let before = e.duration();
+      Some((
+        -i64::try_from(before.as_secs()).ok()?,
+        -(before.subsec_nanos() as i32),
+      ))
+    }
+  }
+}
+
+const OUT_OF_RANGE: &str = "SystemTime is out of range";
+
+/// Returns the encoded length of the value in LEB128 variable length format.
+/// The returned value will be in range [`SystemTime::ENCODED_LEN_RANGE`].
+///
+/// A time too far from [`UNIX_EPOCH`] to be encoded reports [`SystemTime::MAX_ENCODED_LEN`].
+#[inline]
+pub fn encoded_system_time_len(time: &SystemTime) -> NonZeroUsize {
+  match to_secs_and_subsec_nanos(time) {
+    Some((secs, nanos)) => time_utils::encoded_secs_and_subsec_nanos_len(secs, nanos),
+    None => SystemTime::MAX_ENCODED_LEN,
+  }
+}
+
+/// Encodes a `SystemTime` value into LEB128 variable length format, and writes it to the buffer.
+///
+/// The time is stored as a signed offset from [`UNIX_EPOCH`], as zigzag seconds above
+/// zigzag subsecond nanoseconds, so times before 1970 work as well. Fails if the whole
+/// seconds of the offset do not fit in an `i64`.
+#[inline]
+pub fn encode_system_time_to(
+  time: &SystemTime,
+  buf: &mut [u8],
+) -> Result<NonZeroUsize, ConstEncodeError> {
+  match to_secs_and_subsec_nanos(time) {
+    Some((secs, nanos)) => time_utils::encode_secs_and_subsec_nanos_to(secs, nanos, buf),
+    None => Err(ConstEncodeError::other(OUT_OF_RANGE)),
+  }
+}
+
+/// Decodes a `SystemTime` in LEB128 encoded format from the buffer.
+///
+/// Returns the bytes read and the decoded value if successful.
+#[inline]
+pub fn decode_system_time(buf: &[u8]) -> Result<(NonZeroUsize, SystemTime), ConstDecodeError> {
+  let (bytes_read, secs, nanos) = time_utils::decode_secs_and_subsec_nanos(buf)?;
+  // Like the `Duration`s of `chrono` and `time`, a well-formed encoding has
+  // `|nanos| < 1_000_000_000` with the sign of `secs`. Anything else would alias a
+  // canonical encoding.
+  if nanos <= -1_000_000_000 || nanos >= 1_000_000_000 {
+    return Err(ConstDecodeError::other("nanos out of range"));
+  }
+  if (secs > 0 && nanos < 0) || (secs < 0 && nanos > 0) {
+    return Err(ConstDecodeError::other("non-canonical system time"));
+  }
+
+  let offset = Duration::new(secs.unsigned_abs(), nanos.unsigned_abs());
+  let time = if secs < 0 || nanos < 0 {
+    UNIX_EPOCH.checked_sub(offset)
+  } else {
+    UNIX_EPOCH.checked_add(offset)
+  };
+  match time {
+    Some(time) => Ok((bytes_read, time)),
+    // The platform's `SystemTime` covers a narrower range than the encoding.
+    None => Err(ConstDecodeError::other(OUT_OF_RANGE)),
+  }
+}
+
+impl Varint for SystemTime {
+  const MIN_ENCODED_LEN: NonZeroUsize = u128::MIN_ENCODED_LEN;
+  const MAX_ENCODED_LEN: NonZeroUsize = u128::MAX_ENCODED_LEN;
+
+  #[inline]
+  fn encoded_len(&self) -> NonZeroUsize {
+    encoded_system_time_len(self)
+  }
+
+  #[inline]
+  fn encode(&self, buf: &mut [u8]) -> Result<NonZeroUsize, EncodeError> {
+    encode_system_time_to(self, buf).map_err(Into::into)
+  }
+
+  #[inline]
+  fn decode(buf: &[u8]) -> Result<(NonZeroUsize, Self), DecodeError>
+  where
+    Self: Sized,
+  {
+    decode_system_time(buf).map_err(Into::into)
+  }
+}
+
+#[cfg(test)]
+mod tests;
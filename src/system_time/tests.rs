@@ -0,0 +1,71 @@
+use super::*;
+
+use quickcheck_macros::quickcheck;
+
+fn roundtrip(value: SystemTime) -> bool {
+  let mut buf = [0; <SystemTime>::MAX_ENCODED_LEN.get()];
+  let Ok(encoded_len) = value.encode(&mut buf) else {
+    return false;
+  };
+  if encoded_len != value.encoded_len() || !SystemTime::ENCODED_LEN_RANGE.contains(&encoded_len) {
+    return false;
+  }
+
+  matches!(<SystemTime>::decode(&buf), Ok((bytes_read, decoded)) if decoded == value && bytes_read == encoded_len)
+}
+
+#[quickcheck]
+fn encode_decode_system_time(secs: i32, nanos: u32) -> bool {
+  let offset = Duration::new(secs.unsigned_abs() as u64, nanos % 1_000_000_000);
+  let value = if secs < 0 {
+    UNIX_EPOCH - offset
+  } else {
+    UNIX_EPOCH + offset
+  };
+  roundtrip(value)
+}
+
+#[test]
+fn before_and_after_the_epoch() {
+  for value in [
+    UNIX_EPOCH,
+    SystemTime::now(),
+    UNIX_EPOCH + Duration::from_nanos(1),
+    UNIX_EPOCH - Duration::from_nanos(1),
+    UNIX_EPOCH - Duration::new(1, 500_000_000),
+    UNIX_EPOCH - Duration::from_secs(86_400 * 365 * 100),
+  ] {
+    assert!(roundtrip(value), "{value:?}");
+  }
+
+  // Half a second before the epoch is -0.5s, no whole seconds.
+  let mut buf = [0; 19];
+  let len = encode_system_time_to(&(UNIX_EPOCH - Duration::from_millis(500)), &mut buf).unwrap();
+  let merged = time_utils::secs_and_subsec_nanos_to_merged(0, -500_000_000);
+  assert_eq!(
+    &buf[..len.get()],
+    crate::encode_u128_varint(merged).as_slice()
+  );
+}
+
+#[test]
+fn decode_rejects_malformed_offsets() {
+  for (secs, nanos) in [(0, 1_000_000_000), (0, -1_000_000_000), (1, -1), (-1, 1)] {
+    let merged = time_utils::secs_and_subsec_nanos_to_merged(secs, nanos);
+    let encoded = crate::encode_u128_varint(merged);
+    assert!(decode_system_time(&encoded).is_err(), "{secs}, {nanos}");
+  }
+
+  let encoded = crate::encode_u128_varint(1 << 96);
+  assert!(decode_system_time(&encoded).is_err());
+}
+
+#[test]
+fn decode_extreme_offsets_without_panic() {
+  for secs in [i64::MAX, i64::MIN + 1] {
+    let merged = time_utils::secs_and_subsec_nanos_to_merged(secs, 0);
+    let encoded = crate::encode_u128_varint(merged);
+    let result = std::panic::catch_unwind(move || decode_system_time(&encoded));
+    assert!(result.is_ok());
+  }
+}
@@ -101,6 +101,116 @@ fn test_decode_overflow_error() {
   }
 }
 
+#[test]
+fn test_decode_canonical() {
+  // Overlong encodings of zero are accepted by the lenient decoder only.
+  for buf in [
+    &[0x80, 0x00][..],
+    &[0x80, 0x80, 0x00],
+    &[0x80, 0x80, 0x80, 0x80, 0x00],
+  ] {
+    assert_eq!(decode_u32_varint(buf).unwrap().1, 0);
+    assert_eq!(
+      decode_u32_varint_canonical(buf),
+      Err(ConstDecodeError::NonCanonical)
+    );
+    assert_eq!(u32::decode_canonical(buf), Err(DecodeError::NonCanonical));
+  }
+
+  // `1` padded to two bytes.
+  assert_eq!(
+    decode_u64_varint_canonical(&[0x81, 0x00]),
+    Err(ConstDecodeError::NonCanonical)
+  );
+  assert_eq!(
+    decode_i64_varint_canonical(&[0x81, 0x00]),
+    Err(ConstDecodeError::NonCanonical)
+  );
+  assert_eq!(
+    f64::decode_canonical(&[0x80, 0x00]),
+    Err(DecodeError::NonCanonical)
+  );
+  assert_eq!(
+    bool::decode_canonical(&[0x81, 0x00]),
+    Err(DecodeError::NonCanonical)
+  );
+
+  // Canonical encodings, including the single zero byte, are unaffected.
+  assert_eq!(decode_u8_varint_canonical(&[0x00]).unwrap().1, 0);
+  assert_eq!(
+    decode_u8_varint_canonical(&[0xFF, 0x01]).unwrap().1,
+    u8::MAX
+  );
+  assert_eq!(
+    decode_u64_varint_canonical(&[0xAC, 0x02, 0x00]).unwrap(),
+    (NonZeroUsize::new(2).unwrap(), 300)
+  );
+  assert_eq!(
+    decode_u64_varint_canonical(&encode_u64_varint(u64::MAX))
+      .unwrap()
+      .1,
+    u64::MAX
+  );
+
+  // Errors from the underlying decoder are passed through.
+  assert_eq!(
+    decode_u32_varint_canonical(&[0x80; 6]),
+    Err(ConstDecodeError::Overflow)
+  );
+  assert_eq!(
+    decode_u32_varint_canonical(&[0x80]),
+    Err(ConstDecodeError::insufficient_data(1))
+  );
+}
+
+#[test]
+fn test_size_varint_is_portable() {
+  for value in [0usize, 1, 127, 128, 300, u32::MAX as usize, usize::MAX] {
+    assert_eq!(
+      encode_usize_varint(value).as_ref(),
+      encode_u64_varint(value as u64).as_ref()
+    );
+    assert_eq!(
+      decode_usize_varint(&encode_usize_varint(value)).unwrap().1,
+      value
+    );
+  }
+
+  for value in [0isize, -1, 1, -64, 64, isize::MIN, isize::MAX] {
+    assert_eq!(
+      encode_isize_varint(value).as_ref(),
+      encode_i64_varint(value as i64).as_ref()
+    );
+    assert_eq!(
+      decode_isize_varint(&encode_isize_varint(value)).unwrap().1,
+      value
+    );
+  }
+
+  assert_eq!(
+    decode_usize_varint(&[0x80; 11]),
+    Err(ConstDecodeError::Overflow)
+  );
+}
+
+#[cfg(target_pointer_width = "32")]
+#[test]
+fn test_size_varint_overflow() {
+  let encoded = encode_u64_varint(u32::MAX as u64 + 1);
+  assert_eq!(
+    decode_usize_varint(&encoded),
+    Err(ConstDecodeError::Overflow)
+  );
+  assert_eq!(usize::decode(&encoded), Err(DecodeError::Overflow));
+
+  let encoded = encode_i64_varint(i32::MIN as i64 - 1);
+  assert_eq!(
+    decode_isize_varint(&encoded),
+    Err(ConstDecodeError::Overflow)
+  );
+  assert_eq!(isize::decode(&encoded), Err(DecodeError::Overflow));
+}
+
 // Helper function for zig-zag encoding and decoding
 fn test_zigzag_encode_decode<T>(value: T)
 where
@@ -565,3 +675,175 @@ fn encode_i16_sequence_to_empty_sequence_ok() {
   assert_eq!(encode_i16_sequence_to(&seq, &mut []).unwrap(), 0);
   assert_eq!(encode_i16_sequence_to(&seq, &mut [0xFFu8; 4]).unwrap(), 0);
 }
+
+#[test]
+fn sequence_with_len_stops_at_prefix() {
+  let values = [1u32, 300, 70_000];
+  for prefix in [LengthPrefix::Count, LengthPrefix::Bytes] {
+    let len = encoded_sequence_with_len_len(values.iter(), prefix);
+    let mut buf = [0xFFu8; 16];
+    let written = encode_sequence_with_len(values.iter(), prefix, &mut buf).unwrap();
+    assert_eq!(written, len);
+    assert_eq!(
+      buf[0] as usize,
+      match prefix {
+        LengthPrefix::Count => 3,
+        LengthPrefix::Bytes => len - 1,
+      }
+    );
+
+    let mut decoder = sequence_decoder_with_len::<u32>(&buf, prefix).unwrap();
+    assert_eq!(decoder.position(), 1);
+    assert!(decoder.by_ref().map(|res| res.unwrap().1).eq(values));
+    assert_eq!(decoder.position(), len);
+    assert!(decoder.next().is_none());
+
+    assert_eq!(
+      decode_sequence_with_len::<u32, ItemCount>(&buf, prefix).unwrap(),
+      (len, ItemCount(3))
+    );
+  }
+}
+
+#[test]
+fn sequence_with_len_empty() {
+  let values: [u64; 0] = [];
+  for prefix in [LengthPrefix::Count, LengthPrefix::Bytes] {
+    assert_eq!(encoded_sequence_with_len_len(values.iter(), prefix), 1);
+    let mut buf = [0xFFu8; 2];
+    assert_eq!(
+      encode_sequence_with_len(values.iter(), prefix, &mut buf).unwrap(),
+      1
+    );
+    assert_eq!(buf, [0, 0xFF]);
+    assert_eq!(
+      decode_sequence_with_len::<u64, ItemCount>(&buf, prefix).unwrap(),
+      (1, ItemCount(0))
+    );
+  }
+}
+
+#[test]
+fn sequence_with_len_insufficient_space_writes_nothing() {
+  let values = [1u32, 300, 70_000];
+  for prefix in [LengthPrefix::Count, LengthPrefix::Bytes] {
+    let len = encoded_sequence_with_len_len(values.iter(), prefix);
+    for available in 0..len {
+      let mut buf = [0xFFu8; 16];
+      let err = encode_sequence_with_len(values.iter(), prefix, &mut buf[..available]).unwrap_err();
+      assert!(matches!(err, EncodeError::InsufficientSpace(_)));
+      assert!(buf.iter().all(|&b| b == 0xFF));
+    }
+  }
+}
+
+#[test]
+fn sequence_with_len_truncated() {
+  let values = [1u32, 300, 70_000];
+  for prefix in [LengthPrefix::Count, LengthPrefix::Bytes] {
+    let mut buf = [0u8; 16];
+    let written = encode_sequence_with_len(values.iter(), prefix, &mut buf).unwrap();
+    for end in 0..written {
+      assert!(decode_sequence_with_len::<u32, ItemCount>(&buf[..end], prefix).is_err());
+    }
+  }
+
+  // A byte length prefix is checked against the buffer up front.
+  assert!(matches!(
+    sequence_decoder_with_len::<u32>(&[3, 1], LengthPrefix::Bytes),
+    Err(DecodeError::InsufficientData(_))
+  ));
+  // A count prefix fails on the first missing element, and then stops.
+  let mut decoder = sequence_decoder_with_len::<u32>(&[3, 1], LengthPrefix::Count).unwrap();
+  assert_eq!(decoder.next().unwrap().unwrap().1, 1);
+  assert!(decoder.next().unwrap().is_err());
+  assert!(decoder.next().is_none());
+}
+
+#[test]
+fn map_with_len_stops_at_prefix() {
+  let entries = [(1u32, 300u64), (70_000, 2), (3, u64::MAX)];
+  for prefix in [LengthPrefix::Count, LengthPrefix::Bytes] {
+    let len = encoded_map_with_len_len(entries.iter().map(|(k, v)| (k, v)), prefix);
+    let mut buf = [0xFFu8; 32];
+    let written =
+      encode_map_with_len(entries.iter().map(|(k, v)| (k, v)), prefix, &mut buf).unwrap();
+    assert_eq!(written, len);
+
+    let mut decoder = map_decoder_with_len::<u32, u64>(&buf, prefix).unwrap();
+    assert!(decoder.by_ref().map(|res| res.unwrap().1).eq(entries));
+    assert_eq!(decoder.position(), len);
+    assert!(decoder.next().is_none());
+
+    assert_eq!(
+      decode_map_with_len::<u32, u64, ItemCount>(&buf, prefix).unwrap(),
+      (len, ItemCount(3))
+    );
+    for end in 0..written {
+      assert!(decode_map_with_len::<u32, u64, ItemCount>(&buf[..end], prefix).is_err());
+    }
+  }
+}
+
+/// Checks `required_len` against `decode` on every prefix of `buf`.
+fn check_required_len<V: Varint + core::fmt::Debug>(buf: &[u8]) {
+  for end in 0..=buf.len() {
+    let prefix = &buf[..end];
+    match (V::decode(prefix), V::required_len(prefix)) {
+      (Ok((read, _)), Ok(required)) => assert_eq!(read, required, "{prefix:?}"),
+      (Err(DecodeError::InsufficientData(_)), Ok(required)) => {
+        assert!(required.get() > end, "{prefix:?}")
+      }
+      // A terminated varint whose value does not fit.
+      (Err(DecodeError::Overflow), Ok(required)) => assert!(required.get() <= end, "{prefix:?}"),
+      (Err(DecodeError::Overflow), Err(DecodeError::Overflow)) => {}
+      (decoded, required) => panic!("{prefix:?}: decode {decoded:?}, required_len {required:?}"),
+    }
+  }
+}
+
+macro_rules! required_len_matches_decode {
+  ($($ty:ident), +$(,)?) => {
+    paste::paste! {
+      $(
+        #[test]
+        fn [< required_len_matches_decode_ $ty >]() {
+          let mut buf = [0u8; 32];
+          for value in [<$ty>::MIN, <$ty>::MIN / 2, 0 as $ty, 1 as $ty, 300u16 as $ty, <$ty>::MAX / 3, <$ty>::MAX] {
+            let written = value.encode(&mut buf).unwrap();
+            check_required_len::<$ty>(&buf[..written.get()]);
+          }
+          check_required_len::<$ty>(&[0x80; 32]);
+          check_required_len::<$ty>(&[0xFF; 32]);
+          // Overlong zero padding.
+          check_required_len::<$ty>(&[0x80, 0x80, 0x00]);
+        }
+      )*
+    }
+  };
+}
+
+required_len_matches_decode!(
+  u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, usize, isize
+);
+
+#[test]
+fn required_len_matches_decode_floats() {
+  let mut buf = [0u8; 16];
+  for value in [0.0f64, -1.5, f64::MAX, f64::NAN] {
+    let written = value.encode(&mut buf).unwrap();
+    check_required_len::<f64>(&buf[..written.get()]);
+    let written = (value as f32).encode(&mut buf).unwrap();
+    check_required_len::<f32>(&buf[..written.get()]);
+  }
+  check_required_len::<f32>(&[0x80; 16]);
+  check_required_len::<f64>(&[0x80; 16]);
+}
+
+#[test]
+fn default_required_len_uses_decode() {
+  assert_eq!(char::required_len(&[]).unwrap().get(), 1);
+  assert_eq!(char::required_len(&[0xE9]).unwrap().get(), 2);
+  assert_eq!(char::required_len(&[0xE9, 0x01, 0xFF]).unwrap().get(), 2);
+  assert_eq!(char::required_len(&[0x80; 8]), Err(DecodeError::Overflow));
+}
@@ -4,6 +4,7 @@ use time_0_3::{Date, Duration, Month, PrimitiveDateTime, Time, UtcDateTime};
 
 use crate::{
   ConstDecodeError, ConstEncodeError, DecodeError, EncodeError, NON_ZERO_USIZE_ONE, Varint,
+  delta::Delta,
   time_utils::{self, DurationBuffer},
 };
 
@@ -310,6 +311,36 @@ impl_varint_for_time!(
   Date(DateBuffer::CAPACITY, NON_ZERO_USIZE_ONE).date
 );
 
+/// Delta encoded as nanoseconds since the Unix epoch.
+impl Delta for UtcDateTime {
+  type Repr = u128;
+
+  #[inline]
+  fn to_delta_repr(&self) -> Option<Self::Repr> {
+    Some(self.unix_timestamp_nanos() as u128)
+  }
+
+  #[inline]
+  fn from_delta_repr(repr: Self::Repr) -> Option<Self> {
+    UtcDateTime::from_unix_timestamp_nanos(repr as i128).ok()
+  }
+}
+
+/// Delta encoded as nanoseconds since the Unix epoch, as if the value were in UTC.
+impl Delta for PrimitiveDateTime {
+  type Repr = u128;
+
+  #[inline]
+  fn to_delta_repr(&self) -> Option<Self::Repr> {
+    self.as_utc().to_delta_repr()
+  }
+
+  #[inline]
+  fn from_delta_repr(repr: Self::Repr) -> Option<Self> {
+    UtcDateTime::from_delta_repr(repr).map(|dt| PrimitiveDateTime::new(dt.date(), dt.time()))
+  }
+}
+
 const fn u8_to_month(val: u8) -> Result<Month, ConstDecodeError> {
   Ok(match val {
     1 => Month::January,
@@ -169,3 +169,24 @@ fn decode_date_round_trip() {
   assert_eq!(decoded, value);
   assert_eq!(read.get(), enc.len());
 }
+
+#[quickcheck_macros::quickcheck]
+fn fuzzy_delta_datetime(values: Vec<Datetime>) -> bool {
+  use crate::delta::{decode_delta_sequence, encode_delta_sequence, encoded_delta_sequence_len};
+
+  let len = encoded_delta_sequence_len(values.iter()).unwrap();
+  let mut buf = vec![0; len];
+  encode_delta_sequence(values.iter(), &mut buf).unwrap() == len
+    && decode_delta_sequence::<Datetime, Vec<_>>(&buf).unwrap() == (len, values)
+}
+
+#[test]
+fn delta_utc_is_unix_nanos() {
+  let value = Utc::from_unix_timestamp_nanos(1_700_000_000_123_456_789).unwrap();
+  assert_eq!(value.to_delta_repr(), Some(1_700_000_000_123_456_789));
+  assert_eq!(
+    Utc::from_delta_repr((-1i128) as u128),
+    Some(Utc::UNIX_EPOCH - Duration::NANOSECOND)
+  );
+  assert_eq!(Utc::from_delta_repr(u128::MAX / 2), None);
+}
@@ -0,0 +1,4 @@
+#[cfg(feature = "tokio_1")]
+mod v1;
+#[cfg(feature = "tokio_1")]
+pub use v1::*;
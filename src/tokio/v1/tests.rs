@@ -0,0 +1,101 @@
+use super::*;
+
+use core::time::Duration;
+use std::vec::Vec;
+
+use tokio_1::io::{AsyncWriteExt, duplex};
+
+macro_rules! roundtrip {
+  ($($ty:ident = $values:expr), +$(,)?) => {
+    $(
+      paste::paste! {
+        #[tokio_1::test(crate = "tokio_1")]
+        async fn [< duplex_roundtrip_ $ty:snake >]() {
+          let values: Vec<$ty> = $values;
+          // A tiny duplex buffer forces both sides to suspend mid-value.
+          let (mut client, mut server) = duplex(3);
+
+          let expected = values.clone();
+          let writer = tokio_1::spawn(async move {
+            let mut total = 0;
+            for value in &expected {
+              let written = client.write_varint(value).await.unwrap();
+              assert_eq!(written, value.encoded_len());
+              total += written.get();
+            }
+            total
+          });
+
+          for value in &values {
+            assert_eq!(server.read_varint::<$ty>().await.unwrap(), *value);
+          }
+          writer.await.unwrap();
+        }
+      }
+    )*
+  };
+}
+
+roundtrip!(
+  u16 = std::vec![0, 1, 127, 128, 300, u16::MAX],
+  u64 = std::vec![0, 1, 127, 128, u32::MAX as u64, u64::MAX],
+  i64 = std::vec![0, -1, 1, i64::MIN, i64::MAX],
+  u128 = std::vec![0, u64::MAX as u128, u128::MAX],
+  Duration = std::vec![
+    Duration::ZERO,
+    Duration::new(42, 123_456_789),
+    Duration::MAX
+  ],
+);
+
+#[tokio_1::test(crate = "tokio_1")]
+async fn read_unexpected_eof() {
+  let (mut client, mut server) = duplex(16);
+  client.write_all(&[0x80, 0x80]).await.unwrap();
+  drop(client);
+
+  let err = server.read_varint::<u64>().await.unwrap_err();
+  assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+}
+
+#[tokio_1::test(crate = "tokio_1")]
+async fn read_overflow() {
+  let (mut client, mut server) = duplex(16);
+  client.write_all(&[0x80; 16]).await.unwrap();
+
+  let err = server.read_varint::<u32>().await.unwrap_err();
+  assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+}
+
+#[tokio_1::test(crate = "tokio_1")]
+async fn read_does_not_consume_past_value() {
+  let (mut client, mut server) = duplex(16);
+  client.write_varint(&300u32).await.unwrap();
+  client.write_varint(&7u8).await.unwrap();
+
+  assert_eq!(server.read_varint::<u32>().await.unwrap(), 300);
+  assert_eq!(server.read_varint::<u8>().await.unwrap(), 7);
+}
+
+#[tokio_1::test(crate = "tokio_1")]
+async fn read_cancelled_mid_value() {
+  use futures::FutureExt;
+
+  let (mut client, mut server) = duplex(16);
+  // 300 encodes to [0xAC, 0x02], only the first byte has arrived.
+  client.write_all(&[0xAC]).await.unwrap();
+  assert!(server.read_varint::<u32>().now_or_never().is_none());
+
+  // The dropped future took the first byte with it, the stream resumes mid-value.
+  client.write_all(&[0x02, 0x07]).await.unwrap();
+  assert_eq!(server.read_varint::<u32>().await.unwrap(), 2);
+  assert_eq!(server.read_varint::<u8>().await.unwrap(), 7);
+}
+
+#[tokio_1::test(crate = "tokio_1")]
+async fn write_zero() {
+  let mut buf = [0u8; 1];
+  let mut writer = std::io::Cursor::new(&mut buf[..]);
+  let err = writer.write_varint(&300u32).await.unwrap_err();
+  assert_eq!(err.kind(), io::ErrorKind::WriteZero);
+}
@@ -0,0 +1,4 @@
+#[cfg(feature = "tokio-util_0_7")]
+mod v07;
+#[cfg(feature = "tokio-util_0_7")]
+pub use v07::*;
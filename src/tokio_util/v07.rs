@@ -0,0 +1,255 @@
+use core::{marker::PhantomData, num::NonZeroUsize};
+use std::io;
+
+use bytes_1::{Buf, Bytes, BytesMut};
+use tokio_util_0_7::codec::{Decoder, Encoder};
+
+use crate::{
+  DecodeError, EncodeError, Varint,
+  bytes::encode_to_buf,
+  framing::{DEFAULT_MAX_FRAME_SIZE, FrameDecoder, encoded_length_delimited_len},
+};
+
+/// A [`tokio_util::codec`](tokio_util_0_7::codec) codec for frames prefixed with their
+/// length as a `u64` varint.
+///
+/// The wire format is the one of [`framing`](crate::framing). Frames larger than the
+/// [maximum frame size](VarintLengthCodec::max_frame_size) are rejected by both the
+/// encoder and the decoder. The decoder rejects them as soon as the length prefix has
+/// been received, so a peer cannot make it buffer more than the maximum frame size.
+///
+/// ## Example
+///
+/// ```rust
+/// use bytes_1::{Bytes, BytesMut};
+/// use tokio_util_0_7::codec::{Decoder, Encoder};
+/// use varing::tokio_util::VarintLengthCodec;
+///
+/// let mut codec = VarintLengthCodec::new();
+/// let mut buf = BytesMut::new();
+/// codec.encode(Bytes::from_static(b"hello"), &mut buf).unwrap();
+/// assert_eq!(&buf[..], b"\x05hello");
+///
+/// let frame = codec.decode(&mut buf).unwrap().unwrap();
+/// assert_eq!(&frame[..], b"hello");
+/// assert!(buf.is_empty());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct VarintLengthCodec {
+  decoder: FrameDecoder,
+}
+
+impl VarintLengthCodec {
+  /// Creates a codec accepting frames of at most [`DEFAULT_MAX_FRAME_SIZE`] bytes.
+  #[inline]
+  pub const fn new() -> Self {
+    Self {
+      decoder: FrameDecoder::new(),
+    }
+  }
+
+  /// Creates a codec accepting frames of at most `max_frame_size` bytes.
+  #[inline]
+  pub const fn with_max_frame_size(max_frame_size: usize) -> Self {
+    Self {
+      decoder: FrameDecoder::with_max_frame_size(max_frame_size),
+    }
+  }
+
+  /// Returns the maximum frame size, excluding the length prefix.
+  #[inline]
+  pub const fn max_frame_size(&self) -> usize {
+    self.decoder.max_frame_size()
+  }
+
+  /// Sets the maximum frame size, excluding the length prefix.
+  #[inline]
+  pub const fn set_max_frame_size(&mut self, max_frame_size: usize) -> &mut Self {
+    self.decoder.set_max_frame_size(max_frame_size);
+    self
+  }
+}
+
+impl Decoder for VarintLengthCodec {
+  type Item = BytesMut;
+  type Error = io::Error;
+
+  fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+    let (consumed, frame_len) = match self.decoder.decode(src) {
+      Ok((consumed, frame)) => (consumed.get(), frame.len()),
+      Err(DecodeError::InsufficientData(e)) => {
+        if let Some(required) = e.required() {
+          src.reserve(required.get().saturating_sub(src.len()));
+        }
+        return Ok(None);
+      }
+      Err(e) => return Err(e.into()),
+    };
+
+    src.advance(consumed - frame_len);
+    Ok(Some(src.split_to(frame_len)))
+  }
+}
+
+impl Encoder<Bytes> for VarintLengthCodec {
+  type Error = io::Error;
+
+  fn encode(&mut self, item: Bytes, dst: &mut BytesMut) -> Result<(), Self::Error> {
+    if item.len() > self.max_frame_size() {
+      return Err(EncodeError::other("frame length exceeds the maximum frame size").into());
+    }
+
+    dst.reserve(encoded_length_delimited_len(item.len()).get());
+    encode_to_buf(&(item.len() as u64), dst)?;
+    dst.extend_from_slice(&item);
+    Ok(())
+  }
+}
+
+/// A [`tokio_util::codec`](tokio_util_0_7::codec) codec for a stream of varint encoded values.
+///
+/// Values are written back to back, without any framing. The decoder gives up with an
+/// error once [`max_encoded_len`](VarintCodec::max_encoded_len) bytes are buffered without
+/// a complete value, which bounds the memory a peer can make it buffer. It defaults to
+/// [`Varint::MAX_ENCODED_LEN`], capped at [`DEFAULT_MAX_FRAME_SIZE`].
+///
+/// ## Example
+///
+/// ```rust
+/// use bytes_1::BytesMut;
+/// use tokio_util_0_7::codec::{Decoder, Encoder};
+/// use varing::tokio_util::VarintCodec;
+///
+/// let mut codec = VarintCodec::<i64>::new();
+/// let mut buf = BytesMut::new();
+/// codec.encode(-300, &mut buf).unwrap();
+/// codec.encode(1, &mut buf).unwrap();
+///
+/// assert_eq!(codec.decode(&mut buf).unwrap(), Some(-300));
+/// assert_eq!(codec.decode(&mut buf).unwrap(), Some(1));
+/// assert_eq!(codec.decode(&mut buf).unwrap(), None);
+/// ```
+pub struct VarintCodec<T> {
+  max_encoded_len: usize,
+  _m: PhantomData<fn() -> T>,
+}
+
+impl<T> core::fmt::Debug for VarintCodec<T> {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    f.debug_struct("VarintCodec")
+      .field("max_encoded_len", &self.max_encoded_len)
+      .finish()
+  }
+}
+
+impl<T> Clone for VarintCodec<T> {
+  #[inline]
+  fn clone(&self) -> Self {
+    *self
+  }
+}
+
+impl<T> Copy for VarintCodec<T> {}
+
+impl<T: Varint> Default for VarintCodec<T> {
+  #[inline]
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<T: Varint> VarintCodec<T> {
+  /// Creates a codec accepting values of at most [`Varint::MAX_ENCODED_LEN`] bytes, capped
+  /// at [`DEFAULT_MAX_FRAME_SIZE`].
+  #[inline]
+  pub const fn new() -> Self {
+    let max = T::MAX_ENCODED_LEN.get();
+    Self::with_max_encoded_len(if max < DEFAULT_MAX_FRAME_SIZE {
+      max
+    } else {
+      DEFAULT_MAX_FRAME_SIZE
+    })
+  }
+}
+
+impl<T> VarintCodec<T> {
+  /// Creates a codec accepting values of at most `max_encoded_len` encoded bytes.
+  #[inline]
+  pub const fn with_max_encoded_len(max_encoded_len: usize) -> Self {
+    Self {
+      max_encoded_len,
+      _m: PhantomData,
+    }
+  }
+
+  /// Returns the maximum encoded length of a value.
+  #[inline]
+  pub const fn max_encoded_len(&self) -> usize {
+    self.max_encoded_len
+  }
+
+  /// Sets the maximum encoded length of a value.
+  #[inline]
+  pub const fn set_max_encoded_len(&mut self, max_encoded_len: usize) -> &mut Self {
+    self.max_encoded_len = max_encoded_len;
+    self
+  }
+
+  #[inline]
+  fn check_len(&self, len: NonZeroUsize) -> Result<(), io::Error> {
+    if len.get() > self.max_encoded_len {
+      return Err(
+        DecodeError::other("encoded value length exceeds the maximum encoded length").into(),
+      );
+    }
+    Ok(())
+  }
+}
+
+impl<T: Varint> Decoder for VarintCodec<T> {
+  type Item = T;
+  type Error = io::Error;
+
+  fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+    match T::decode(src) {
+      Ok((read, value)) => {
+        if read.get() > src.len() {
+          return Err(crate::invalid_consumed_length().into());
+        }
+        self.check_len(read)?;
+        src.advance(read.get());
+        Ok(Some(value))
+      }
+      Err(DecodeError::InsufficientData(e)) => {
+        // One more byte is needed at least.
+        let required = e
+          .required()
+          .unwrap_or_else(|| NonZeroUsize::MIN.saturating_add(src.len()));
+        self.check_len(required)?;
+        src.reserve(required.get().saturating_sub(src.len()));
+        Ok(None)
+      }
+      Err(e) => Err(e.into()),
+    }
+  }
+}
+
+impl<T: Varint> Encoder<T> for VarintCodec<T> {
+  type Error = io::Error;
+
+  fn encode(&mut self, item: T, dst: &mut BytesMut) -> Result<(), Self::Error> {
+    let encoded_len = item.encoded_len();
+    if encoded_len.get() > self.max_encoded_len {
+      return Err(
+        EncodeError::other("encoded value length exceeds the maximum encoded length").into(),
+      );
+    }
+
+    dst.reserve(encoded_len.get());
+    encode_to_buf(&item, dst)?;
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,169 @@
+use super::*;
+
+use core::time::Duration;
+use std::vec::Vec;
+
+use futures::{SinkExt, StreamExt};
+use quickcheck_macros::quickcheck;
+use tokio_util_0_7::codec::Framed;
+
+/// Feeds `data` to the decoder one byte at a time, collecting every decoded item.
+fn decode_trickle<D: Decoder>(codec: &mut D, data: &[u8]) -> Result<Vec<D::Item>, D::Error> {
+  let mut src = BytesMut::new();
+  let mut items = Vec::new();
+  for byte in data {
+    src.extend_from_slice(&[*byte]);
+    while let Some(item) = codec.decode(&mut src)? {
+      items.push(item);
+    }
+  }
+  assert!(src.is_empty());
+  Ok(items)
+}
+
+#[quickcheck]
+fn length_codec_roundtrip(frames: Vec<Vec<u8>>) -> bool {
+  let mut codec = VarintLengthCodec::new();
+  let mut buf = BytesMut::new();
+  for frame in &frames {
+    codec.encode(Bytes::from(frame.clone()), &mut buf).unwrap();
+  }
+
+  let decoded = decode_trickle(&mut codec, &buf).unwrap();
+  decoded
+    .iter()
+    .map(|f| &f[..])
+    .eq(frames.iter().map(|f| &f[..]))
+}
+
+macro_rules! codec_roundtrip {
+  ($($ty:ty), +$(,)?) => {
+    $(
+      paste::paste! {
+        #[quickcheck]
+        fn [< varint_codec_roundtrip_ $ty:snake >](values: Vec<$ty>) -> bool {
+          let mut codec = VarintCodec::<$ty>::new();
+          let mut buf = BytesMut::new();
+          for value in &values {
+            codec.encode(*value, &mut buf).unwrap();
+          }
+
+          decode_trickle(&mut codec, &buf).unwrap() == values
+        }
+      }
+    )*
+  };
+}
+
+codec_roundtrip!(u8, u32, u64, u128, i16, i64, char, Duration);
+
+#[test]
+fn length_codec_reserves_whole_frame() {
+  let mut codec = VarintLengthCodec::new();
+  let mut src = BytesMut::from(&[0xAC, 0x02][..]);
+  assert!(codec.decode(&mut src).unwrap().is_none());
+  assert!(src.capacity() >= 302);
+}
+
+#[test]
+fn length_codec_max_frame_size() {
+  let mut codec = VarintLengthCodec::with_max_frame_size(4);
+  assert_eq!(codec.max_frame_size(), 4);
+
+  let mut dst = BytesMut::new();
+  let err = codec
+    .encode(Bytes::from_static(b"hello"), &mut dst)
+    .unwrap_err();
+  assert_eq!(err.kind(), io::ErrorKind::Other);
+  assert!(dst.is_empty());
+
+  // Rejected from the length prefix alone, before the payload is buffered.
+  let mut src = BytesMut::from(&[0x05][..]);
+  let err = codec.decode(&mut src).unwrap_err();
+  assert_eq!(err.kind(), io::ErrorKind::Other);
+
+  codec.set_max_frame_size(5);
+  codec
+    .encode(Bytes::from_static(b"hello"), &mut dst)
+    .unwrap();
+  assert_eq!(&codec.decode(&mut dst).unwrap().unwrap()[..], b"hello");
+}
+
+#[test]
+fn length_codec_invalid_prefix() {
+  let mut codec = VarintLengthCodec::new();
+  let mut src = BytesMut::from(&[0xFF; 11][..]);
+  let err = codec.decode(&mut src).unwrap_err();
+  assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn varint_codec_max_encoded_len() {
+  let mut codec = VarintCodec::<u64>::with_max_encoded_len(2);
+  assert_eq!(codec.max_encoded_len(), 2);
+
+  let mut src = BytesMut::from(&[0x80, 0x80][..]);
+  let err = codec.decode(&mut src).unwrap_err();
+  assert_eq!(err.kind(), io::ErrorKind::Other);
+
+  let mut dst = BytesMut::new();
+  assert!(codec.encode(1 << 14, &mut dst).is_err());
+  assert!(dst.is_empty());
+
+  codec.set_max_encoded_len(3);
+  codec.encode(1 << 14, &mut dst).unwrap();
+  assert_eq!(codec.decode(&mut dst).unwrap(), Some(1 << 14));
+}
+
+#[test]
+fn varint_codec_default_max() {
+  assert_eq!(
+    VarintCodec::<u64>::new().max_encoded_len(),
+    u64::MAX_ENCODED_LEN.get()
+  );
+
+  let mut codec = VarintCodec::<u32>::new();
+  let mut src = BytesMut::from(&[0x80; 6][..]);
+  let err = codec.decode(&mut src).unwrap_err();
+  assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+}
+
+#[tokio_1::test(crate = "tokio_1")]
+async fn framed_length_codec() {
+  let (client, server) = tokio_1::io::duplex(8);
+  let mut client = Framed::new(client, VarintLengthCodec::new());
+  let mut server = Framed::new(server, VarintLengthCodec::new());
+
+  let frames = [&b"hello"[..], &[], &[0xAA; 300]];
+  let writer = tokio_1::spawn(async move {
+    for frame in frames {
+      client.send(Bytes::from_static(frame)).await.unwrap();
+    }
+  });
+
+  for frame in frames {
+    assert_eq!(&server.next().await.unwrap().unwrap()[..], frame);
+  }
+  writer.await.unwrap();
+  assert!(server.next().await.is_none());
+}
+
+#[tokio_1::test(crate = "tokio_1")]
+async fn framed_varint_codec() {
+  let (client, server) = tokio_1::io::duplex(3);
+  let mut client = Framed::new(client, VarintCodec::<i64>::new());
+  let mut server = Framed::new(server, VarintCodec::<i64>::new());
+
+  let values = [0, -1, i64::MAX, i64::MIN, 300];
+  let writer = tokio_1::spawn(async move {
+    for value in values {
+      client.send(value).await.unwrap();
+    }
+  });
+
+  for value in values {
+    assert_eq!(server.next().await.unwrap().unwrap(), value);
+  }
+  writer.await.unwrap();
+  assert!(server.next().await.is_none());
+}
@@ -0,0 +1,432 @@
+//! Derive macro for [`varing::Varint`](https://docs.rs/varing/latest/varing/trait.Varint.html).
+//!
+//! This crate is not meant to be used directly, enable the `derive` feature of
+//! [`varing`](https://docs.rs/varing) instead.
+#![forbid(unsafe_code)]
+#![deny(missing_docs)]
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::{
+  Data, DeriveInput, Error, Expr, Fields, Ident, LitStr, Path, Result, Type, parse_macro_input,
+  spanned::Spanned,
+};
+
+/// Integer types accepted in `#[repr(...)]` as the discriminant type of an enum.
+const REPR_TYPES: &[&str] = &[
+  "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize",
+];
+
+/// Derives `Varint` for a struct or an enum.
+///
+/// - Structs encode their fields in declaration order, every field type must implement
+///   `Varint`. Structs without fields are rejected.
+/// - Enums encode the discriminant of the variant as a varint, followed by the fields of
+///   the variant (if any) in declaration order. The discriminant is encoded as the
+///   `#[repr(...)]` integer type of the enum, or `u32` if there is none, so negative
+///   discriminants require a signed `repr`.
+///
+/// `MIN_ENCODED_LEN` and `MAX_ENCODED_LEN` are computed at compile time from the field
+/// types and discriminants, and `required_len` scans the fields with their own
+/// `required_len` instead of decoding them.
+///
+/// Use `#[varing(crate = "path")]` if `varing` is not available as `::varing`,
+/// e.g. when it is re-exported by another crate.
+///
+/// ## Example
+///
+/// ```rust
+/// use varing::Varint;
+///
+/// #[derive(Debug, PartialEq, Varint)]
+/// struct Point {
+///   x: i32,
+///   y: i32,
+/// }
+///
+/// #[derive(Debug, PartialEq, Varint)]
+/// enum Shape {
+///   Empty,
+///   Circle(Point, u32),
+/// }
+///
+/// assert_eq!(Shape::MIN_ENCODED_LEN.get(), 1);
+/// assert_eq!(Shape::MAX_ENCODED_LEN.get(), 1 + 5 + 5 + 5);
+///
+/// let shape = Shape::Circle(Point { x: -1, y: 1 }, 300);
+/// let mut buf = [0u8; 16];
+/// let written = shape.encode(&mut buf).unwrap();
+/// assert_eq!(&buf[..written.get()], &[0x01, 0x01, 0x02, 0xAC, 0x02]);
+///
+/// let (read, decoded) = Shape::decode(&buf).unwrap();
+/// assert_eq!(read, written);
+/// assert_eq!(decoded, shape);
+/// ```
+#[proc_macro_derive(Varint, attributes(varing))]
+pub fn derive_varint(input: TokenStream) -> TokenStream {
+  let input = parse_macro_input!(input as DeriveInput);
+  expand(input)
+    .unwrap_or_else(Error::into_compile_error)
+    .into()
+}
+
+fn expand(input: DeriveInput) -> Result<TokenStream2> {
+  let krate = crate_path(&input)?;
+  match &input.data {
+    Data::Struct(data) => expand_struct(&input, &krate, &data.fields),
+    Data::Enum(data) => {
+      if data.variants.is_empty() {
+        return Err(Error::new(
+          input.ident.span(),
+          "`Varint` cannot be derived for enums without variants",
+        ));
+      }
+      expand_enum(&input, &krate, data.variants.iter().collect())
+    }
+    Data::Union(data) => Err(Error::new(
+      data.union_token.span(),
+      "`Varint` cannot be derived for unions",
+    )),
+  }
+}
+
+/// Parses `#[varing(crate = "...")]`, defaulting to `::varing`.
+fn crate_path(input: &DeriveInput) -> Result<Path> {
+  let mut krate = None;
+  for attr in input
+    .attrs
+    .iter()
+    .filter(|attr| attr.path().is_ident("varing"))
+  {
+    attr.parse_nested_meta(|meta| {
+      if meta.path.is_ident("crate") {
+        let path: LitStr = meta.value()?.parse()?;
+        krate = Some(path.parse()?);
+        Ok(())
+      } else {
+        Err(meta.error("unsupported `varing` attribute, expected `crate = \"...\"`"))
+      }
+    })?;
+  }
+  Ok(krate.unwrap_or_else(|| syn::parse_quote!(::varing)))
+}
+
+/// Returns the integer type from `#[repr(...)]`, defaulting to `u32`.
+fn repr_type(input: &DeriveInput) -> Result<Ident> {
+  let mut repr = None;
+  for attr in input
+    .attrs
+    .iter()
+    .filter(|attr| attr.path().is_ident("repr"))
+  {
+    attr.parse_nested_meta(|meta| {
+      if let Some(ident) = meta.path.get_ident() {
+        if REPR_TYPES.iter().any(|ty| ident == ty) {
+          repr = Some(ident.clone());
+        }
+      }
+      // Skip the arguments of `align(N)`/`packed(N)`.
+      if meta.input.peek(syn::token::Paren) {
+        let _ = meta.input.parse::<TokenStream2>();
+      }
+      Ok(())
+    })?;
+  }
+  Ok(repr.unwrap_or_else(|| Ident::new("u32", Span::call_site())))
+}
+
+/// Binding names and types of the fields, in declaration order.
+fn bindings(fields: &Fields) -> Vec<(Ident, &Type)> {
+  fields
+    .iter()
+    .enumerate()
+    .map(|(idx, field)| (format_ident!("__field{}", idx), &field.ty))
+    .collect()
+}
+
+/// The pattern destructuring `fields` into `bindings`, e.g. `{ a: __field0 }` or `(__field0)`.
+fn pattern(fields: &Fields, bindings: &[(Ident, &Type)]) -> TokenStream2 {
+  let names = bindings.iter().map(|(name, _)| name);
+  match fields {
+    Fields::Named(fields) => {
+      let members = fields.named.iter().map(|field| &field.ident);
+      quote!({ #(#members: #names),* })
+    }
+    Fields::Unnamed(_) => quote!((#(#names),*)),
+    Fields::Unit => quote!(),
+  }
+}
+
+/// Adds a `Varint` bound for every field type to the where clause.
+fn generics_with_bounds<'a>(
+  input: &DeriveInput,
+  krate: &Path,
+  field_types: impl Iterator<Item = &'a Type>,
+) -> syn::Generics {
+  let mut generics = input.generics.clone();
+  let where_clause = generics.make_where_clause();
+  for ty in field_types {
+    where_clause
+      .predicates
+      .push(syn::parse_quote!(#ty: #krate::Varint));
+  }
+  generics
+}
+
+/// Statements encoding each binding in order, advancing `offset`.
+fn encode_fields(krate: &Path, bindings: &[(Ident, &Type)]) -> TokenStream2 {
+  let names = bindings.iter().map(|(name, _)| name);
+  quote! {
+    #(let offset = #krate::__private::encode_field(#names, buf, offset)?;)*
+  }
+}
+
+/// Statements decoding each binding in order, advancing `offset`.
+fn decode_fields(krate: &Path, bindings: &[(Ident, &Type)]) -> TokenStream2 {
+  let decodes = bindings.iter().map(|(name, ty)| {
+    quote! {
+      let (offset, #name) = #krate::__private::decode_field::<#ty>(buf, offset)?;
+    }
+  });
+  quote!(#(#decodes)*)
+}
+
+/// Statements finding the offset past each binding in order, advancing `offset`.
+fn required_fields(krate: &Path, bindings: &[(Ident, &Type)]) -> TokenStream2 {
+  let tys = bindings.iter().map(|(_, ty)| ty);
+  quote! {
+    #(let offset = #krate::__private::required_field_len::<#tys>(buf, offset)?;)*
+  }
+}
+
+/// Sums the given associated length constant over the field types, saturating at `usize::MAX`.
+fn const_len(krate: &Path, bindings: &[(Ident, &Type)], len: &str) -> TokenStream2 {
+  let len = Ident::new(len, Span::call_site());
+  let tys = bindings.iter().map(|(_, ty)| ty);
+  quote!(0usize #(.saturating_add(<#tys as #krate::Varint>::#len.get()))*)
+}
+
+fn expand_struct(input: &DeriveInput, krate: &Path, fields: &Fields) -> Result<TokenStream2> {
+  if fields.is_empty() {
+    return Err(Error::new(
+      input.ident.span(),
+      "`Varint` cannot be derived for structs without fields",
+    ));
+  }
+
+  let name = &input.ident;
+  let bindings = bindings(fields);
+  let pattern = pattern(fields, &bindings);
+  let names = bindings.iter().map(|(name, _)| name);
+  let min = const_len(krate, &bindings, "MIN_ENCODED_LEN");
+  let max = const_len(krate, &bindings, "MAX_ENCODED_LEN");
+  let encode = encode_fields(krate, &bindings);
+  let decode = decode_fields(krate, &bindings);
+  let required = required_fields(krate, &bindings);
+
+  let generics = generics_with_bounds(input, krate, fields.iter().map(|field| &field.ty));
+  let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+  Ok(quote! {
+    impl #impl_generics #krate::Varint for #name #ty_generics #where_clause {
+      const MIN_ENCODED_LEN: ::core::num::NonZeroUsize = #krate::__private::non_zero(#min);
+      const MAX_ENCODED_LEN: ::core::num::NonZeroUsize = #krate::__private::non_zero(#max);
+
+      #[inline]
+      fn encoded_len(&self) -> ::core::num::NonZeroUsize {
+        let Self #pattern = self;
+        #krate::__private::non_zero(0usize #(+ #krate::Varint::encoded_len(#names).get())*)
+      }
+
+      fn encode(
+        &self,
+        buf: &mut [u8],
+      ) -> ::core::result::Result<::core::num::NonZeroUsize, #krate::EncodeError> {
+        #krate::__private::check_space(self, buf)?;
+        let offset = 0usize;
+        let Self #pattern = self;
+        #encode
+        ::core::result::Result::Ok(#krate::__private::non_zero(offset))
+      }
+
+      fn decode(
+        buf: &[u8],
+      ) -> ::core::result::Result<(::core::num::NonZeroUsize, Self), #krate::DecodeError>
+      where
+        Self: ::core::marker::Sized,
+      {
+        let offset = 0usize;
+        #decode
+        ::core::result::Result::Ok((#krate::__private::non_zero(offset), Self #pattern))
+      }
+
+      fn required_len(
+        buf: &[u8],
+      ) -> ::core::result::Result<::core::num::NonZeroUsize, #krate::DecodeError>
+      where
+        Self: ::core::marker::Sized,
+      {
+        let offset = 0usize;
+        #required
+        ::core::result::Result::Ok(#krate::__private::non_zero(offset))
+      }
+    }
+  })
+}
+
+fn expand_enum(
+  input: &DeriveInput,
+  krate: &Path,
+  variants: Vec<&syn::Variant>,
+) -> Result<TokenStream2> {
+  let name = &input.ident;
+  let repr = repr_type(input)?;
+  let tag_len = format_ident!("encoded_{}_varint_len", repr);
+  let unknown = LitStr::new(
+    &format!("unknown discriminant for enum `{name}`"),
+    Span::call_site(),
+  );
+
+  // Discriminants follow the language rules: explicit, or the previous one plus one.
+  let mut tags = Vec::with_capacity(variants.len());
+  let mut tag_consts = Vec::with_capacity(variants.len());
+  let mut prev: Option<Ident> = None;
+  for variant in &variants {
+    let tag = format_ident!("__TAG_{}", variant.ident);
+    let value: Expr = match (&variant.discriminant, &prev) {
+      (Some((_, expr)), _) => expr.clone(),
+      (None, Some(prev)) => syn::parse_quote!(#prev + 1),
+      (None, None) => syn::parse_quote!(0),
+    };
+    tag_consts.push(quote!(const #tag: #repr = #value;));
+    prev = Some(tag.clone());
+    tags.push(tag);
+  }
+
+  let mut mins = Vec::with_capacity(variants.len());
+  let mut maxs = Vec::with_capacity(variants.len());
+  let mut encoded_len_arms = Vec::with_capacity(variants.len());
+  let mut encode_arms = Vec::with_capacity(variants.len());
+  let mut decode_branches = Vec::with_capacity(variants.len());
+  let mut required_branches = Vec::with_capacity(variants.len());
+  for (variant, tag) in variants.iter().zip(&tags) {
+    let ident = &variant.ident;
+    let bindings = bindings(&variant.fields);
+    let pattern = pattern(&variant.fields, &bindings);
+    let names = bindings.iter().map(|(name, _)| name);
+    let tag_len = quote!(#krate::#tag_len(#tag).get());
+
+    let min = const_len(krate, &bindings, "MIN_ENCODED_LEN");
+    let max = const_len(krate, &bindings, "MAX_ENCODED_LEN");
+    mins.push(quote!(#tag_len.saturating_add(#min)));
+    maxs.push(quote!(#tag_len.saturating_add(#max)));
+
+    encoded_len_arms.push(quote! {
+      Self::#ident #pattern => #tag_len #(+ #krate::Varint::encoded_len(#names).get())*,
+    });
+
+    let encode = encode_fields(krate, &bindings);
+    encode_arms.push(quote! {
+      Self::#ident #pattern => {
+        let offset = #krate::__private::encode_field(&#tag, buf, offset)?;
+        #encode
+        offset
+      }
+    });
+
+    let decode = decode_fields(krate, &bindings);
+    decode_branches.push(quote! {
+      if tag == #tag {
+        #decode
+        return ::core::result::Result::Ok((#krate::__private::non_zero(offset), Self::#ident #pattern));
+      }
+    });
+
+    let required = required_fields(krate, &bindings);
+    required_branches.push(quote! {
+      if tag == #tag {
+        #required
+        return ::core::result::Result::Ok(#krate::__private::non_zero(offset));
+      }
+    });
+  }
+
+  let min = mins
+    .into_iter()
+    .reduce(|acc, len| quote!(#krate::__private::min(#acc, #len)))
+    .expect("enum has at least one variant");
+  let max = maxs
+    .into_iter()
+    .reduce(|acc, len| quote!(#krate::__private::max(#acc, #len)))
+    .expect("enum has at least one variant");
+
+  let generics = generics_with_bounds(
+    input,
+    krate,
+    variants
+      .iter()
+      .flat_map(|variant| variant.fields.iter().map(|field| &field.ty)),
+  );
+  let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+  Ok(quote! {
+    const _: () = {
+      #(
+        #[allow(non_upper_case_globals)]
+        #tag_consts
+      )*
+
+      impl #impl_generics #krate::Varint for #name #ty_generics #where_clause {
+        const MIN_ENCODED_LEN: ::core::num::NonZeroUsize = #krate::__private::non_zero(#min);
+        const MAX_ENCODED_LEN: ::core::num::NonZeroUsize = #krate::__private::non_zero(#max);
+
+        #[inline]
+        fn encoded_len(&self) -> ::core::num::NonZeroUsize {
+          #krate::__private::non_zero(match self {
+            #(#encoded_len_arms)*
+          })
+        }
+
+        fn encode(
+          &self,
+          buf: &mut [u8],
+        ) -> ::core::result::Result<::core::num::NonZeroUsize, #krate::EncodeError> {
+          #krate::__private::check_space(self, buf)?;
+          let offset = 0usize;
+          let offset = match self {
+            #(#encode_arms)*
+          };
+          ::core::result::Result::Ok(#krate::__private::non_zero(offset))
+        }
+
+        fn decode(
+          buf: &[u8],
+        ) -> ::core::result::Result<(::core::num::NonZeroUsize, Self), #krate::DecodeError>
+        where
+          Self: ::core::marker::Sized,
+        {
+          let (offset, tag) = #krate::__private::decode_field::<#repr>(buf, 0)?;
+          #(#decode_branches)*
+          ::core::result::Result::Err(#krate::DecodeError::other(#unknown))
+        }
+
+        fn required_len(
+          buf: &[u8],
+        ) -> ::core::result::Result<::core::num::NonZeroUsize, #krate::DecodeError>
+        where
+          Self: ::core::marker::Sized,
+        {
+          let offset = #krate::__private::required_field_len::<#repr>(buf, 0)?;
+          if offset > buf.len() {
+            return ::core::result::Result::Ok(#krate::__private::non_zero(offset));
+          }
+
+          let (offset, tag) = #krate::__private::decode_field::<#repr>(buf, 0)?;
+          #(#required_branches)*
+          ::core::result::Result::Err(#krate::DecodeError::other(#unknown))
+        }
+      }
+    };
+  })
+}